@@ -6,22 +6,29 @@
 
 use reedline::{
     default_emacs_keybindings, ColumnarMenu, DefaultCompleter, DefaultPrompt, EditCommand, Emacs,
-    KeyCode, KeyModifiers, Keybindings, MenuBuilder, Reedline, ReedlineEvent, ReedlineMenu, Signal,
+    KeyCode, KeyCombination, KeyModifiers, Keybindings, MenuBuilder, Reedline, ReedlineEvent,
+    ReedlineMenu, Signal,
 };
 use std::io;
 
 fn add_menu_keybindings(keybindings: &mut Keybindings) {
     keybindings.add_binding(
-        KeyModifiers::NONE,
-        KeyCode::Tab,
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Tab,
+        },
+        vec![],
         ReedlineEvent::UntilFound(vec![
             ReedlineEvent::Menu("completion_menu".to_string()),
             ReedlineEvent::MenuNext,
         ]),
     );
     keybindings.add_binding(
-        KeyModifiers::ALT,
-        KeyCode::Enter,
+        KeyCombination {
+            modifier: KeyModifiers::ALT,
+            key_code: KeyCode::Enter,
+        },
+        vec![],
         ReedlineEvent::Edit(vec![EditCommand::InsertNewline]),
     );
 }
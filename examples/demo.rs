@@ -95,7 +95,7 @@ fn main() -> reedline::Result<()> {
         vi_normal: Some(SetCursorStyle::SteadyBlock),
         helix_insert: Some(SetCursorStyle::BlinkingBar),
         helix_normal: Some(SetCursorStyle::SteadyBlock),
-        emacs: None,
+        ..Default::default()
     };
 
     let mut line_editor = Reedline::create()
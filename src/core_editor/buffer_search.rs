@@ -0,0 +1,83 @@
+use std::ops::Range;
+
+/// State of a search through the text of the current buffer
+///
+/// Kept after the search is finished so that the matches can still be visited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BufferSearch {
+    /// The string being searched for
+    pub term: String,
+    /// Direction of the search, also used when jumping to the next match
+    pub backward: bool,
+    /// Select the match instead of only moving the cursor to its start
+    pub select: bool,
+    /// Cursor position the incremental search starts from, restored when it gets cancelled
+    pub origin: usize,
+}
+
+impl BufferSearch {
+    pub fn new(backward: bool, select: bool, origin: usize) -> Self {
+        Self {
+            term: String::new(),
+            backward,
+            select,
+            origin,
+        }
+    }
+
+    /// Ranges of all matches of the search term in `buffer`
+    pub fn matches<'a>(&'a self, buffer: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        buffer
+            .match_indices(self.term.as_str())
+            .filter(|_| !self.term.is_empty())
+            .map(|(start, found)| start..start + found.len())
+    }
+
+    /// The first match starting at or after `pos`, or the last match starting before `pos` if
+    /// searching `backward`. Wraps around the ends of the buffer.
+    pub fn find(&self, buffer: &str, pos: usize, backward: bool) -> Option<Range<usize>> {
+        let mut matches = self.matches(buffer);
+        if backward {
+            let matches: Vec<_> = matches.collect();
+            matches
+                .iter()
+                .rfind(|found| found.start < pos)
+                .or_else(|| matches.last())
+                .cloned()
+        } else {
+            let first = matches.next()?;
+            if first.start >= pos {
+                return Some(first);
+            }
+            matches.find(|found| found.start >= pos).or(Some(first))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("foo bar foo", "foo", 0, false, Some(0..3))]
+    #[case("foo bar foo", "foo", 1, false, Some(8..11))]
+    #[case("foo bar foo", "foo", 9, false, Some(0..3))]
+    #[case("foo bar foo", "foo", 8, true, Some(0..3))]
+    #[case("foo bar foo", "foo", 0, true, Some(8..11))]
+    #[case("foo bar foo", "baz", 0, false, None)]
+    #[case("foo bar foo", "", 0, false, None)]
+    fn test_find(
+        #[case] buffer: &str,
+        #[case] term: &str,
+        #[case] pos: usize,
+        #[case] backward: bool,
+        #[case] expected: Option<Range<usize>>,
+    ) {
+        let mut search = BufferSearch::new(false, false, 0);
+        search.term = term.to_string();
+
+        assert_eq!(search.find(buffer, pos, backward), expected);
+    }
+}
@@ -52,6 +52,38 @@ impl Clipboard for LocalClipboard {
     }
 }
 
+/// Cut buffer of the [`Editor`](crate::Editor), remembering what an edit writes to it
+/// so that the text can also be passed to the registers and the kill ring
+pub(crate) struct CutBuffer {
+    clipboard: Box<dyn Clipboard>,
+    written: Option<(String, ClipboardMode)>,
+}
+
+impl CutBuffer {
+    pub(crate) fn new(clipboard: Box<dyn Clipboard>) -> Self {
+        Self {
+            clipboard,
+            written: None,
+        }
+    }
+
+    /// Take what was written since the last call
+    pub(crate) fn take_written(&mut self) -> Option<(String, ClipboardMode)> {
+        self.written.take()
+    }
+}
+
+impl Clipboard for CutBuffer {
+    fn set(&mut self, content: &str, mode: ClipboardMode) {
+        self.written = Some((content.to_string(), mode));
+        self.clipboard.set(content, mode);
+    }
+
+    fn get(&mut self) -> (String, ClipboardMode) {
+        self.clipboard.get()
+    }
+}
+
 /// Creates a local clipboard
 pub fn get_local_clipboard() -> Box<dyn Clipboard> {
     Box::new(LocalClipboard::new())
@@ -1,9 +1,53 @@
-use super::{edit_stack::EditStack, Clipboard, ClipboardMode, LineBuffer};
+use super::{
+    edit_stack::EditStack, kill_ring::KillRing, line_buffer::pair_delimiters, registers::Registers,
+    Clipboard, ClipboardMode, CutBuffer, LineBuffer,
+};
 #[cfg(feature = "system_clipboard")]
 use crate::core_editor::get_system_clipboard;
-use crate::enums::{EditType, UndoBehavior};
+use crate::enums::{EditType, SelectionMode, TextObject, UndoBehavior};
 use crate::{core_editor::get_local_clipboard, EditCommand};
-use std::ops::DerefMut;
+use std::{
+    cmp::Reverse,
+    ops::{Range, RangeInclusive},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A selection next to the primary one, which is made up of the selection anchor
+/// and the insertion point of the [`LineBuffer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Selection {
+    anchor: Option<usize>,
+    cursor: usize,
+}
+
+impl Selection {
+    fn start(&self) -> usize {
+        self.anchor
+            .map_or(self.cursor, |anchor| anchor.min(self.cursor))
+    }
+
+    fn end(&self) -> usize {
+        self.anchor
+            .map_or(self.cursor, |anchor| anchor.max(self.cursor))
+    }
+
+    /// The selection covering both, the same one if the two are just the same cursor
+    fn merge(&self, other: &Selection) -> Selection {
+        if self.anchor.is_none() && other.anchor.is_none() && self.cursor == other.cursor {
+            return *self;
+        }
+        Selection {
+            anchor: Some(self.start().min(other.start())),
+            cursor: self.end().max(other.end()),
+        }
+    }
+
+    fn shift(&mut self, delta: isize) {
+        let shift = |pos: usize| (pos as isize + delta).max(0) as usize;
+        self.anchor = self.anchor.map(shift);
+        self.cursor = shift(self.cursor);
+    }
+}
 
 /// Stateful editor executing changes to the underlying [`LineBuffer`]
 ///
@@ -11,24 +55,35 @@ use std::ops::DerefMut;
 /// the undo/redo history and has facilities for cut/copy/yank/paste
 pub struct Editor {
     line_buffer: LineBuffer,
-    cut_buffer: Box<dyn Clipboard>,
+    cut_buffer: CutBuffer,
     #[cfg(feature = "system_clipboard")]
     system_clipboard: Box<dyn Clipboard>,
     edit_stack: EditStack<LineBuffer>,
     last_undo_behavior: UndoBehavior,
     selection_anchor: Option<usize>,
+    selection_mode: SelectionMode,
+    secondary_selections: Vec<Selection>,
+    registers: Registers,
+    kill_ring: KillRing,
+    /// The selection anchor is an Emacs mark, which cursor moves don't clear
+    mark_active: bool,
 }
 
 impl Default for Editor {
     fn default() -> Self {
         Editor {
             line_buffer: LineBuffer::new(),
-            cut_buffer: get_local_clipboard(),
+            cut_buffer: CutBuffer::new(get_local_clipboard()),
             #[cfg(feature = "system_clipboard")]
             system_clipboard: get_system_clipboard(),
             edit_stack: EditStack::new(),
             last_undo_behavior: UndoBehavior::CreateUndoPoint,
             selection_anchor: None,
+            selection_mode: SelectionMode::Char,
+            secondary_selections: Vec::new(),
+            registers: Registers::default(),
+            kill_ring: KillRing::default(),
+            mark_active: false,
         }
     }
 }
@@ -43,10 +98,176 @@ impl Editor {
     /// [`UndoBehavior`] specifies how this change should be reflected on the undo stack.
     pub(crate) fn set_line_buffer(&mut self, line_buffer: LineBuffer, undo_behavior: UndoBehavior) {
         self.line_buffer = line_buffer;
+        self.clear_selections();
         self.update_undo_state(undo_behavior);
     }
 
+    /// Get the [`Registers`] holding cut and copied text
+    pub const fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Get mutable access to the [`Registers`]
+    pub fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
     pub(crate) fn run_edit_command(&mut self, command: &EditCommand) {
+        // Choosing a register must not affect the selection or the undo history
+        if let EditCommand::SelectRegister(name) = command {
+            self.registers.select(*name);
+            return;
+        }
+
+        let register = self.registers.take_selected();
+        // Only what this command writes to the cut buffer goes to the registers
+        self.cut_buffer.take_written();
+        // Without a region `KillRegion` cuts the word in front of the cursor
+        let backward_kill = is_backward_kill(command)
+            || (matches!(command, EditCommand::KillRegion) && !self.mark_active);
+        let mut killed = false;
+        if is_paste(command) {
+            match register {
+                Some(name) => self.paste_from_register(name, command),
+                None => self.run_on_selections(command),
+            }
+        } else {
+            self.run_on_selections(command);
+            // Store what the command cut or copied in the registers as well
+            if let Some((content, mode)) = self.cut_buffer.take_written() {
+                if !content.is_empty() {
+                    self.store_in_registers(register, &content, mode, is_copy(command));
+                    self.kill_ring.kill(&content, backward_kill);
+                    killed = true;
+                }
+            }
+        }
+        self.kill_ring.finish_edit(
+            killed,
+            matches!(command, EditCommand::Yank | EditCommand::YankPop),
+        );
+
+        let new_undo_behavior = match (command, command.edit_type()) {
+            (_, EditType::MoveCursor { .. }) => UndoBehavior::MoveCursor,
+            (EditCommand::InsertChar(c), EditType::EditText) => UndoBehavior::InsertCharacter(*c),
+            (EditCommand::Delete, EditType::EditText) => {
+                let deleted_char = self.edit_stack.current().grapheme_right().chars().next();
+                UndoBehavior::Delete(deleted_char)
+            }
+            (EditCommand::Backspace, EditType::EditText) => {
+                let deleted_char = self.edit_stack.current().grapheme_left().chars().next();
+                UndoBehavior::Backspace(deleted_char)
+            }
+            (_, EditType::UndoRedo) => UndoBehavior::UndoRedo,
+            (_, _) => UndoBehavior::CreateUndoPoint,
+        };
+
+        self.update_undo_state(new_undo_behavior);
+    }
+
+    fn run_on_selections(&mut self, command: &EditCommand) {
+        match command {
+            EditCommand::Undo | EditCommand::Redo | EditCommand::SelectAll => {
+                self.secondary_selections.clear();
+                self.apply_edit_command(command);
+            }
+            // These work on all selections by themselves
+            EditCommand::CopySelectionToNextLine
+            | EditCommand::SelectRegexMatches(_)
+            | EditCommand::KeepPrimarySelection => self.apply_edit_command(command),
+            _ if self.secondary_selections.is_empty() => self.apply_edit_command(command),
+            _ => self.apply_to_all_selections(command),
+        }
+    }
+
+    fn paste_from_register(&mut self, name: char, command: &EditCommand) {
+        let content = match name {
+            '"' => None,
+            #[cfg(feature = "system_clipboard")]
+            '+' => Some(self.system_clipboard.get()),
+            _ => Some(self.registers.get_with_mode(name).unwrap_or_default()),
+        };
+        match content {
+            Some((content, mode)) => {
+                let mut register = get_local_clipboard();
+                register.set(&content, mode);
+                let cut_buffer = std::mem::replace(&mut self.cut_buffer, CutBuffer::new(register));
+                self.run_on_selections(command);
+                self.cut_buffer = cut_buffer;
+            }
+            None => self.run_on_selections(command),
+        }
+    }
+
+    fn store_in_registers(
+        &mut self,
+        register: Option<char>,
+        content: &str,
+        mode: ClipboardMode,
+        yank: bool,
+    ) {
+        match register {
+            #[cfg(feature = "system_clipboard")]
+            Some('+') => self.system_clipboard.set(content, mode),
+            _ => self.registers.record(register, content, mode, yank),
+        }
+        // Like in Vi the cut buffer holds all of a register that was appended to
+        if let Some(name @ 'A'..='Z') = register {
+            if let Some((content, mode)) = self.registers.get_with_mode(name) {
+                self.cut_buffer.set(&content, mode);
+            }
+        }
+    }
+
+    /// Run the command once for every selection, starting with the last one so that
+    /// the edits don't move the selections that are still to be processed
+    fn apply_to_all_selections(&mut self, command: &EditCommand) {
+        // Overlapping selections would edit the same text twice
+        self.normalize_selections();
+        let primary = self.primary_selection();
+        let mut selections: Vec<(bool, Selection)> = self
+            .secondary_selections
+            .drain(..)
+            .map(|selection| (false, selection))
+            .chain(std::iter::once((true, primary)))
+            .collect();
+        selections.sort_by_key(|(_, selection)| Reverse(selection.start()));
+
+        let mut primary_clipboard = None;
+        let mut processed: Vec<(bool, Selection)> = Vec::with_capacity(selections.len());
+        for (is_primary, selection) in selections {
+            let len_before = self.line_buffer.len();
+            self.selection_anchor = selection.anchor.map(|anchor| anchor.min(len_before));
+            self.line_buffer
+                .set_insertion_point(selection.cursor.min(len_before));
+
+            self.apply_edit_command(command);
+
+            let delta = self.line_buffer.len() as isize - len_before as isize;
+            for (_, selection) in &mut processed {
+                selection.shift(delta);
+            }
+            if is_primary {
+                primary_clipboard = Some(self.cut_buffer.get());
+            }
+            processed.push((is_primary, self.primary_selection()));
+        }
+
+        // The cut buffer keeps what the primary selection put there
+        if let Some((content, mode)) = primary_clipboard {
+            self.cut_buffer.set(&content, mode);
+        }
+        for (is_primary, selection) in processed {
+            if is_primary {
+                self.set_primary_selection(selection);
+            } else {
+                self.secondary_selections.push(selection);
+            }
+        }
+        self.normalize_selections();
+    }
+
+    fn apply_edit_command(&mut self, command: &EditCommand) {
         match command {
             EditCommand::MoveToStart { select } => self.move_to_start(*select),
             EditCommand::MoveToLineStart { select } => self.move_to_line_start(*select),
@@ -55,6 +276,17 @@ impl Editor {
             EditCommand::MoveToPosition { position, select } => {
                 self.move_to_position(*position, *select)
             }
+            EditCommand::MoveToLineNonBlankStart { select } => {
+                self.move_to_line_non_blank_start(*select)
+            }
+            EditCommand::MoveLineUp { select } => {
+                self.update_selection_anchor(*select);
+                self.line_buffer.move_line_up();
+            }
+            EditCommand::MoveLineDown { select } => {
+                self.update_selection_anchor(*select);
+                self.line_buffer.move_line_down();
+            }
             EditCommand::MoveLeft { select } => self.move_left(*select),
             EditCommand::MoveRight { select } => self.move_right(*select),
             EditCommand::MoveWordLeft { select } => self.move_word_left(*select),
@@ -92,10 +324,13 @@ impl Editor {
             EditCommand::CutBigWordRightToNext => self.cut_big_word_right_to_next(),
             EditCommand::PasteCutBufferBefore => self.insert_cut_buffer_before(),
             EditCommand::PasteCutBufferAfter => self.insert_cut_buffer_after(),
+            EditCommand::Yank => self.yank(),
+            EditCommand::YankPop => self.yank_pop(),
             EditCommand::UppercaseWord => self.line_buffer.uppercase_word(),
             EditCommand::LowercaseWord => self.line_buffer.lowercase_word(),
             EditCommand::SwitchcaseChar => self.line_buffer.switchcase_char(),
             EditCommand::CapitalizeChar => self.line_buffer.capitalize_char(),
+            EditCommand::CapitalizeWord => self.line_buffer.capitalize_word(),
             EditCommand::SwapWords => self.line_buffer.swap_words(),
             EditCommand::SwapGraphemes => self.line_buffer.swap_graphemes(),
             EditCommand::Undo => self.undo(),
@@ -116,11 +351,86 @@ impl Editor {
             EditCommand::MoveLeftBefore { c, select } => {
                 self.move_left_until_char(*c, true, true, *select)
             }
+            EditCommand::SelectToChar {
+                c,
+                right,
+                before_char,
+                count,
+                extend,
+            } => self.select_to_char(*c, *right, *before_char, *count, *extend),
             EditCommand::SelectAll => self.select_all(),
             EditCommand::CutSelection => self.cut_selection_to_cut_buffer(),
             EditCommand::CopySelection => self.copy_selection_to_cut_buffer(),
             EditCommand::Paste => self.paste_cut_buffer(),
             EditCommand::ClearSelection => self.selection_anchor = None,
+            EditCommand::EnsureSelection => self.ensure_selection(),
+            EditCommand::SetMark => self.set_mark(),
+            EditCommand::ExchangePointAndMark => self.exchange_point_and_mark(),
+            EditCommand::KillRegion => {
+                if self.mark_active {
+                    self.cut_selection_to_cut_buffer();
+                } else {
+                    self.cut_word_left();
+                }
+            }
+            EditCommand::PasteAfterSelection => self.paste_after_selection(),
+            EditCommand::PasteBeforeSelection => self.paste_before_selection(),
+            EditCommand::ReplaceSelectionChars(c) => self.replace_selection_chars(*c),
+            EditCommand::MoveToMatchingBracket { select } => self.move_to_matching_bracket(*select),
+            EditCommand::SurroundSelection(c) => self.surround_selection(*c),
+            EditCommand::ReplaceSurrounding(from, to) => self.replace_surrounding(*from, *to),
+            EditCommand::DeleteSurrounding(c) => self.delete_surrounding(*c),
+            EditCommand::SelectTextObject(text_object) => self.select_text_object(*text_object),
+            EditCommand::CutTextObject(text_object) => self.cut_text_object(*text_object),
+            EditCommand::CopyTextObject(text_object) => self.copy_text_object(*text_object),
+            EditCommand::CopyCurrentLine => self.copy_current_line(),
+            EditCommand::CopyFromLineStart => self.copy_from_line_start(),
+            EditCommand::CopyToLineEnd => self.copy_to_line_end(),
+            EditCommand::CopyWordLeft => self
+                .copy_range(self.line_buffer.word_left_index()..self.line_buffer.insertion_point()),
+            EditCommand::CopyBigWordLeft => self.copy_range(
+                self.line_buffer.big_word_left_index()..self.line_buffer.insertion_point(),
+            ),
+            EditCommand::CopyWordRight => self.copy_range(
+                self.line_buffer.insertion_point()..self.line_buffer.word_right_index(),
+            ),
+            EditCommand::CopyBigWordRight => self
+                .copy_range(self.line_buffer.insertion_point()..self.line_buffer.next_whitespace()),
+            EditCommand::CopyWordRightToNext => self.copy_range(
+                self.line_buffer.insertion_point()..self.line_buffer.word_right_start_index(),
+            ),
+            EditCommand::CopyBigWordRightToNext => self.copy_range(
+                self.line_buffer.insertion_point()..self.line_buffer.big_word_right_start_index(),
+            ),
+            EditCommand::CopyLeft => self.copy_range(
+                self.line_buffer.grapheme_left_index()..self.line_buffer.insertion_point(),
+            ),
+            EditCommand::CopyRight => self.copy_range(
+                self.line_buffer.insertion_point()..self.line_buffer.grapheme_right_index(),
+            ),
+            EditCommand::CopyRightUntil(c) => self.copy_right_until_char(*c, false, true),
+            EditCommand::CopyRightBefore(c) => self.copy_right_until_char(*c, true, true),
+            EditCommand::CopyLeftUntil(c) => self.copy_left_until_char(*c, false, true),
+            EditCommand::CopyLeftBefore(c) => self.copy_left_until_char(*c, true, true),
+            EditCommand::CopySelectionToNextLine => self.copy_selection_to_next_line(),
+            EditCommand::SelectRegexMatches(regex) => self.select_regex_matches(regex),
+            EditCommand::KeepPrimarySelection => self.secondary_selections.clear(),
+            EditCommand::SelectRegister(name) => self.registers.select(*name),
+            EditCommand::SetSelectionMode(mode) => self.set_selection_mode(*mode),
+            EditCommand::UppercaseSelection => {
+                self.change_selection_case(|text| text.to_uppercase())
+            }
+            EditCommand::LowercaseSelection => {
+                self.change_selection_case(|text| text.to_lowercase())
+            }
+            EditCommand::SwitchcaseSelection => self.change_selection_case(switch_case),
+            EditCommand::IndentLines => self.indent_lines(false),
+            EditCommand::DedentLines => self.indent_lines(true),
+            EditCommand::JoinLines => self.join_lines(),
+            EditCommand::IncrementNumber(amount) => self.line_buffer.increment_number(*amount),
+            EditCommand::DecrementNumber(amount) => {
+                self.line_buffer.increment_number(amount.wrapping_neg())
+            }
             #[cfg(feature = "system_clipboard")]
             EditCommand::CutSelectionSystem => self.cut_selection_to_system(),
             #[cfg(feature = "system_clipboard")]
@@ -128,30 +438,28 @@ impl Editor {
             #[cfg(feature = "system_clipboard")]
             EditCommand::PasteSystem => self.paste_from_system(),
         }
-        if !matches!(command.edit_type(), EditType::MoveCursor { select: true }) {
-            self.selection_anchor = None;
+        match command.edit_type() {
+            EditType::MoveCursor { select: true } => {}
+            EditType::MoveCursor { select: false } if self.mark_active => {}
+            _ => self.selection_anchor = None,
+        }
+        if self.selection_anchor.is_none() {
+            self.selection_mode = SelectionMode::Char;
+            self.mark_active = false;
         }
         if let EditType::MoveCursor { select: true } = command.edit_type() {}
+    }
 
-        let new_undo_behavior = match (command, command.edit_type()) {
-            (_, EditType::MoveCursor { .. }) => UndoBehavior::MoveCursor,
-            (EditCommand::InsertChar(c), EditType::EditText) => UndoBehavior::InsertCharacter(*c),
-            (EditCommand::Delete, EditType::EditText) => {
-                let deleted_char = self.edit_stack.current().grapheme_right().chars().next();
-                UndoBehavior::Delete(deleted_char)
-            }
-            (EditCommand::Backspace, EditType::EditText) => {
-                let deleted_char = self.edit_stack.current().grapheme_left().chars().next();
-                UndoBehavior::Backspace(deleted_char)
-            }
-            (_, EditType::UndoRedo) => UndoBehavior::UndoRedo,
-            (_, _) => UndoBehavior::CreateUndoPoint,
-        };
-
-        self.update_undo_state(new_undo_behavior);
+    /// Drops every selection and the mark, which would point into the replaced text
+    fn clear_selections(&mut self) {
+        self.selection_anchor = None;
+        self.selection_mode = SelectionMode::Char;
+        self.secondary_selections.clear();
+        self.mark_active = false;
     }
+
     fn update_selection_anchor(&mut self, select: bool) {
-        self.selection_anchor = if select {
+        self.selection_anchor = if select || self.mark_active {
             self.selection_anchor
                 .or_else(|| Some(self.insertion_point()))
         } else {
@@ -185,12 +493,14 @@ impl Editor {
     {
         self.update_undo_state(undo_behavior);
         func(&mut self.line_buffer);
+        self.clear_selections();
     }
 
     /// Set the text of the current [`LineBuffer`] given the specified [`UndoBehavior`]
     /// Insertion point update to the end of the buffer.
     pub(crate) fn set_buffer(&mut self, buffer: String, undo_behavior: UndoBehavior) {
         self.line_buffer.set_buffer(buffer);
+        self.clear_selections();
         self.update_undo_state(undo_behavior);
     }
 
@@ -238,6 +548,10 @@ impl Editor {
         self.line_buffer.move_to_line_end();
     }
 
+    fn move_to_line_non_blank_start(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.line_non_blank_start_index(), select);
+    }
+
     fn undo(&mut self) {
         let val = self.edit_stack.undo();
         self.line_buffer = val.clone();
@@ -403,9 +717,50 @@ impl Editor {
         }
     }
 
+    /// Copy `range` of the buffer into the cut buffer, leaving the buffer untouched
+    fn copy_range(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            self.cut_buffer
+                .set(&self.line_buffer.get_buffer()[range], ClipboardMode::Normal);
+        }
+    }
+
+    fn copy_current_line(&mut self) {
+        let copy_slice = &self.line_buffer.get_buffer()[self.line_buffer.current_line_range()];
+        if !copy_slice.is_empty() {
+            self.cut_buffer.set(copy_slice, ClipboardMode::Lines);
+        }
+    }
+
+    fn copy_from_line_start(&mut self) {
+        let insertion_offset = self.line_buffer.insertion_point();
+        let line_start = self.line_buffer.current_line_range().start;
+        self.copy_range(line_start..insertion_offset);
+    }
+
+    fn copy_to_line_end(&mut self) {
+        self.copy_range(
+            self.line_buffer.insertion_point()..self.line_buffer.find_current_line_end(),
+        );
+    }
+
+    fn copy_right_until_char(&mut self, c: char, before_char: bool, current_line: bool) {
+        if let Some(index) = self.line_buffer.find_char_right(c, current_line) {
+            let extra = if before_char { 0 } else { c.len_utf8() };
+            self.copy_range(self.line_buffer.insertion_point()..index + extra);
+        }
+    }
+
+    fn copy_left_until_char(&mut self, c: char, before_char: bool, current_line: bool) {
+        if let Some(index) = self.line_buffer.find_char_left(c, current_line) {
+            let extra = if before_char { c.len_utf8() } else { 0 };
+            self.copy_range(index + extra..self.line_buffer.insertion_point());
+        }
+    }
+
     fn insert_cut_buffer_before(&mut self) {
         self.delete_selection();
-        insert_clipboard_content_before(&mut self.line_buffer, self.cut_buffer.deref_mut())
+        insert_clipboard_content_before(&mut self.line_buffer, &mut self.cut_buffer)
     }
 
     fn insert_cut_buffer_after(&mut self) {
@@ -428,6 +783,33 @@ impl Editor {
         }
     }
 
+    fn yank(&mut self) {
+        self.delete_selection();
+        if let Some(text) = self.kill_ring.yank().map(str::to_string) {
+            let start = self.line_buffer.insertion_point();
+            self.line_buffer.insert_str(&text);
+            self.kill_ring.set_yanked(start..start + text.len());
+        }
+    }
+
+    /// Replace the text inserted by the previous yank with the kill before it
+    fn yank_pop(&mut self) {
+        let Some(yanked) = self.kill_ring.yanked() else {
+            return;
+        };
+        // The buffer could have been replaced without an edit, e.g. by the history
+        if self.line_buffer.get_buffer().get(yanked.clone()) != self.kill_ring.current() {
+            return;
+        }
+        if let Some(text) = self.kill_ring.rotate().map(str::to_string) {
+            self.line_buffer.replace_range(yanked.clone(), &text);
+            self.line_buffer
+                .set_insertion_point(yanked.start + text.len());
+            self.kill_ring
+                .set_yanked(yanked.start..yanked.start + text.len());
+        }
+    }
+
     fn move_right_until_char(
         &mut self,
         c: char,
@@ -458,6 +840,57 @@ impl Editor {
         }
     }
 
+    /// Select up to the `count`th `c` on the current line like Helix, a till motion skips the
+    /// char right next to the cursor so that repeating it moves on
+    fn select_to_char(
+        &mut self,
+        c: char,
+        right: bool,
+        before_char: bool,
+        count: usize,
+        extend: bool,
+    ) {
+        let buffer = self.line_buffer.get_buffer();
+        let cursor = self.insertion_point();
+        let line = self.line_buffer.current_line_range();
+        let target = if right {
+            let start = self.line_buffer.grapheme_right_index().min(line.end);
+            let text = &buffer[start..line.end];
+            let skip = usize::from(before_char && text.starts_with(c));
+            text.match_indices(c)
+                .nth(count.saturating_sub(1) + skip)
+                .map(|(index, _)| {
+                    let index = start + index;
+                    if before_char {
+                        self.line_buffer.grapheme_left_index_from_pos(index)
+                    } else {
+                        index
+                    }
+                })
+        } else {
+            let text = &buffer[line.start..cursor];
+            let skip = usize::from(before_char && text.ends_with(c));
+            text.rmatch_indices(c)
+                .nth(count.saturating_sub(1) + skip)
+                .map(|(index, _)| {
+                    let index = line.start + index;
+                    if before_char {
+                        index + c.len_utf8()
+                    } else {
+                        index
+                    }
+                })
+        };
+
+        // Without enough occurrences the selection stays as it is
+        if let Some(target) = target {
+            if !extend || self.selection_anchor.is_none() {
+                self.selection_anchor = Some(cursor);
+            }
+            self.line_buffer.set_insertion_point(target);
+        }
+    }
+
     fn cut_right_until_char(&mut self, c: char, before_char: bool, current_line: bool) {
         if let Some(index) = self.line_buffer.find_char_right(c, current_line) {
             // Saving the section of the string that will be deleted to be
@@ -538,6 +971,11 @@ impl Editor {
     }
 
     fn cut_selection_to_cut_buffer(&mut self) {
+        match self.selection_mode {
+            SelectionMode::Char => {}
+            SelectionMode::Line => return self.cut_selected_lines(),
+            SelectionMode::Block => return self.cut_selected_block(),
+        }
         if let Some((start, end)) = self.get_selection() {
             let cut_slice = &self.line_buffer.get_buffer()[start..end];
             self.cut_buffer.set(cut_slice, ClipboardMode::Normal);
@@ -555,6 +993,21 @@ impl Editor {
     }
 
     fn copy_selection_to_cut_buffer(&mut self) {
+        match self.selection_mode {
+            SelectionMode::Char => {}
+            SelectionMode::Line => {
+                if let Some((start, end)) = self.get_selection() {
+                    let copy_slice = &self.line_buffer.get_buffer()[start..end];
+                    self.cut_buffer.set(copy_slice, ClipboardMode::Lines);
+                }
+                return;
+            }
+            SelectionMode::Block => {
+                let copy = self.selected_block_text();
+                self.cut_buffer.set(&copy, ClipboardMode::Normal);
+                return;
+            }
+        }
         if let Some((start, end)) = self.get_selection() {
             let cut_slice = &self.line_buffer.get_buffer()[start..end];
             self.cut_buffer.set(cut_slice, ClipboardMode::Normal);
@@ -563,7 +1016,34 @@ impl Editor {
 
     /// If a selection is active returns the selected range, otherwise None.
     /// The range is guaranteed to be ascending.
+    ///
+    /// In [`SelectionMode::Block`] this is the part of the block on the line of the cursor.
+    /// The region of an Emacs mark ends before the cursor instead of including it.
     pub fn get_selection(&self) -> Option<(usize, usize)> {
+        match (self.selection_mode, self.selection_anchor) {
+            (_, Some(mark)) if self.mark_active => {
+                let cursor = self.insertion_point();
+                return Some((mark.min(cursor), mark.max(cursor)));
+            }
+            (SelectionMode::Line, Some(anchor)) => {
+                return Some(line_selection(
+                    self.line_buffer.get_buffer(),
+                    anchor,
+                    self.insertion_point(),
+                ))
+            }
+            (SelectionMode::Block, Some(anchor)) => {
+                let buffer = self.line_buffer.get_buffer();
+                let cursor = self.insertion_point();
+                let (cursor_line_start, _) = line_selection(buffer, cursor, cursor);
+                return block_rows(buffer, anchor, cursor)
+                    .into_iter()
+                    .find(|(start, _)| {
+                        line_selection(buffer, *start, *start).0 == cursor_line_start
+                    });
+            }
+            _ => {}
+        }
         self.selection_anchor.map(|selection_anchor| {
             if self.insertion_point() > selection_anchor {
                 (selection_anchor, self.line_buffer.grapheme_right_index())
@@ -579,105 +1059,684 @@ impl Editor {
         })
     }
 
-    fn delete_selection(&mut self) {
-        if let Some((start, end)) = self.get_selection() {
-            self.line_buffer.clear_range_safe(start, end);
-            self.selection_anchor = None;
+    /// Returns the ranges of the secondary selections, ascending like [`Editor::get_selection`].
+    /// A secondary cursor without a selection covers the grapheme under it.
+    ///
+    /// In [`SelectionMode::Block`] these are the parts of the block on the other lines.
+    pub fn get_secondary_selections(&self) -> Vec<(usize, usize)> {
+        if let (SelectionMode::Block, Some(anchor)) = (self.selection_mode, self.selection_anchor) {
+            let primary = self.get_selection();
+            return block_rows(
+                self.line_buffer.get_buffer(),
+                anchor,
+                self.insertion_point(),
+            )
+            .into_iter()
+            .filter(|row| Some(*row) != primary)
+            .collect();
         }
+        self.secondary_selections
+            .iter()
+            .map(|selection| self.selection_range(selection))
+            .collect()
     }
 
-    fn backspace(&mut self) {
-        if self.selection_anchor.is_some() {
-            self.delete_selection();
-        } else {
-            self.line_buffer.delete_left_grapheme();
+    fn selection_range(&self, selection: &Selection) -> (usize, usize) {
+        let start = selection.start();
+        let end = selection
+            .anchor
+            .map_or(selection.cursor, |anchor| anchor.max(selection.cursor));
+        (start, self.line_buffer.grapheme_right_index_from_pos(end))
+    }
+
+    /// Get the current [`SelectionMode`]
+    pub const fn selection_mode(&self) -> SelectionMode {
+        self.selection_mode
+    }
+
+    fn set_selection_mode(&mut self, mode: SelectionMode) {
+        self.selection_mode = mode;
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.insertion_point());
         }
     }
 
-    fn delete(&mut self) {
-        if self.selection_anchor.is_some() {
-            self.delete_selection();
+    fn cut_selected_lines(&mut self) {
+        let Some((start, end)) = self.get_selection() else {
+            return;
+        };
+        self.cut_buffer.set(
+            &self.line_buffer.get_buffer()[start..end],
+            ClipboardMode::Lines,
+        );
+        // Without a line below the line break above has to go to not leave an empty line
+        let start = if end == self.line_buffer.len() && start > 0 {
+            self.line_buffer.grapheme_left_index_from_pos(start)
         } else {
-            self.line_buffer.delete_right_grapheme();
-        }
+            start
+        };
+        self.line_buffer.clear_range_safe(start, end);
+        self.line_buffer.set_insertion_point(start);
+        self.line_buffer.move_to_line_start();
+        self.selection_anchor = None;
     }
 
-    fn move_word_left(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.word_left_index(), select);
+    /// The rows of the block selection, one per line
+    fn selected_block_text(&self) -> String {
+        let buffer = self.line_buffer.get_buffer();
+        self.selection_anchor
+            .map(|anchor| block_rows(buffer, anchor, self.insertion_point()))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(start, end)| &buffer[start..end])
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
-    fn move_big_word_left(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.big_word_left_index(), select);
+    fn cut_selected_block(&mut self) {
+        let Some(anchor) = self.selection_anchor else {
+            return;
+        };
+        let rows = block_rows(
+            self.line_buffer.get_buffer(),
+            anchor,
+            self.insertion_point(),
+        );
+        let cut = self.selected_block_text();
+        if let Some(&(top_left, _)) = rows.first() {
+            self.cut_buffer.set(&cut, ClipboardMode::Normal);
+            for (start, end) in rows.into_iter().rev() {
+                self.line_buffer.clear_range_safe(start, end);
+            }
+            self.line_buffer.set_insertion_point(top_left);
+        }
+        self.selection_anchor = None;
     }
 
-    fn move_word_right(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.word_right_index(), select);
+    fn primary_selection(&self) -> Selection {
+        Selection {
+            anchor: self.selection_anchor,
+            cursor: self.insertion_point(),
+        }
     }
 
-    fn move_word_right_start(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.word_right_start_index(), select);
+    fn set_primary_selection(&mut self, selection: Selection) {
+        self.selection_anchor = selection.anchor;
+        self.line_buffer.set_insertion_point(selection.cursor);
     }
 
-    fn move_big_word_right_start(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.big_word_right_start_index(), select);
+    /// Clamps the selections to the buffer, drops the ones that became invalid and merges
+    /// the ones that overlap, a merge with the primary selection becomes the primary one
+    fn normalize_selections(&mut self) {
+        let buffer = self.line_buffer.get_buffer();
+        let clamp = |selection: Selection| Selection {
+            anchor: selection.anchor.map(|anchor| anchor.min(buffer.len())),
+            cursor: selection.cursor.min(buffer.len()),
+        };
+        let mut selections: Vec<(bool, Selection)> = std::mem::take(&mut self.secondary_selections)
+            .into_iter()
+            .map(clamp)
+            .filter(|selection| {
+                std::iter::once(selection.cursor)
+                    .chain(selection.anchor)
+                    .all(|pos| buffer.is_char_boundary(pos))
+            })
+            .map(|selection| (false, selection))
+            .chain(std::iter::once((true, clamp(self.primary_selection()))))
+            .collect();
+        selections.sort_by_key(|(_, selection)| selection.start());
+
+        let mut merged: Vec<(bool, Selection)> = Vec::with_capacity(selections.len());
+        for (is_primary, selection) in selections {
+            match merged.last_mut() {
+                Some((last_is_primary, last))
+                    if selection.start() < self.selection_range(last).1 =>
+                {
+                    *last = last.merge(&selection);
+                    *last_is_primary |= is_primary;
+                }
+                _ => merged.push((is_primary, selection)),
+            }
+        }
+
+        for (is_primary, selection) in merged {
+            if is_primary {
+                self.set_primary_selection(selection);
+            } else {
+                self.secondary_selections.push(selection);
+            }
+        }
     }
 
-    fn move_word_right_end(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.word_right_end_index(), select);
+    fn copy_selection_to_next_line(&mut self) {
+        let buffer = self.line_buffer.get_buffer();
+        let mut copies = vec![];
+        let mut new_primary = None;
+        for (is_primary, selection) in self
+            .secondary_selections
+            .iter()
+            .map(|selection| (false, *selection))
+            .chain(std::iter::once((true, self.primary_selection())))
+        {
+            let (start, end) = (
+                selection.start(),
+                selection
+                    .anchor
+                    .map_or(selection.cursor, |anchor| anchor.max(selection.cursor)),
+            );
+            // The copy goes on the line after the last one the selection touches
+            let lines = buffer[start..end].matches('\n').count() + 1;
+            let copy =
+                match selection.anchor {
+                    Some(anchor) => same_column_below(buffer, anchor, lines)
+                        .zip(same_column_below(buffer, selection.cursor, lines)),
+                    None => same_column_below(buffer, selection.cursor, lines).map(|c| (c, c)),
+                };
+            let Some((anchor, cursor)) = copy else {
+                continue;
+            };
+            let copy = Selection {
+                anchor: selection.anchor.map(|_| anchor),
+                cursor,
+            };
+            if is_primary {
+                new_primary = Some(copy);
+            } else {
+                copies.push(copy);
+            }
+        }
+
+        self.secondary_selections.extend(copies);
+        if let Some(new_primary) = new_primary {
+            self.secondary_selections.push(self.primary_selection());
+            self.set_primary_selection(new_primary);
+        }
+        self.normalize_selections();
+    }
+
+    fn select_regex_matches(&mut self, regex: &str) {
+        let buffer = self.line_buffer.get_buffer();
+        let mut matches = vec![];
+        let mut new_primary = None;
+        for (is_primary, selection) in self
+            .secondary_selections
+            .iter()
+            .map(|selection| (false, *selection))
+            .chain(std::iter::once((true, self.primary_selection())))
+        {
+            let (start, end) = self.selection_range(&selection);
+            for found in pattern_matches(regex, &buffer[start..end]) {
+                let selection = Selection {
+                    anchor: Some(start + found.start),
+                    cursor: self
+                        .line_buffer
+                        .grapheme_left_index_from_pos(start + found.end),
+                };
+                if is_primary {
+                    new_primary = Some(selection);
+                }
+                matches.push(selection);
+            }
+        }
+
+        // Without any match the selections stay as they are
+        let Some(new_primary) = new_primary.or_else(|| matches.last().copied()) else {
+            return;
+        };
+        self.secondary_selections = matches;
+        self.set_primary_selection(new_primary);
+        self.normalize_selections();
     }
 
-    fn move_big_word_right_end(&mut self, select: bool) {
-        self.move_to_position(self.line_buffer.big_word_right_end_index(), select);
+    fn set_mark(&mut self) {
+        self.selection_anchor = Some(self.insertion_point());
+        self.selection_mode = SelectionMode::Char;
+        self.mark_active = true;
     }
 
-    fn insert_char(&mut self, c: char) {
-        self.delete_selection();
-        self.line_buffer.insert_char(c);
+    /// Drops the Emacs mark together with its region, leaving other selections alone
+    pub(crate) fn deactivate_mark(&mut self) {
+        if self.mark_active {
+            self.selection_anchor = None;
+            self.mark_active = false;
+        }
     }
 
-    fn insert_str(&mut self, str: &str) {
-        self.delete_selection();
-        self.line_buffer.insert_str(str);
+    fn exchange_point_and_mark(&mut self) {
+        if let Some(mark) = self.selection_anchor {
+            self.selection_anchor = Some(self.insertion_point());
+            self.line_buffer.set_insertion_point(mark);
+            self.mark_active = true;
+        }
     }
 
-    fn insert_newline(&mut self) {
-        self.delete_selection();
-        self.line_buffer.insert_newline();
+    fn ensure_selection(&mut self) {
+        if self.selection_anchor.is_none() && !self.is_cursor_at_buffer_end() {
+            self.selection_anchor = Some(self.insertion_point());
+        }
     }
 
-    #[cfg(feature = "system_clipboard")]
-    fn paste_from_system(&mut self) {
-        self.delete_selection();
-        insert_clipboard_content_before(&mut self.line_buffer, self.system_clipboard.deref_mut());
+    fn replace_selection_chars(&mut self, c: char) {
+        let (start, end) = self.get_selection().unwrap_or_else(|| {
+            (
+                self.insertion_point(),
+                self.line_buffer.grapheme_right_index(),
+            )
+        });
+        // Line breaks are kept so that the shape of the selection doesn't change
+        let replacement: String = self.line_buffer.get_buffer()[start..end]
+            .graphemes(true)
+            .map(|grapheme| {
+                if grapheme == "\n" || grapheme == "\r\n" {
+                    grapheme.to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect();
+        self.line_buffer.replace_range(start..end, &replacement);
+        self.line_buffer.set_insertion_point(start);
+        self.selection_anchor = None;
     }
 
-    fn paste_cut_buffer(&mut self) {
-        self.delete_selection();
-        insert_clipboard_content_before(&mut self.line_buffer, self.cut_buffer.deref_mut());
+    fn move_to_matching_bracket(&mut self, select: bool) {
+        if let Some(index) = self.line_buffer.matching_bracket_index() {
+            self.move_to_position(index, select);
+        }
     }
-}
 
-fn insert_clipboard_content_before(line_buffer: &mut LineBuffer, clipboard: &mut dyn Clipboard) {
-    match clipboard.get() {
-        (content, ClipboardMode::Normal) => {
-            line_buffer.insert_str(&content);
+    fn surround_selection(&mut self, c: char) {
+        let (start, end) = self.get_selection().unwrap_or_else(|| {
+            (
+                self.insertion_point(),
+                self.line_buffer.grapheme_right_index(),
+            )
+        });
+        let (open, close) = pair_delimiters(c);
+        self.line_buffer.replace_range(end..end, &close.to_string());
+        self.line_buffer
+            .replace_range(start..start, &open.to_string());
+        self.line_buffer.set_insertion_point(start);
+        self.selection_anchor = None;
+    }
+
+    fn replace_surrounding(&mut self, from: char, to: char) {
+        let (from_open, from_close) = pair_delimiters(from);
+        if let Some((start, end)) = self
+            .line_buffer
+            .surrounding_pair_indices(from_open, from_close)
+        {
+            let (to_open, to_close) = pair_delimiters(to);
+            self.line_buffer
+                .replace_range(end..end + from_close.len_utf8(), &to_close.to_string());
+            self.line_buffer
+                .replace_range(start..start + from_open.len_utf8(), &to_open.to_string());
+            self.line_buffer.set_insertion_point(start);
         }
-        (mut content, ClipboardMode::Lines) => {
-            // TODO: Simplify that?
-            line_buffer.move_to_line_start();
-            line_buffer.move_line_up();
-            if !content.ends_with('\n') {
-                // TODO: Make sure platform requirements are met
-                content.push('\n');
-            }
-            line_buffer.insert_str(&content);
+    }
+
+    fn delete_surrounding(&mut self, c: char) {
+        let (open, close) = pair_delimiters(c);
+        if let Some((start, end)) = self.line_buffer.surrounding_pair_indices(open, close) {
+            self.line_buffer.clear_range(end..end + close.len_utf8());
+            self.line_buffer.clear_range(start..start + open.len_utf8());
+            self.line_buffer.set_insertion_point(start);
         }
     }
-}
 
-#[cfg(test)]
-mod test {
+    fn select_text_object(&mut self, text_object: TextObject) {
+        if let Some(range) = self.line_buffer.text_object_range(text_object) {
+            if range.is_empty() {
+                self.selection_anchor = None;
+                self.line_buffer.set_insertion_point(range.start);
+            } else {
+                self.selection_anchor = Some(range.start);
+                self.line_buffer
+                    .set_insertion_point(self.line_buffer.grapheme_left_index_from_pos(range.end));
+            }
+        }
+    }
+
+    /// Change the case of the selection, or of the current character without one,
+    /// leaving the cursor at the start of the changed text
+    fn change_selection_case(&mut self, change_case: fn(&str) -> String) {
+        let cursor = self.insertion_point();
+        let ranges = match (self.selection_mode, self.selection_anchor) {
+            (SelectionMode::Block, Some(anchor)) => {
+                block_rows(self.line_buffer.get_buffer(), anchor, cursor)
+            }
+            _ => vec![self
+                .get_selection()
+                .unwrap_or((cursor, self.line_buffer.grapheme_right_index()))],
+        };
+        for &(start, end) in ranges.iter().rev() {
+            let changed = change_case(&self.line_buffer.get_buffer()[start..end]);
+            self.line_buffer.replace_range(start..end, &changed);
+        }
+        if let Some(&(start, _)) = ranges.first() {
+            self.line_buffer.set_insertion_point(start);
+        }
+        self.selection_anchor = None;
+    }
+
+    /// Positions spanning the lines touched by the selection, or the cursor without one
+    fn selected_lines(&self) -> RangeInclusive<usize> {
+        let cursor = self.insertion_point();
+        let anchor = self.selection_anchor.unwrap_or(cursor);
+        anchor.min(cursor)..=anchor.max(cursor)
+    }
+
+    fn indent_lines(&mut self, dedent: bool) {
+        let lines = self.selected_lines();
+        let first_line_start = self.line_buffer.get_buffer()[..*lines.start()]
+            .rfind('\n')
+            .map_or(0, |offset| offset + 1);
+        if dedent {
+            self.line_buffer.dedent_lines(lines);
+        } else {
+            self.line_buffer.indent_lines(lines);
+        }
+        // Like in Vi, shifting a selection leaves the cursor on its first line
+        if self.selection_anchor.take().is_some() {
+            self.line_buffer.set_insertion_point(first_line_start);
+            self.line_buffer
+                .set_insertion_point(self.line_buffer.line_non_blank_start_index());
+        }
+    }
+
+    fn join_lines(&mut self) {
+        self.line_buffer.join_lines(self.selected_lines());
+        self.selection_anchor = None;
+    }
+
+    fn cut_text_object(&mut self, text_object: TextObject) {
+        if let Some(range) = self.line_buffer.text_object_range(text_object) {
+            self.copy_range(range.clone());
+            self.line_buffer.set_insertion_point(range.start);
+            self.line_buffer.clear_range(range);
+        }
+    }
+
+    fn copy_text_object(&mut self, text_object: TextObject) {
+        if let Some(range) = self.line_buffer.text_object_range(text_object) {
+            self.copy_range(range);
+        }
+    }
+
+    /// Whether an edit should replace the selection, which the region of an Emacs mark never is
+    fn has_replaceable_selection(&self) -> bool {
+        self.selection_anchor.is_some() && !self.mark_active
+    }
+
+    fn delete_selection(&mut self) {
+        if !self.has_replaceable_selection() {
+            return;
+        }
+        if let Some((start, end)) = self.get_selection() {
+            self.line_buffer.clear_range_safe(start, end);
+            self.selection_anchor = None;
+        }
+    }
+
+    fn backspace(&mut self) {
+        if self.has_replaceable_selection() {
+            self.delete_selection();
+        } else {
+            self.line_buffer.delete_left_grapheme();
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.has_replaceable_selection() {
+            self.delete_selection();
+        } else {
+            self.line_buffer.delete_right_grapheme();
+        }
+    }
+
+    fn move_word_left(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.word_left_index(), select);
+    }
+
+    fn move_big_word_left(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.big_word_left_index(), select);
+    }
+
+    fn move_word_right(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.word_right_index(), select);
+    }
+
+    fn move_word_right_start(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.word_right_start_index(), select);
+    }
+
+    fn move_big_word_right_start(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.big_word_right_start_index(), select);
+    }
+
+    fn move_word_right_end(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.word_right_end_index(), select);
+    }
+
+    fn move_big_word_right_end(&mut self, select: bool) {
+        self.move_to_position(self.line_buffer.big_word_right_end_index(), select);
+    }
+
+    fn insert_char(&mut self, c: char) {
+        self.delete_selection();
+        self.line_buffer.insert_char(c);
+    }
+
+    fn insert_str(&mut self, str: &str) {
+        self.delete_selection();
+        self.line_buffer.insert_str(str);
+    }
+
+    fn insert_newline(&mut self) {
+        self.delete_selection();
+        self.line_buffer.insert_newline();
+    }
+
+    #[cfg(feature = "system_clipboard")]
+    fn paste_from_system(&mut self) {
+        self.delete_selection();
+        insert_clipboard_content_before(&mut self.line_buffer, self.system_clipboard.as_mut());
+    }
+
+    fn paste_after_selection(&mut self) {
+        if let Some((_, end)) = self.get_selection() {
+            self.line_buffer
+                .set_insertion_point(self.line_buffer.grapheme_left_index_from_pos(end));
+            self.selection_anchor = None;
+        }
+        let len = self.line_buffer.len();
+        self.insert_cut_buffer_after();
+        // Leave the cursor on the last pasted grapheme so repeated pastes line up
+        if self.line_buffer.len() > len {
+            self.line_buffer.move_left();
+        }
+    }
+
+    fn paste_before_selection(&mut self) {
+        if let Some((start, _)) = self.get_selection() {
+            self.line_buffer.set_insertion_point(start);
+            self.selection_anchor = None;
+        }
+        self.insert_cut_buffer_before();
+    }
+
+    fn paste_cut_buffer(&mut self) {
+        self.delete_selection();
+        insert_clipboard_content_before(&mut self.line_buffer, &mut self.cut_buffer);
+    }
+}
+
+/// Range of the whole lines touched by the selection between `anchor` and `cursor`,
+/// including the line break of the last line
+fn line_selection(buffer: &str, anchor: usize, cursor: usize) -> (usize, usize) {
+    let (first, last) = (anchor.min(cursor), anchor.max(cursor));
+    let start = buffer[..first].rfind('\n').map_or(0, |index| index + 1);
+    let end = buffer[last..]
+        .find('\n')
+        .map_or(buffer.len(), |index| last + index + 1);
+    (start, end)
+}
+
+/// Ranges of the rows of the block with `anchor` and `cursor` in opposite corners, top to
+/// bottom. Lines ending left of the block don't have a row.
+fn block_rows(buffer: &str, anchor: usize, cursor: usize) -> Vec<(usize, usize)> {
+    let column = |pos: usize| {
+        let line_start = buffer[..pos].rfind('\n').map_or(0, |index| index + 1);
+        buffer[line_start..pos].graphemes(true).count()
+    };
+    let (left, right) = (
+        column(anchor).min(column(cursor)),
+        column(anchor).max(column(cursor)),
+    );
+    let (block_start, block_end) = line_selection(buffer, anchor, cursor);
+
+    let mut rows = Vec::new();
+    let mut line_start = block_start;
+    for line in buffer[block_start..block_end].split_inclusive('\n') {
+        let text = line.trim_end_matches(['\r', '\n']);
+        let mut graphemes = text.grapheme_indices(true).skip(left);
+        if let Some((start, _)) = graphemes.next() {
+            let end = graphemes
+                .nth(right - left)
+                .map_or(text.len(), |(index, _)| index);
+            rows.push((line_start + start, line_start + end));
+        }
+        line_start += line.len();
+    }
+    rows
+}
+
+/// Swap upper and lower case characters
+fn switch_case(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_lowercase() {
+                c.to_uppercase().to_string()
+            } else {
+                c.to_lowercase().to_string()
+            }
+        })
+        .collect()
+}
+
+/// Commands inserting the content of the cut buffer
+fn is_paste(command: &EditCommand) -> bool {
+    matches!(
+        command,
+        EditCommand::Paste
+            | EditCommand::PasteCutBufferBefore
+            | EditCommand::PasteCutBufferAfter
+            | EditCommand::PasteAfterSelection
+            | EditCommand::PasteBeforeSelection
+    )
+}
+
+/// Commands cutting text in front of the cursor, which is prepended to the previous kill
+fn is_backward_kill(command: &EditCommand) -> bool {
+    matches!(
+        command,
+        EditCommand::CutFromStart
+            | EditCommand::CutFromLineStart
+            | EditCommand::CutWordLeft
+            | EditCommand::CutBigWordLeft
+            | EditCommand::CutLeftUntil(_)
+            | EditCommand::CutLeftBefore(_)
+    )
+}
+
+/// Commands filling the cut buffer without changing the text, a yank in Vi terms
+fn is_copy(command: &EditCommand) -> bool {
+    matches!(
+        command,
+        EditCommand::CopySelection
+            | EditCommand::CopyCurrentLine
+            | EditCommand::CopyFromLineStart
+            | EditCommand::CopyToLineEnd
+            | EditCommand::CopyWordLeft
+            | EditCommand::CopyBigWordLeft
+            | EditCommand::CopyWordRight
+            | EditCommand::CopyBigWordRight
+            | EditCommand::CopyWordRightToNext
+            | EditCommand::CopyBigWordRightToNext
+            | EditCommand::CopyLeft
+            | EditCommand::CopyRight
+            | EditCommand::CopyRightUntil(_)
+            | EditCommand::CopyRightBefore(_)
+            | EditCommand::CopyLeftUntil(_)
+            | EditCommand::CopyLeftBefore(_)
+            | EditCommand::CopyTextObject(_)
+    )
+}
+
+/// Position in the same grapheme column `lines` lines below the one containing `pos`.
+/// Stops at the end of a shorter line, returns `None` if the buffer has too few lines.
+fn same_column_below(buffer: &str, pos: usize, lines: usize) -> Option<usize> {
+    let line_start = buffer[..pos].rfind('\n').map_or(0, |index| index + 1);
+    let column = buffer[line_start..pos].graphemes(true).count();
+    let mut target_start = line_start;
+    for _ in 0..lines {
+        target_start += buffer[target_start..].find('\n')? + 1;
+    }
+    let target_line = buffer[target_start..].lines().next().unwrap_or_default();
+    let offset = target_line
+        .grapheme_indices(true)
+        .nth(column)
+        .map_or(target_line.len(), |(index, _)| index);
+    Some(target_start + offset)
+}
+
+fn insert_clipboard_content_before(line_buffer: &mut LineBuffer, clipboard: &mut dyn Clipboard) {
+    match clipboard.get() {
+        (content, ClipboardMode::Normal) => {
+            line_buffer.insert_str(&content);
+        }
+        (mut content, ClipboardMode::Lines) => {
+            // TODO: Simplify that?
+            line_buffer.move_to_line_start();
+            line_buffer.move_line_up();
+            if !content.ends_with('\n') {
+                // TODO: Make sure platform requirements are met
+                content.push('\n');
+            }
+            line_buffer.insert_str(&content);
+        }
+    }
+}
+
+/// Non-empty matches of the regex `pattern` in `text`, none for an invalid regex
+#[cfg(feature = "regex")]
+fn pattern_matches(pattern: &str, text: &str) -> Vec<Range<usize>> {
+    regex::Regex::new(pattern)
+        .map(|regex| {
+            regex
+                .find_iter(text)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Occurrences of the literal `pattern` in `text`
+#[cfg(not(feature = "regex"))]
+fn pattern_matches(pattern: &str, text: &str) -> Vec<Range<usize>> {
+    if pattern.is_empty() {
+        return vec![];
+    }
+    text.match_indices(pattern)
+        .map(|(index, found)| index..index + found.len())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
     use super::*;
+    use crate::enums::TextObjectKind;
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
@@ -763,6 +1822,688 @@ mod test {
         assert_eq!(editor.get_buffer(), expected);
     }
 
+    #[rstest]
+    #[case("abc", 1, None, "ac", "b")]
+    #[case("abc def", 4, Some(6), "abc ", "def")]
+    #[case("abc def", 6, Some(4), "abc ", "def")]
+    #[case("abc", 3, None, "abc", "")]
+    fn test_cut_ensured_selection(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] anchor: Option<usize>,
+        #[case] expected: &str,
+        #[case] expected_cut: &str,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+        editor.selection_anchor = anchor;
+
+        editor.run_edit_command(&EditCommand::EnsureSelection);
+        editor.run_edit_command(&EditCommand::CutSelection);
+
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(editor.cut_buffer.get().0, expected_cut);
+    }
+
+    #[rstest]
+    #[case("abc", 1, None, "abXc", 2)]
+    #[case("abc def", 4, Some(6), "abc defX", 7)]
+    #[case("abc def", 2, Some(0), "abcX def", 3)]
+    fn test_paste_after_selection(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] anchor: Option<usize>,
+        #[case] expected: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut editor = editor_with(input);
+        editor.cut_buffer.set("X", ClipboardMode::Normal);
+        editor.line_buffer.set_insertion_point(position);
+        editor.selection_anchor = anchor;
+
+        editor.run_edit_command(&EditCommand::PasteAfterSelection);
+
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(editor.insertion_point(), expected_position);
+    }
+
+    #[rstest]
+    #[case("abc", 1, None, "aXbc")]
+    #[case("abc def", 6, Some(4), "abc Xdef")]
+    fn test_paste_before_selection(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] anchor: Option<usize>,
+        #[case] expected: &str,
+    ) {
+        let mut editor = editor_with(input);
+        editor.cut_buffer.set("X", ClipboardMode::Normal);
+        editor.line_buffer.set_insertion_point(position);
+        editor.selection_anchor = anchor;
+
+        editor.run_edit_command(&EditCommand::PasteBeforeSelection);
+
+        assert_eq!(editor.get_buffer(), expected);
+    }
+
+    #[rstest]
+    #[case("abc", 1, None, 'x', "axc")]
+    #[case("abc def", 4, Some(6), 'x', "abc xxx")]
+    #[case("a🔄c", 0, Some(5), '-', "---")]
+    #[case("ab\ncd", 1, Some(3), 'x', "ax\nxd")]
+    fn test_replace_selection_chars(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] anchor: Option<usize>,
+        #[case] replacement: char,
+        #[case] expected: &str,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+        editor.selection_anchor = anchor;
+
+        editor.run_edit_command(&EditCommand::ReplaceSelectionChars(replacement));
+
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(editor.get_selection(), None);
+    }
+
+    #[rstest]
+    #[case("abc", 1, None, EditCommand::UppercaseSelection, "aBc", 1)]
+    #[case("abc def", 6, Some(4), EditCommand::UppercaseSelection, "abc DEF", 4)]
+    #[case("ÄBC", 0, Some(2), EditCommand::LowercaseSelection, "äbC", 0)]
+    #[case(
+        "aBc\nDeF",
+        1,
+        Some(5),
+        EditCommand::SwitchcaseSelection,
+        "abC\ndEF",
+        1
+    )]
+    fn test_change_selection_case(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] anchor: Option<usize>,
+        #[case] command: EditCommand,
+        #[case] expected: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+        editor.selection_anchor = anchor;
+
+        editor.run_edit_command(&command);
+
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(editor.insertion_point(), expected_position);
+        assert_eq!(editor.get_selection(), None);
+    }
+
+    #[test]
+    fn test_change_block_selection_case() {
+        let mut editor = editor_with("abc\ndef");
+        editor.line_buffer.set_insertion_point(5);
+        editor.selection_anchor = Some(0);
+        editor.run_edit_command(&EditCommand::SetSelectionMode(SelectionMode::Block));
+
+        editor.run_edit_command(&EditCommand::UppercaseSelection);
+
+        assert_eq!(editor.get_buffer(), "ABc\nDEf");
+        assert_eq!(editor.insertion_point(), 0);
+    }
+
+    #[test]
+    fn test_indent_selected_lines() {
+        let mut editor = editor_with("foo\n  bar\nbaz");
+        editor.line_buffer.set_insertion_point(6);
+        editor.selection_anchor = Some(1);
+
+        editor.run_edit_command(&EditCommand::IndentLines);
+
+        assert_eq!(editor.get_buffer(), "    foo\n      bar\nbaz");
+        assert_eq!(editor.insertion_point(), 4);
+        assert_eq!(editor.get_selection(), None);
+    }
+
+    #[rstest]
+    #[case("abc", 1, None, '(', "a(b)c")]
+    #[case("abc def", 4, Some(6), ']', "abc [def]")]
+    #[case("abc def", 0, Some(2), '"', "\"abc\" def")]
+    fn test_surround_selection(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] anchor: Option<usize>,
+        #[case] c: char,
+        #[case] expected: &str,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+        editor.selection_anchor = anchor;
+
+        editor.run_edit_command(&EditCommand::SurroundSelection(c));
+
+        assert_eq!(editor.get_buffer(), expected);
+    }
+
+    #[rstest]
+    #[case("f(a, (b))", 6, '(', '[', "f(a, [b])")]
+    #[case("f(a, (b))", 3, ')', '{', "f{a, (b)}")]
+    #[case("say 'hi'", 6, '\'', '"', "say \"hi\"")]
+    #[case("abc", 1, '(', '[', "abc")]
+    fn test_replace_surrounding(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] from: char,
+        #[case] to: char,
+        #[case] expected: &str,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+
+        editor.run_edit_command(&EditCommand::ReplaceSurrounding(from, to));
+
+        assert_eq!(editor.get_buffer(), expected);
+    }
+
+    #[rstest]
+    #[case("f(a, (b))", 6, '(', "f(a, b)")]
+    #[case("say 'hi'", 6, '\'', "say hi")]
+    #[case("abc", 1, '(', "abc")]
+    fn test_delete_surrounding(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] c: char,
+        #[case] expected: &str,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+
+        editor.run_edit_command(&EditCommand::DeleteSurrounding(c));
+
+        assert_eq!(editor.get_buffer(), expected);
+    }
+
+    #[rstest]
+    #[case("abc def ghi", 5, TextObjectKind::Word, false, Some((4, 7)))]
+    #[case("f(a, b)", 3, TextObjectKind::Pair('('), true, Some((1, 7)))]
+    #[case("f()", 1, TextObjectKind::Pair('('), false, None)]
+    fn test_select_text_object(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] kind: TextObjectKind,
+        #[case] around: bool,
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+
+        editor.run_edit_command(&EditCommand::SelectTextObject(TextObject { kind, around }));
+
+        assert_eq!(editor.get_selection(), expected);
+    }
+
+    #[rstest]
+    #[case("abc def ghi", 5, TextObjectKind::Word, false, "abc  ghi", "def", 4)]
+    #[case("abc def ghi", 5, TextObjectKind::Word, true, "abc ghi", "def ", 4)]
+    #[case(
+        "say \"hi there\"",
+        7,
+        TextObjectKind::Pair('"'),
+        false,
+        "say \"\"",
+        "hi there",
+        5
+    )]
+    #[case("f(a, b)", 3, TextObjectKind::Pair(')'), true, "f", "(a, b)", 1)]
+    #[case("abc", 1, TextObjectKind::Pair('('), false, "abc", "", 1)]
+    fn test_cut_text_object(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] kind: TextObjectKind,
+        #[case] around: bool,
+        #[case] expected_buffer: &str,
+        #[case] expected_cut: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+
+        editor.run_edit_command(&EditCommand::CutTextObject(TextObject { kind, around }));
+
+        assert_eq!(editor.get_buffer(), expected_buffer);
+        assert_eq!(editor.cut_buffer.get().0, expected_cut);
+        assert_eq!(editor.insertion_point(), expected_position);
+    }
+
+    #[rstest]
+    #[case(EditCommand::CopyTextObject(TextObject { kind: TextObjectKind::Word, around: false }), "def")]
+    #[case(EditCommand::CopyWordRightToNext, "ef ")]
+    #[case(EditCommand::CopyWordLeft, "d")]
+    #[case(EditCommand::CopyFromLineStart, "abc d")]
+    #[case(EditCommand::CopyToLineEnd, "ef ghi")]
+    #[case(EditCommand::CopyRightUntil('g'), "ef g")]
+    #[case(EditCommand::CopyLeftBefore('c'), " d")]
+    #[case(EditCommand::CopyCurrentLine, "abc def ghi")]
+    fn test_copy_keeps_buffer(#[case] command: EditCommand, #[case] expected_copy: &str) {
+        let mut editor = editor_with("abc def ghi");
+        editor.line_buffer.set_insertion_point(5);
+
+        editor.run_edit_command(&command);
+
+        assert_eq!(editor.get_buffer(), "abc def ghi");
+        assert_eq!(editor.insertion_point(), 5);
+        assert_eq!(editor.cut_buffer.get().0, expected_copy);
+    }
+
+    #[rstest]
+    #[case("abc\ndef\nghi", 1, None, 1, 5, vec![(1, 2)])]
+    #[case("abc\ndef\nghi", 0, Some(2), 1, 6, vec![(0, 3)])]
+    #[case("abc\ndef\nghi", 0, Some(2), 2, 10, vec![(0, 3), (4, 7)])]
+    #[case("abcd\nx\nghi", 3, None, 1, 6, vec![(3, 4)])]
+    #[case("abc\ndef", 5, None, 1, 5, vec![])]
+    fn test_copy_selection_to_next_line(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] select_to: Option<usize>,
+        #[case] times: usize,
+        #[case] expected_cursor: usize,
+        #[case] expected_secondary: Vec<(usize, usize)>,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(position);
+        if let Some(select_to) = select_to {
+            editor.run_edit_command(&EditCommand::MoveToPosition {
+                position: select_to,
+                select: true,
+            });
+        }
+
+        for _ in 0..times {
+            editor.run_edit_command(&EditCommand::CopySelectionToNextLine);
+        }
+
+        assert_eq!(editor.insertion_point(), expected_cursor);
+        assert_eq!(editor.get_secondary_selections(), expected_secondary);
+    }
+
+    #[rstest]
+    #[case("foo bar foo", "foo", vec![(0, 3), (8, 11)])]
+    #[cfg_attr(feature = "regex", case("a1 b22 c333", r"\d+", vec![(1, 2), (4, 6), (8, 11)]))]
+    #[cfg_attr(not(feature = "regex"), case("a.b a.c", "a.", vec![(0, 2), (4, 6)]))]
+    #[case("foo bar", "baz", vec![(0, 7)])]
+    #[cfg_attr(feature = "regex", case("foo bar", "(", vec![(0, 7)]))]
+    fn test_select_regex_matches(
+        #[case] input: &str,
+        #[case] regex: &str,
+        #[case] expected: Vec<(usize, usize)>,
+    ) {
+        let mut editor = editor_with(input);
+        editor.run_edit_command(&EditCommand::SelectAll);
+
+        editor.run_edit_command(&EditCommand::SelectRegexMatches(regex.to_string()));
+
+        let mut selections = editor.get_secondary_selections();
+        selections.extend(editor.get_selection());
+        selections.sort_unstable();
+        assert_eq!(selections, expected);
+        assert_eq!(editor.get_selection(), expected.last().copied());
+    }
+
+    #[test]
+    fn yank_and_delete_fill_numbered_registers() {
+        let mut editor = editor_with("abc def ghi");
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+
+        editor.run_edit_command(&EditCommand::CopyWordRightToNext);
+        editor.run_edit_command(&EditCommand::CutWordRightToNext);
+        editor.run_edit_command(&EditCommand::CutWordRightToNext);
+
+        assert_eq!(editor.get_buffer(), "ghi");
+        assert_eq!(editor.registers().get('0'), Some("abc "));
+        assert_eq!(editor.registers().get('1'), Some("def "));
+        assert_eq!(editor.registers().get('2'), Some("abc "));
+        assert_eq!(editor.cut_buffer.get().0, "def ");
+    }
+
+    #[test]
+    fn named_register_is_kept_across_deletes() {
+        let mut editor = editor_with("abc def");
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+
+        editor.run_edit_command(&EditCommand::SelectRegister('a'));
+        editor.run_edit_command(&EditCommand::CopyWordRightToNext);
+        editor.run_edit_command(&EditCommand::SelectRegister('A'));
+        editor.run_edit_command(&EditCommand::CopyWordRight);
+        editor.run_edit_command(&EditCommand::CutToLineEnd);
+        editor.run_edit_command(&EditCommand::SelectRegister('a'));
+        editor.run_edit_command(&EditCommand::PasteCutBufferBefore);
+
+        assert_eq!(editor.registers().get('a'), Some("abc abc"));
+        assert_eq!(editor.registers().get('0'), None);
+        assert_eq!(editor.get_buffer(), "abc abc");
+        // Without a register the paste uses the last cut again
+        editor.run_edit_command(&EditCommand::PasteCutBufferBefore);
+        assert_eq!(editor.get_buffer(), "abc abcabc def");
+    }
+
+    #[test]
+    fn consecutive_kills_are_yanked_together() {
+        let mut editor = editor_with("foo bar baz");
+        editor.run_edit_command(&EditCommand::MoveLeft { select: false });
+        editor.run_edit_command(&EditCommand::MoveWordLeft { select: false });
+
+        editor.run_edit_command(&EditCommand::CutToLineEnd);
+        editor.run_edit_command(&EditCommand::CutWordLeft);
+        editor.run_edit_command(&EditCommand::Yank);
+
+        assert_eq!(editor.get_buffer(), "foo bar baz");
+    }
+
+    #[test]
+    fn yank_pop_replaces_the_yanked_text() {
+        let mut editor = editor_with("foo bar");
+        editor.run_edit_command(&EditCommand::CutWordLeft);
+        editor.run_edit_command(&EditCommand::MoveLeft { select: false });
+        editor.run_edit_command(&EditCommand::CutWordLeft);
+        editor.run_edit_command(&EditCommand::Yank);
+        assert_eq!(editor.get_buffer(), "foo ");
+
+        editor.run_edit_command(&EditCommand::YankPop);
+        assert_eq!(editor.get_buffer(), "bar ");
+        assert_eq!(editor.insertion_point(), 3);
+
+        editor.run_edit_command(&EditCommand::Undo);
+        assert_eq!(editor.get_buffer(), "foo ");
+
+        // Only possible right after a yank
+        editor.run_edit_command(&EditCommand::MoveToEnd { select: false });
+        editor.run_edit_command(&EditCommand::YankPop);
+        assert_eq!(editor.get_buffer(), "foo ");
+    }
+
+    #[test]
+    fn region_follows_the_cursor_until_an_edit() {
+        let mut editor = editor_with("foo bar baz");
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+        editor.run_edit_command(&EditCommand::MoveWordRightStart { select: false });
+
+        editor.run_edit_command(&EditCommand::SetMark);
+        editor.run_edit_command(&EditCommand::MoveWordRightStart { select: false });
+        assert_eq!(editor.get_selection(), Some((4, 8)));
+        editor.run_edit_command(&EditCommand::ExchangePointAndMark);
+        assert_eq!(editor.insertion_point(), 4);
+        editor.run_edit_command(&EditCommand::MoveLeft { select: false });
+        assert_eq!(editor.get_selection(), Some((3, 8)));
+
+        editor.run_edit_command(&EditCommand::UppercaseSelection);
+        assert_eq!(editor.get_buffer(), "foo BAR baz");
+        assert_eq!(editor.get_selection(), None);
+        editor.run_edit_command(&EditCommand::MoveRight { select: false });
+        assert_eq!(editor.get_selection(), None);
+    }
+
+    #[rstest]
+    #[case(true, "foo baz")]
+    #[case(false, "foo bar baz")]
+    fn region_is_killed_or_copied(#[case] cut: bool, #[case] expected: &str) {
+        let mut editor = editor_with("foo bar baz");
+        editor.run_edit_command(&EditCommand::MoveToPosition {
+            position: 4,
+            select: false,
+        });
+        editor.run_edit_command(&EditCommand::SetMark);
+        editor.run_edit_command(&EditCommand::MoveWordRightStart { select: false });
+
+        if cut {
+            editor.run_edit_command(&EditCommand::KillRegion);
+        } else {
+            editor.run_edit_command(&EditCommand::CopySelection);
+        }
+
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(editor.get_selection(), None);
+        editor.run_edit_command(&EditCommand::MoveToEnd { select: false });
+        editor.run_edit_command(&EditCommand::Yank);
+        assert_eq!(editor.get_buffer(), format!("{expected}bar "));
+    }
+
+    #[rstest]
+    #[case(EditCommand::InsertChar('X'), "foo barX baz")]
+    #[case(EditCommand::InsertString("XY".into()), "foo barXY baz")]
+    #[case(EditCommand::Backspace, "foo ba baz")]
+    #[case(EditCommand::Delete, "foo barbaz")]
+    fn edits_keep_the_mark_region(#[case] command: EditCommand, #[case] expected: &str) {
+        let mut editor = editor_with("foo bar baz");
+        editor.run_edit_command(&EditCommand::MoveToPosition {
+            position: 4,
+            select: false,
+        });
+        editor.run_edit_command(&EditCommand::SetMark);
+        editor.run_edit_command(&EditCommand::MoveToPosition {
+            position: 7,
+            select: false,
+        });
+
+        editor.run_edit_command(&command);
+
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(editor.get_selection(), None);
+    }
+
+    #[test]
+    fn yank_keeps_the_mark_region() {
+        let mut editor = editor_with("foo bar baz");
+        editor.run_edit_command(&EditCommand::CutWordLeft);
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+        editor.run_edit_command(&EditCommand::SetMark);
+        editor.run_edit_command(&EditCommand::MoveToEnd { select: false });
+
+        editor.run_edit_command(&EditCommand::Yank);
+
+        assert_eq!(editor.get_buffer(), "foo bar baz");
+    }
+
+    #[test]
+    fn kill_region_without_mark_cuts_the_word_left() {
+        let mut editor = editor_with("foo bar");
+
+        editor.run_edit_command(&EditCommand::KillRegion);
+
+        assert_eq!(editor.get_buffer(), "foo ");
+    }
+
+    #[test]
+    fn selecting_a_register_keeps_the_selection() {
+        let mut editor = editor_with("abc def");
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+        editor.run_edit_command(&EditCommand::MoveWordRightEnd { select: true });
+
+        editor.run_edit_command(&EditCommand::SelectRegister('b'));
+        editor.run_edit_command(&EditCommand::CopySelection);
+
+        assert_eq!(editor.registers().get('b'), Some("abc"));
+    }
+
+    #[test]
+    fn pasting_an_empty_register_inserts_nothing() {
+        let mut editor = editor_with("abc");
+        editor.cut_buffer.set("X", ClipboardMode::Normal);
+
+        editor.run_edit_command(&EditCommand::SelectRegister('z'));
+        editor.run_edit_command(&EditCommand::PasteCutBufferBefore);
+
+        assert_eq!(editor.get_buffer(), "abc");
+    }
+
+    #[rstest]
+    #[case("abc\ndef\nghi", 5, 9, Some((4, 11)))]
+    #[case("abc\ndef\nghi", 9, 1, Some((0, 11)))]
+    #[case("abc\ndef\nghi", 9, 9, Some((8, 11)))]
+    fn test_line_selection(
+        #[case] input: &str,
+        #[case] anchor: usize,
+        #[case] cursor: usize,
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(anchor);
+        editor.run_edit_command(&EditCommand::SetSelectionMode(SelectionMode::Line));
+        editor.line_buffer.set_insertion_point(cursor);
+
+        assert_eq!(editor.get_selection(), expected);
+    }
+
+    #[rstest]
+    #[case("abc\ndef\nghi", 1, 5, "abc\ndef\n", "ghi", 0)]
+    #[case("abc\ndef\nghi", 5, 9, "def\nghi", "abc", 0)]
+    #[case("abc\ndef\nghi", 5, 5, "def\n", "abc\nghi", 4)]
+    fn test_cut_selected_lines(
+        #[case] input: &str,
+        #[case] anchor: usize,
+        #[case] cursor: usize,
+        #[case] expected_cut: &str,
+        #[case] expected_buffer: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(anchor);
+        editor.run_edit_command(&EditCommand::SetSelectionMode(SelectionMode::Line));
+        editor.line_buffer.set_insertion_point(cursor);
+
+        editor.run_edit_command(&EditCommand::CutSelection);
+
+        assert_eq!(editor.cut_buffer.get().0, expected_cut);
+        assert!(matches!(editor.cut_buffer.get().1, ClipboardMode::Lines));
+        assert_eq!(editor.get_buffer(), expected_buffer);
+        assert_eq!(editor.insertion_point(), expected_position);
+        assert_eq!(editor.selection_mode(), SelectionMode::Char);
+    }
+
+    #[test]
+    fn copied_lines_are_pasted_below() {
+        let mut editor = editor_with("abc\ndef");
+        editor.line_buffer.set_insertion_point(1);
+        editor.run_edit_command(&EditCommand::SetSelectionMode(SelectionMode::Line));
+        editor.run_edit_command(&EditCommand::CopySelection);
+        editor.line_buffer.set_insertion_point(2);
+
+        editor.run_edit_command(&EditCommand::PasteCutBufferAfter);
+
+        assert_eq!(editor.get_buffer(), "abc\nabc\ndef");
+    }
+
+    #[rstest]
+    #[case("abcd\nefgh\nijkl", 1, 12, Some((11, 13)), vec![(1, 3), (6, 8)])]
+    #[case("abcd\nefgh\nijkl", 12, 1, Some((1, 3)), vec![(6, 8), (11, 13)])]
+    #[case("abcd\ne\nijkl", 1, 9, Some((8, 10)), vec![(1, 3)])]
+    #[case("abcd\nefgh", 2, 2, Some((2, 3)), vec![])]
+    fn test_block_selection(
+        #[case] input: &str,
+        #[case] anchor: usize,
+        #[case] cursor: usize,
+        #[case] expected: Option<(usize, usize)>,
+        #[case] expected_secondary: Vec<(usize, usize)>,
+    ) {
+        let mut editor = editor_with(input);
+        editor.line_buffer.set_insertion_point(anchor);
+        editor.run_edit_command(&EditCommand::SetSelectionMode(SelectionMode::Block));
+        editor.line_buffer.set_insertion_point(cursor);
+
+        assert_eq!(editor.get_selection(), expected);
+        assert_eq!(editor.get_secondary_selections(), expected_secondary);
+    }
+
+    #[test]
+    fn cut_selected_block() {
+        let mut editor = editor_with("abcd\ne\nijkl");
+        editor.line_buffer.set_insertion_point(1);
+        editor.run_edit_command(&EditCommand::SetSelectionMode(SelectionMode::Block));
+        editor.line_buffer.set_insertion_point(9);
+
+        editor.run_edit_command(&EditCommand::CutSelection);
+
+        assert_eq!(editor.get_buffer(), "ad\ne\nil");
+        assert_eq!(editor.cut_buffer.get().0, "bc\njk");
+        assert_eq!(editor.insertion_point(), 1);
+        assert_eq!(editor.get_secondary_selections(), vec![]);
+    }
+
+    fn editor_with_matches(input: &str, regex: &str) -> Editor {
+        let mut editor = editor_with(input);
+        editor.run_edit_command(&EditCommand::SelectAll);
+        editor.run_edit_command(&EditCommand::SelectRegexMatches(regex.to_string()));
+        editor
+    }
+
+    #[rstest]
+    #[case(vec![EditCommand::CutSelection], " bar ")]
+    #[case(vec![EditCommand::ClearSelection, EditCommand::InsertChar('x')], "foxo bar foxo")]
+    #[case(
+        vec![EditCommand::SurroundSelection('(')],
+        "(foo) bar (foo)"
+    )]
+    #[case(
+        vec![
+            EditCommand::MoveToLineStart { select: false },
+            EditCommand::InsertString("> ".to_string()),
+        ],
+        "> foo bar foo"
+    )]
+    fn test_edit_applies_to_all_selections(
+        #[case] commands: Vec<EditCommand>,
+        #[case] expected: &str,
+    ) {
+        let mut editor = editor_with_matches("foo bar foo", "foo");
+
+        for command in &commands {
+            editor.run_edit_command(command);
+        }
+
+        assert_eq!(editor.get_buffer(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_cut_buffer_keeps_primary_selection() {
+        let mut editor = editor_with_matches("ab cd", r"\w+");
+        editor.run_edit_command(&EditCommand::CutSelection);
+        editor.run_edit_command(&EditCommand::KeepPrimarySelection);
+        editor.run_edit_command(&EditCommand::PasteCutBufferBefore);
+
+        assert_eq!(editor.get_buffer(), " cd");
+    }
+
+    #[test]
+    fn test_keep_primary_selection() {
+        let mut editor = editor_with_matches("foo bar foo", "foo");
+
+        editor.run_edit_command(&EditCommand::KeepPrimarySelection);
+
+        assert_eq!(editor.get_secondary_selections(), vec![]);
+        assert_eq!(editor.get_selection(), Some((8, 11)));
+    }
+
+    #[test]
+    fn test_overlapping_selections_are_merged() {
+        let mut editor = editor_with_matches("ababab", "ab");
+
+        editor.run_edit_command(&EditCommand::MoveRight { select: true });
+
+        assert_eq!(editor.get_secondary_selections(), vec![]);
+        assert_eq!(editor.get_selection(), Some((0, 6)));
+    }
+
+    #[test]
+    fn test_undo_drops_secondary_selections() {
+        let mut editor = editor_with_matches("foo bar foo", "foo");
+        editor.run_edit_command(&EditCommand::CutSelection);
+
+        editor.run_edit_command(&EditCommand::Undo);
+
+        assert_eq!(editor.get_buffer(), "foo bar foo");
+        assert_eq!(editor.get_secondary_selections(), vec![]);
+    }
+
     fn str_to_edit_commands(s: &str) -> Vec<EditCommand> {
         s.chars().map(EditCommand::InsertChar).collect()
     }
@@ -0,0 +1,121 @@
+use std::{collections::VecDeque, ops::Range};
+
+/// Number of kills remembered by the [`KillRing`], the same as in Emacs
+const KILL_RING_MAX: usize = 120;
+
+/// Ring of killed text for Emacs style yanking
+///
+/// Consecutive kills are merged into one entry, so that repeated `C-k` or `M-d` can be
+/// yanked back at once. After a yank, [`KillRing::rotate`] steps through the earlier kills
+/// for `M-y`.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct KillRing {
+    entries: VecDeque<String>,
+    /// Entry inserted by the last yank or rotation
+    current: usize,
+    /// The last edit was a kill the next one gets merged with
+    after_kill: bool,
+    /// Range of the text inserted by the last edit if it was a yank
+    yanked: Option<Range<usize>>,
+}
+
+impl KillRing {
+    /// Store killed `text`, merging it with the previous kill if there was no other edit
+    /// in between. A `backward` kill is put in front of the previous one.
+    pub fn kill(&mut self, text: &str, backward: bool) {
+        match self.entries.front_mut() {
+            Some(entry) if self.after_kill && backward => entry.insert_str(0, text),
+            Some(entry) if self.after_kill => entry.push_str(text),
+            _ => {
+                self.entries.push_front(text.to_string());
+                self.entries.truncate(KILL_RING_MAX);
+            }
+        }
+        self.current = 0;
+        self.after_kill = true;
+    }
+
+    /// Forget about the last edit once the next one is done
+    ///
+    /// Kills and yanks keep their own state, any other edit ends a series of kills
+    /// and doesn't allow rotating the yanked text anymore.
+    pub fn finish_edit(&mut self, killed: bool, yanked: bool) {
+        if !killed {
+            self.after_kill = false;
+        }
+        if !yanked {
+            self.yanked = None;
+        }
+    }
+
+    /// The most recent kill, which the next yank inserts
+    pub fn yank(&mut self) -> Option<&str> {
+        self.current = 0;
+        self.entries.front().map(String::as_str)
+    }
+
+    /// Move on to the kill before the one yanked last, wrapping around to the most recent
+    pub fn rotate(&mut self) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.current = (self.current + 1) % self.entries.len();
+        self.entries.get(self.current).map(String::as_str)
+    }
+
+    /// The entry inserted by the last yank or rotation
+    pub fn current(&self) -> Option<&str> {
+        self.entries.get(self.current).map(String::as_str)
+    }
+
+    /// Range of the text inserted by the last edit if it was a yank
+    pub fn yanked(&self) -> Option<Range<usize>> {
+        self.yanked.clone()
+    }
+
+    pub fn set_yanked(&mut self, range: Range<usize>) {
+        self.yanked = Some(range);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn consecutive_kills_are_merged() {
+        let mut kill_ring = KillRing::default();
+        kill_ring.kill("foo ", false);
+        kill_ring.kill("bar", false);
+        kill_ring.kill("> ", true);
+
+        assert_eq!(kill_ring.yank(), Some("> foo bar"));
+        assert_eq!(kill_ring.rotate(), Some("> foo bar"));
+    }
+
+    #[test]
+    fn other_edits_end_merging() {
+        let mut kill_ring = KillRing::default();
+        kill_ring.kill("foo", false);
+        kill_ring.finish_edit(true, false);
+        kill_ring.finish_edit(false, false);
+        kill_ring.kill("bar", false);
+
+        assert_eq!(kill_ring.yank(), Some("bar"));
+        assert_eq!(kill_ring.rotate(), Some("foo"));
+        assert_eq!(kill_ring.rotate(), Some("bar"));
+    }
+
+    #[test]
+    fn ring_is_limited() {
+        let mut kill_ring = KillRing::default();
+        for kill in 0..=KILL_RING_MAX {
+            kill_ring.kill(&kill.to_string(), false);
+            kill_ring.finish_edit(false, false);
+        }
+
+        assert_eq!(kill_ring.entries.len(), KILL_RING_MAX);
+        assert_eq!(kill_ring.entries.back().map(String::as_str), Some("1"));
+    }
+}
@@ -1,9 +1,16 @@
 use {
+    crate::enums::{TextObject, TextObjectKind},
     itertools::Itertools,
-    std::{convert::From, ops::Range},
+    std::{
+        convert::From,
+        ops::{Range, RangeInclusive},
+    },
     unicode_segmentation::UnicodeSegmentation,
 };
 
+/// Indentation added to a line by [`LineBuffer::indent_lines`]
+const INDENT: &str = "    ";
+
 /// In memory representation of the entered line(s) including a cursor position to facilitate cursor based editing.
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct LineBuffer {
@@ -150,6 +157,19 @@ impl LineBuffer {
         )
     }
 
+    /// Position of the first non-whitespace character of the current line
+    ///
+    /// Falls back to the line end if the line only contains whitespace
+    pub fn line_non_blank_start_index(&self) -> usize {
+        let line_start = self.lines[..self.insertion_point]
+            .rfind('\n')
+            .map_or(0, |offset| offset + 1);
+        let line_end = self.find_current_line_end();
+        self.lines[line_start..line_end]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(line_end, |offset| line_start + offset)
+    }
+
     /// Position *behind* the next unicode grapheme to the right
     pub fn grapheme_right_index_from_pos(&self, pos: usize) -> usize {
         self.lines[pos..]
@@ -163,7 +183,7 @@ impl LineBuffer {
     pub fn grapheme_left_index_from_pos(&self, pos: usize) -> usize {
         self.lines[..pos]
             .grapheme_indices(true)
-            .last()
+            .next_back()
             .map(|(i, _)| i)
             .unwrap_or(0)
     }
@@ -214,7 +234,7 @@ impl LineBuffer {
             .unwrap_or_else(|| {
                 self.lines
                     .grapheme_indices(true)
-                    .last()
+                    .next_back()
                     .map(|x| x.0)
                     .unwrap_or(0)
             })
@@ -239,7 +259,7 @@ impl LineBuffer {
             .unwrap_or_else(|| {
                 self.lines
                     .grapheme_indices(true)
-                    .last()
+                    .next_back()
                     .map(|x| x.0)
                     .unwrap_or(0)
             })
@@ -272,8 +292,7 @@ impl LineBuffer {
     pub fn word_left_index(&self) -> usize {
         self.lines[..self.insertion_point]
             .split_word_bound_indices()
-            .filter(|(_, word)| !is_whitespace_str(word))
-            .last()
+            .rfind(|(_, word)| !is_whitespace_str(word))
             .map(|(i, _)| i)
             .unwrap_or(0)
     }
@@ -471,8 +490,7 @@ impl LineBuffer {
         let right_index = self.word_right_index();
         let left_index = self.lines[..right_index]
             .split_word_bound_indices()
-            .filter(|(_, word)| !is_whitespace_str(word))
-            .last()
+            .rfind(|(_, word)| !is_whitespace_str(word))
             .map(|(i, _)| i)
             .unwrap_or(0);
 
@@ -495,6 +513,143 @@ impl LineBuffer {
         left_index..right_index
     }
 
+    /// Position of the bracket matching the one under the cursor
+    ///
+    /// Returns `None` if the cursor isn't on a bracket or the bracket is unbalanced
+    pub fn matching_bracket_index(&self) -> Option<usize> {
+        let c = self.lines[self.insertion_point..].chars().next()?;
+        let (open, close) = match pair_delimiters(c) {
+            (open, close) if open != close => (open, close),
+            _ => return None,
+        };
+        if c == open {
+            self.find_pair_close(self.insertion_point + open.len_utf8(), open, close)
+        } else {
+            self.find_pair_open(self.insertion_point, open, close)
+        }
+    }
+
+    /// Positions of the `open` and `close` delimiters enclosing the cursor
+    ///
+    /// Brackets are matched respecting nesting, identical delimiters like quotes are
+    /// paired up from the start of the buffer.
+    /// A delimiter under the cursor counts as enclosing it.
+    pub fn surrounding_pair_indices(&self, open: char, close: char) -> Option<(usize, usize)> {
+        if open == close {
+            let positions: Vec<usize> = self
+                .lines
+                .char_indices()
+                .filter(|(_, c)| *c == open)
+                .map(|(i, _)| i)
+                .collect();
+            return positions
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .find(|(start, end)| (*start..=*end).contains(&self.insertion_point));
+        }
+
+        let start = if self.lines[self.insertion_point..].starts_with(open) {
+            self.insertion_point
+        } else {
+            self.find_pair_open(self.insertion_point, open, close)?
+        };
+        let end = self.find_pair_close(start + open.len_utf8(), open, close)?;
+        Some((start, end))
+    }
+
+    /// Range of the text object at the cursor
+    ///
+    /// Inner word objects cover the word or the whitespace under the cursor, around
+    /// additionally covers the trailing (or if there is none, the leading) whitespace.
+    /// Inner pair objects cover the text between the delimiters, around includes them.
+    pub fn text_object_range(&self, text_object: TextObject) -> Option<Range<usize>> {
+        match text_object.kind {
+            TextObjectKind::Word => {
+                let (start, word) = self
+                    .lines
+                    .split_word_bound_indices()
+                    .find(|(i, word)| i + word.len() > self.insertion_point)?;
+                Some(self.extend_word_object(start..start + word.len(), text_object.around))
+            }
+            TextObjectKind::BigWord => {
+                let on_whitespace = self.on_whitespace();
+                let (start, end) = self.big_word_bounds(on_whitespace)?;
+                Some(self.extend_word_object(start..end, text_object.around))
+            }
+            TextObjectKind::Pair(c) => {
+                let (open, close) = pair_delimiters(c);
+                let (start, end) = self.surrounding_pair_indices(open, close)?;
+                Some(if text_object.around {
+                    start..end + close.len_utf8()
+                } else {
+                    start + open.len_utf8()..end
+                })
+            }
+        }
+    }
+
+    fn big_word_bounds(&self, whitespace: bool) -> Option<(usize, usize)> {
+        if self.insertion_point >= self.lines.len() {
+            return None;
+        }
+        let start = self.lines[..self.insertion_point]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace() != whitespace)
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let end = self.lines[self.insertion_point..]
+            .char_indices()
+            .find(|(_, c)| c.is_whitespace() != whitespace)
+            .map_or(self.lines.len(), |(i, _)| self.insertion_point + i);
+        Some((start, end))
+    }
+
+    fn extend_word_object(&self, range: Range<usize>, around: bool) -> Range<usize> {
+        if !around || is_whitespace_str(&self.lines[range.clone()]) {
+            return range;
+        }
+        // Whitespace is only added from the same line
+        let is_blank = |c: char| c.is_whitespace() && c != '\n' && c != '\r';
+        let trailing = self.lines[range.end..]
+            .find(|c: char| !is_blank(c))
+            .unwrap_or(self.lines.len() - range.end);
+        if trailing > 0 {
+            range.start..range.end + trailing
+        } else {
+            self.lines[..range.start].trim_end_matches(is_blank).len()..range.end
+        }
+    }
+
+    fn find_pair_open(&self, before: usize, open: char, close: char) -> Option<usize> {
+        let mut depth = 0;
+        for (i, c) in self.lines[..before].char_indices().rev() {
+            if c == close {
+                depth += 1;
+            } else if c == open {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    fn find_pair_close(&self, from: usize, open: char, close: char) -> Option<usize> {
+        let mut depth = 0;
+        for (i, c) in self.lines[from..].char_indices() {
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    return Some(from + i);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+
     /// Uppercases the current word
     pub fn uppercase_word(&mut self) {
         let change_range = self.current_word_range();
@@ -511,6 +666,22 @@ impl LineBuffer {
         self.move_word_right();
     }
 
+    /// Capitalizes the current word, lowercasing the rest of it
+    pub fn capitalize_word(&mut self) {
+        let change_range = self.current_word_range();
+        let word = &self.get_buffer()[change_range.clone()];
+        let mut chars = word.chars();
+        let capitalized = match chars.next() {
+            Some(first) => first
+                .to_uppercase()
+                .chain(chars.as_str().to_lowercase().chars())
+                .collect(),
+            None => String::new(),
+        };
+        self.replace_range(change_range, &capitalized);
+        self.move_word_right();
+    }
+
     /// Switches the ASCII case of the current char
     pub fn switchcase_char(&mut self) {
         let insertion_offset = self.insertion_point();
@@ -777,6 +948,199 @@ impl LineBuffer {
             self.insertion_point = index + c.len_utf8();
         }
     }
+
+    /// Start offsets of the lines containing the positions in `range`
+    fn line_starts(&self, range: RangeInclusive<usize>) -> Vec<usize> {
+        let end = (*range.end()).min(self.lines.len());
+        let first = self.lines[..(*range.start()).min(end)]
+            .rfind('\n')
+            .map_or(0, |offset| offset + 1);
+        std::iter::once(first)
+            .chain(
+                self.lines[first..end]
+                    .match_indices('\n')
+                    .map(|(offset, _)| first + offset + 1),
+            )
+            .collect()
+    }
+
+    /// Indents the lines containing the positions in `range` by four spaces
+    ///
+    /// Empty lines are left alone. The insertion point stays on the same character.
+    pub fn indent_lines(&mut self, range: RangeInclusive<usize>) {
+        for line_start in self.line_starts(range).into_iter().rev() {
+            if matches!(
+                self.lines[line_start..].chars().next(),
+                None | Some('\n' | '\r')
+            ) {
+                continue;
+            }
+            self.lines.insert_str(line_start, INDENT);
+            if self.insertion_point >= line_start {
+                self.insertion_point += INDENT.len();
+            }
+        }
+    }
+
+    /// Removes one level of indentation, a tab or up to four spaces,
+    /// from the lines containing the positions in `range`
+    ///
+    /// The insertion point stays on the same character, or moves to the start of the line
+    /// if its character was removed.
+    pub fn dedent_lines(&mut self, range: RangeInclusive<usize>) {
+        for line_start in self.line_starts(range).into_iter().rev() {
+            let line = &self.lines[line_start..];
+            let removed = if line.starts_with('\t') {
+                1
+            } else {
+                line.chars()
+                    .take(INDENT.len())
+                    .take_while(|c| *c == ' ')
+                    .count()
+            };
+            self.clear_range(line_start..line_start + removed);
+            if self.insertion_point >= line_start + removed {
+                self.insertion_point -= removed;
+            } else if self.insertion_point > line_start {
+                self.insertion_point = line_start;
+            }
+        }
+    }
+
+    /// Joins the lines containing the positions in `range`, or the line of the insertion
+    /// point with the next one if the range lies on a single line
+    ///
+    /// Like in Vi the indentation of the joined lines is replaced with a single space, which
+    /// is left out if the line ends with whitespace or the joined line is empty or starts
+    /// with `)`. The insertion point moves to the last place where lines were joined.
+    pub fn join_lines(&mut self, range: RangeInclusive<usize>) {
+        let line_starts = self.line_starts(range);
+        let line_start = line_starts[0];
+        for _ in 0..line_starts.len().saturating_sub(1).max(1) {
+            let Some(newline) = self.lines[line_start..]
+                .find('\n')
+                .map(|offset| line_start + offset)
+            else {
+                break;
+            };
+            let line_end = if self.lines[..newline].ends_with('\r') {
+                newline - 1
+            } else {
+                newline
+            };
+            let next_line = &self.lines[newline + 1..];
+            let indent = next_line.len() - next_line.trim_start_matches([' ', '\t']).len();
+            let joined = &next_line[indent..];
+            let separator = if self.lines[line_start..line_end].ends_with(char::is_whitespace)
+                || line_end == line_start
+                || joined.is_empty()
+                || joined.starts_with(['\n', '\r', ')'])
+            {
+                ""
+            } else {
+                " "
+            };
+            self.replace_range(line_end..newline + 1 + indent, separator);
+            self.insertion_point = line_end;
+        }
+    }
+
+    /// Adds `amount` to the number under or after the insertion point on the current line
+    ///
+    /// Like in Vi a `-` in front of a decimal number makes it negative, numbers starting with
+    /// `0x` are hexadecimal and leading zeros keep the width of the number. The insertion
+    /// point moves to the last digit of the changed number.
+    pub fn increment_number(&mut self, amount: i64) {
+        let line = self.current_line_range();
+        let Some(number) = number_at(&self.lines[line.clone()], self.insertion_point - line.start)
+        else {
+            return;
+        };
+        let range = line.start + number.start..line.start + number.end;
+        if let Some(replacement) = add_to_number(&self.lines[range.clone()], amount) {
+            self.insertion_point = range.start + replacement.len() - 1;
+            self.lines.replace_range(range, &replacement);
+        }
+    }
+}
+
+/// Range of the first number in `line` that ends after `pos`
+fn number_at(line: &str, pos: usize) -> Option<Range<usize>> {
+    let bytes = line.as_bytes();
+    let digits_from = |start: usize, is_digit: fn(&u8) -> bool| {
+        start + bytes[start..].iter().take_while(|b| is_digit(b)).count()
+    };
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'0'
+            && matches!(bytes.get(index + 1), Some(b'x' | b'X'))
+            && matches!(bytes.get(index + 2), Some(b) if b.is_ascii_hexdigit())
+        {
+            let end = digits_from(index + 2, u8::is_ascii_hexdigit);
+            if end > pos {
+                return Some(index..end);
+            }
+            index = end;
+        } else if bytes[index].is_ascii_digit() {
+            let end = digits_from(index, u8::is_ascii_digit);
+            if end > pos {
+                let start = if index > 0 && bytes[index - 1] == b'-' {
+                    index - 1
+                } else {
+                    index
+                };
+                return Some(start..end);
+            }
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+    None
+}
+
+/// Text of `number` after adding `amount`, keeping its format
+///
+/// Hexadecimal numbers wrap around, decimal numbers that would overflow are left alone.
+fn add_to_number(number: &str, amount: i64) -> Option<String> {
+    if let Some(digits) = number
+        .strip_prefix("0x")
+        .or_else(|| number.strip_prefix("0X"))
+    {
+        let value = u64::from_str_radix(digits, 16)
+            .ok()?
+            .wrapping_add(amount as u64);
+        let width = digits.len();
+        let hex = if digits.bytes().any(|b| b.is_ascii_uppercase()) {
+            format!("{value:0width$X}")
+        } else {
+            format!("{value:0width$x}")
+        };
+        return Some(format!("{}{hex}", &number[..2]));
+    }
+
+    let digits = number.trim_start_matches('-');
+    let value = number.parse::<i64>().ok()?.checked_add(amount)?;
+    let width = if digits.len() > 1 && digits.starts_with('0') {
+        digits.len()
+    } else {
+        0
+    };
+    let sign = if value < 0 { "-" } else { "" };
+    Some(format!("{sign}{:0width$}", value.unsigned_abs()))
+}
+
+/// Opening and closing delimiter of the pair `c` belongs to
+///
+/// Characters other than brackets are paired with themselves
+pub(crate) fn pair_delimiters(c: char) -> (char, char) {
+    match c {
+        '(' | ')' => ('(', ')'),
+        '[' | ']' => ('[', ']'),
+        '{' | '}' => ('{', '}'),
+        '<' | '>' => ('<', '>'),
+        _ => (c, c),
+    }
 }
 
 /// Match any sequence of characters that are considered a word boundary
@@ -1007,6 +1371,28 @@ mod test {
         line_buffer.assert_valid();
     }
 
+    #[rstest]
+    #[case("this is a TEST", 13, "this is a Test", 14)]
+    #[case("this is a TEST", 10, "this is a Test", 14)]
+    #[case("", 0, "", 0)]
+    #[case("tHIS is", 0, "This is", 4)]
+    fn capitalize_word_works(
+        #[case] input: &str,
+        #[case] in_location: usize,
+        #[case] output: &str,
+        #[case] out_location: usize,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(in_location);
+        line_buffer.capitalize_word();
+
+        let mut expected = buffer_with(output);
+        expected.set_insertion_point(out_location);
+
+        assert_eq!(expected, line_buffer);
+        line_buffer.assert_valid();
+    }
+
     #[rstest]
     #[case("", 0, "", 0)]
     #[case("a test", 2, "a Test", 3)]
@@ -1518,6 +1904,95 @@ mod test {
         assert_eq!(index, expected,);
     }
 
+    #[rstest]
+    #[case("(abc)", 0, Some(4))]
+    #[case("(abc)", 4, Some(0))]
+    #[case("f(a, [b], (c))", 1, Some(13))]
+    #[case("f(a, [b], (c))", 5, Some(7))]
+    #[case("f(a, [b], (c))", 12, Some(10))]
+    #[case("(abc", 0, None)]
+    #[case("abc", 1, None)]
+    #[case("abc", 3, None)]
+    fn test_matching_bracket_index(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+
+        assert_eq!(line_buffer.matching_bracket_index(), expected);
+    }
+
+    #[rstest]
+    #[case("f(a, (b), c)", 3, '(', ')', Some((1, 11)))]
+    #[case("f(a, (b), c)", 6, '(', ')', Some((5, 7)))]
+    #[case("f(a, (b), c)", 5, '(', ')', Some((5, 7)))]
+    #[case("f(a, (b), c)", 7, '(', ')', Some((5, 7)))]
+    #[case("f(a, (b), c)", 0, '(', ')', None)]
+    #[case(r#"a "b" "c d""#, 8, '"', '"', Some((6, 10)))]
+    #[case(r#"a "b" "c d""#, 4, '"', '"', Some((2, 4)))]
+    #[case(r#"a "b" "c d""#, 5, '"', '"', None)]
+    fn test_surrounding_pair_indices(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] open: char,
+        #[case] close: char,
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+
+        assert_eq!(line_buffer.surrounding_pair_indices(open, close), expected);
+    }
+
+    #[rstest]
+    #[case("abc def  ghi", 5, TextObjectKind::Word, false, Some(4..7))]
+    #[case("abc def  ghi", 5, TextObjectKind::Word, true, Some(4..9))]
+    #[case("abc def", 5, TextObjectKind::Word, true, Some(3..7))]
+    #[case("abc def  ghi", 7, TextObjectKind::Word, false, Some(7..9))]
+    #[case("abc def\nghi", 5, TextObjectKind::Word, true, Some(3..7))]
+    #[case("a.b-c d", 1, TextObjectKind::BigWord, false, Some(0..5))]
+    #[case("a.b-c d", 1, TextObjectKind::BigWord, true, Some(0..6))]
+    #[case("abc", 3, TextObjectKind::Word, false, None)]
+    #[case("f(a, b)", 3, TextObjectKind::Pair(')'), false, Some(2..6))]
+    #[case("f(a, b)", 3, TextObjectKind::Pair('('), true, Some(1..7))]
+    #[case("f()", 1, TextObjectKind::Pair('('), false, Some(2..2))]
+    #[case("say 'hi'", 6, TextObjectKind::Pair('\''), false, Some(5..7))]
+    #[case("abc", 1, TextObjectKind::Pair('['), false, None)]
+    fn test_text_object_range(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] kind: TextObjectKind,
+        #[case] around: bool,
+        #[case] expected: Option<Range<usize>>,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+
+        assert_eq!(
+            line_buffer.text_object_range(TextObject { kind, around }),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("  abc", 0, 2)]
+    #[case("  abc", 5, 2)]
+    #[case("abc\n\t  def", 9, 7)]
+    #[case("abc\n   \ndef", 5, 7)]
+    #[case("", 0, 0)]
+    fn test_line_non_blank_start_index(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] expected: usize,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+
+        assert_eq!(line_buffer.line_non_blank_start_index(), expected);
+    }
+
     #[rstest]
     #[case("abc def ghi", 0, 4)]
     #[case("abc-def ghi", 0, 3)]
@@ -1607,4 +2082,93 @@ mod test {
 
         assert_eq!(index, expected);
     }
+
+    #[rstest]
+    #[case("foo\nbar", 5, 5..=5, "foo\n    bar", 9)]
+    #[case("foo\n\nbar", 1, 0..=6, "    foo\n\n    bar", 5)]
+    #[case("foo\n  bar", 8, 4..=4, "foo\n      bar", 12)]
+    fn test_indent_lines(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] range: RangeInclusive<usize>,
+        #[case] expected: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+        line_buffer.indent_lines(range);
+
+        assert_eq!(line_buffer.get_buffer(), expected);
+        assert_eq!(line_buffer.insertion_point(), expected_position);
+    }
+
+    #[rstest]
+    #[case("      foo", 7, 0..=0, "  foo", 3)]
+    #[case("\t\tfoo\n  bar", 2, 0..=8, "\tfoo\nbar", 1)]
+    #[case("  foo", 1, 0..=0, "foo", 0)]
+    #[case("foo", 1, 0..=0, "foo", 1)]
+    fn test_dedent_lines(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] range: RangeInclusive<usize>,
+        #[case] expected: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+        line_buffer.dedent_lines(range);
+
+        assert_eq!(line_buffer.get_buffer(), expected);
+        assert_eq!(line_buffer.insertion_point(), expected_position);
+    }
+
+    #[rstest]
+    #[case("foo\n    bar", 0..=0, "foo bar", 3)]
+    #[case("foo \nbar", 0..=0, "foo bar", 4)]
+    #[case("foo\n\nbar", 0..=0, "foo\nbar", 3)]
+    #[case("foo(\n)", 0..=0, "foo()", 4)]
+    #[case("a\nb\nc\nd", 0..=4, "a b c\nd", 3)]
+    #[case("a\r\nb", 0..=0, "a b", 1)]
+    #[case("foo", 0..=0, "foo", 0)]
+    fn test_join_lines(
+        #[case] input: &str,
+        #[case] range: RangeInclusive<usize>,
+        #[case] expected: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(0);
+        line_buffer.join_lines(range);
+
+        assert_eq!(line_buffer.get_buffer(), expected);
+        assert_eq!(line_buffer.insertion_point(), expected_position);
+    }
+
+    #[rstest]
+    #[case("port 8080", 0, 1, "port 8081", 8)]
+    #[case("port 8080", 7, 20, "port 8100", 8)]
+    #[case("a -5 b", 0, 7, "a 2 b", 2)]
+    #[case("a -5 b", 3, -1, "a -6 b", 3)]
+    #[case("v007", 0, 1, "v008", 3)]
+    #[case("v007", 0, -8, "v-001", 4)]
+    #[case("0xff", 0, 1, "0x100", 4)]
+    #[case("0x0F", 3, 1, "0x10", 3)]
+    #[case("0x00", 0, -1, "0xffffffffffffffff", 17)]
+    #[case("1 2\n3", 2, 1, "1 3\n3", 2)]
+    #[case("1 x\n3", 2, 1, "1 x\n3", 2)]
+    #[case("9223372036854775807", 0, 1, "9223372036854775807", 0)]
+    fn test_increment_number(
+        #[case] input: &str,
+        #[case] position: usize,
+        #[case] amount: i64,
+        #[case] expected: &str,
+        #[case] expected_position: usize,
+    ) {
+        let mut line_buffer = buffer_with(input);
+        line_buffer.set_insertion_point(position);
+        line_buffer.increment_number(amount);
+
+        assert_eq!(line_buffer.get_buffer(), expected);
+        assert_eq!(line_buffer.insertion_point(), expected_position);
+    }
 }
@@ -1,10 +1,15 @@
+mod buffer_search;
 mod clip_buffer;
 mod edit_stack;
 mod editor;
+mod kill_ring;
 mod line_buffer;
+mod registers;
 
+pub(crate) use buffer_search::BufferSearch;
 #[cfg(feature = "system_clipboard")]
 pub(crate) use clip_buffer::get_system_clipboard;
-pub(crate) use clip_buffer::{get_local_clipboard, Clipboard, ClipboardMode};
+pub(crate) use clip_buffer::{get_local_clipboard, Clipboard, ClipboardMode, CutBuffer};
 pub use editor::Editor;
 pub use line_buffer::LineBuffer;
+pub use registers::Registers;
@@ -0,0 +1,157 @@
+use super::ClipboardMode;
+use std::collections::HashMap;
+
+/// Store of named registers holding cut and copied text, following the Vi conventions
+///
+/// - `"` is the unnamed register, the cut buffer of the [`Editor`](crate::Editor)
+/// - `0` holds the last yank when no register was chosen
+/// - `1` to `9` hold the history of deletions, `1` being the most recent
+/// - `a` to `z` are named registers, choosing `A` to `Z` appends to them
+/// - `+` is the system clipboard if the `system_clipboard` feature is enabled
+#[derive(Debug, Default, Clone)]
+pub struct Registers {
+    contents: HashMap<char, (String, ClipboardMode)>,
+    selected: Option<char>,
+}
+
+impl Registers {
+    /// Check if `name` refers to a register
+    pub fn is_valid(name: char) -> bool {
+        matches!(name, '"' | '+' | '0'..='9' | 'a'..='z' | 'A'..='Z')
+    }
+
+    /// Content of the register `name`, if it has been written to
+    ///
+    /// The unnamed register and the system clipboard are owned by the
+    /// [`Editor`](crate::Editor) and are not part of the store.
+    pub fn get(&self, name: char) -> Option<&str> {
+        self.contents
+            .get(&name.to_ascii_lowercase())
+            .map(|(content, _)| content.as_str())
+    }
+
+    /// Overwrite the register `name` with `content`, appending if `name` is uppercase
+    ///
+    /// Only the numbered and named registers can be written this way.
+    pub fn set(&mut self, name: char, content: &str) {
+        if name.is_ascii_alphanumeric() {
+            self.write(name, content, ClipboardMode::Normal);
+        }
+    }
+
+    /// Register the next cut, copy or paste should use
+    pub fn selected(&self) -> Option<char> {
+        self.selected
+    }
+
+    pub(crate) fn select(&mut self, name: char) {
+        self.selected = Self::is_valid(name).then_some(name);
+    }
+
+    pub(crate) fn take_selected(&mut self) -> Option<char> {
+        self.selected.take()
+    }
+
+    pub(crate) fn get_with_mode(&self, name: char) -> Option<(String, ClipboardMode)> {
+        self.contents.get(&name.to_ascii_lowercase()).cloned()
+    }
+
+    /// Store text that was cut or copied into `register` or, without a chosen register,
+    /// into the yank register `0` or the deletion history `1` to `9`
+    pub(crate) fn record(
+        &mut self,
+        register: Option<char>,
+        content: &str,
+        mode: ClipboardMode,
+        yank: bool,
+    ) {
+        match register {
+            None | Some('"') if yank => self.write('0', content, mode),
+            None | Some('"') => {
+                for digit in (1..9).rev() {
+                    if let Some(entry) = self.contents.remove(&digit_register(digit)) {
+                        self.contents.insert(digit_register(digit + 1), entry);
+                    }
+                }
+                self.write('1', content, mode);
+            }
+            Some(name) => self.write(name, content, mode),
+        }
+    }
+
+    fn write(&mut self, name: char, content: &str, mode: ClipboardMode) {
+        if name.is_ascii_uppercase() {
+            let (existing, existing_mode) =
+                self.contents.entry(name.to_ascii_lowercase()).or_default();
+            existing.push_str(content);
+            if let ClipboardMode::Lines = mode {
+                *existing_mode = ClipboardMode::Lines;
+            }
+        } else {
+            self.contents.insert(name, (content.to_string(), mode));
+        }
+    }
+}
+
+fn digit_register(digit: u32) -> char {
+    char::from_digit(digit, 10).expect("register digits are below 10")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn yank_without_register_writes_zero() {
+        let mut registers = Registers::default();
+        registers.record(None, "yanked", ClipboardMode::Normal, true);
+
+        assert_eq!(registers.get('0'), Some("yanked"));
+        assert_eq!(registers.get('1'), None);
+    }
+
+    #[test]
+    fn deletions_shift_through_numbered_registers() {
+        let mut registers = Registers::default();
+        for deleted in ["first", "second", "third"] {
+            registers.record(None, deleted, ClipboardMode::Normal, false);
+        }
+
+        assert_eq!(registers.get('1'), Some("third"));
+        assert_eq!(registers.get('2'), Some("second"));
+        assert_eq!(registers.get('3'), Some("first"));
+        assert_eq!(registers.get('0'), None);
+    }
+
+    #[test]
+    fn deletion_history_is_limited_to_nine() {
+        let mut registers = Registers::default();
+        for deleted in 0..12 {
+            registers.record(None, &deleted.to_string(), ClipboardMode::Normal, false);
+        }
+
+        assert_eq!(registers.get('1'), Some("11"));
+        assert_eq!(registers.get('9'), Some("3"));
+    }
+
+    #[test]
+    fn uppercase_appends_to_named_register() {
+        let mut registers = Registers::default();
+        registers.record(Some('a'), "foo", ClipboardMode::Normal, true);
+        registers.record(Some('A'), "bar", ClipboardMode::Normal, false);
+
+        assert_eq!(registers.get('a'), Some("foobar"));
+        assert_eq!(registers.get('A'), Some("foobar"));
+        assert_eq!(registers.get('0'), None);
+        assert_eq!(registers.get('1'), None);
+    }
+
+    #[test]
+    fn invalid_register_is_not_selected() {
+        let mut registers = Registers::default();
+        registers.select('!');
+
+        assert_eq!(registers.selected(), None);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{core_editor::Editor, History};
+
+/// A callback run for [`ReedlineEvent::Custom`](crate::ReedlineEvent::Custom) without
+/// leaving `read_line`
+///
+/// Unlike [`ReedlineEvent::ExecuteHostCommand`](crate::ReedlineEvent::ExecuteHostCommand),
+/// the callback edits the buffer in place, so menus and the rest of the editor state are
+/// kept. It can read the history, e.g. to look at the previous entries.
+///
+/// Closures taking the [`Editor`] and the [`History`] implement this trait.
+///
+/// ## Example
+/// ```rust
+/// use reedline::{
+///     default_emacs_keybindings, CustomEventResult, Editor, Emacs, History, KeyCombination,
+///     Reedline, ReedlineEvent, UndoBehavior,
+/// };
+/// use crossterm::event::{KeyCode, KeyModifiers};
+///
+/// let mut keybindings = default_emacs_keybindings();
+/// keybindings.add_binding(
+///     KeyCombination {
+///         modifier: KeyModifiers::ALT,
+///         key_code: KeyCode::Char('s'),
+///     },
+///     vec![],
+///     ReedlineEvent::Custom("subshell".to_string()),
+/// );
+///
+/// let line_editor = Reedline::create()
+///     .with_edit_mode(Box::new(Emacs::new(keybindings)))
+///     .with_custom_event(
+///         "subshell",
+///         Box::new(|editor: &mut Editor, _history: &dyn History| {
+///             let Some((start, end)) = editor.get_selection() else {
+///                 return CustomEventResult::Message("Nothing selected".to_string());
+///             };
+///             let wrapped = format!("$({})", &editor.get_buffer()[start..end]);
+///             editor.edit_buffer(
+///                 |buffer| buffer.replace_range(start..end, &wrapped),
+///                 UndoBehavior::CreateUndoPoint,
+///             );
+///             CustomEventResult::Handled
+///         }),
+///     );
+/// ```
+pub trait CustomEvent: Send {
+    /// Run the callback on the `editor`
+    fn run(&mut self, editor: &mut Editor, history: &dyn History) -> CustomEventResult;
+}
+
+impl<F> CustomEvent for F
+where
+    F: FnMut(&mut Editor, &dyn History) -> CustomEventResult + Send,
+{
+    fn run(&mut self, editor: &mut Editor, history: &dyn History) -> CustomEventResult {
+        self(editor, history)
+    }
+}
+
+/// Outcome of a [`CustomEvent`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomEventResult {
+    /// The callback was applied, the buffer gets repainted
+    Handled,
+    /// The callback didn't apply, so that
+    /// [`ReedlineEvent::UntilFound`](crate::ReedlineEvent::UntilFound) tries the next event
+    Inapplicable,
+    /// Show the message below the buffer until the next key is pressed
+    Message(String),
+}
@@ -1,6 +1,6 @@
 use crate::{
     enums::{ReedlineEvent, ReedlineRawEvent},
-    PromptEditMode,
+    KeyCombination, PromptEditMode,
 };
 
 /// Define the style of parsing for the edit events
@@ -13,4 +13,39 @@ pub trait EditMode: Send {
 
     /// What to display in the prompt indicator
     fn edit_mode(&self) -> PromptEditMode;
+
+    /// Whether a multi-key sequence is waiting for its next key
+    fn has_pending_sequence(&self) -> bool {
+        false
+    }
+
+    /// Give up on the pending key sequence when its next key didn't arrive in time
+    ///
+    /// The keys pressed so far are replayed as if they didn't start a sequence.
+    fn cancel_pending_sequence(&mut self) -> ReedlineEvent {
+        ReedlineEvent::None
+    }
+
+    /// Text the pending key sequence inserts if it gets cancelled
+    ///
+    /// It is shown at the cursor until the sequence resolves.
+    fn provisional_text(&self) -> Option<String> {
+        None
+    }
+
+    /// Keys that can continue the pending key sequence with the event each one triggers,
+    /// `None` if the key starts a longer sequence
+    fn sequence_continuations(&self) -> Vec<(KeyCombination, Option<ReedlineEvent>)> {
+        vec![]
+    }
+
+    /// Leave the mode used to type a buffer search or an ex command line
+    ///
+    /// Called when the line editor ended the search without the edit mode, e.g. on Ctrl-C.
+    fn exit_search(&mut self) {}
+}
+
+/// Repeat the `event` `count` times, for the counts of Helix and the arguments of Emacs
+pub(crate) fn apply_multiplier(event: ReedlineEvent, count: usize) -> ReedlineEvent {
+    ReedlineEvent::Multiple(std::iter::repeat(event).take(count).collect())
 }
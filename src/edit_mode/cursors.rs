@@ -2,12 +2,21 @@ use crossterm::cursor::SetCursorStyle;
 
 /// Maps cursor shapes to each edit mode (emacs, vi normal & vi insert).
 /// If any of the fields is `None`, the cursor won't get changed by Reedline for that mode.
+///
+/// The vi visual modes use the `vi_normal` cursor unless they have one of their own.
+/// Fields get added for new modes, so fill the ones you don't set with
+/// `..Default::default()`. The visual mode fields are such an addition and break struct
+/// literals listing every field.
 #[derive(Default)]
 pub struct CursorConfig {
     /// The cursor to be used when in vi insert mode
     pub vi_insert: Option<SetCursorStyle>,
     /// The cursor to be used when in vi normal mode
     pub vi_normal: Option<SetCursorStyle>,
+    /// The cursor to be used when in vi visual line mode, `vi_normal` if `None`
+    pub vi_visual_line: Option<SetCursorStyle>,
+    /// The cursor to be used when in vi visual block mode, `vi_normal` if `None`
+    pub vi_visual_block: Option<SetCursorStyle>,
     /// The cursor to be used when in helix insert mode
     pub helix_insert: Option<SetCursorStyle>,
     /// The cursor to be used when in helix normal mode
@@ -8,16 +8,25 @@ use crate::{
         },
         EditMode,
     },
-    enums::{EditCommand, ReedlineEvent, ReedlineRawEvent},
+    enums::{EditCommand, EmacsEvent, ReedlineEvent, ReedlineRawEvent},
     PromptEditMode,
 };
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 
-use super::keybindings::{
-    to_lowercase_key_code, KeyNode, KeySequenceResult, PartialKeySequence, Sequence,
+use super::{
+    base::apply_multiplier,
+    keybindings::{
+        to_lowercase_key_code, KeyNode, KeySequenceResult, PartialKeySequence, Sequence,
+    },
 };
 
+/// Upper bound for the numeric argument, the repeated event is expanded that many times
+const MAX_ARGUMENT: usize = 1_000;
+
 /// Returns the current default emacs keybindings
+///
+/// `C-u` starts a numeric argument like in readline.
+/// It used to be bound to [`EditCommand::CutFromStart`], bind it again to keep that.
 pub fn default_emacs_keybindings() -> Keybindings {
     use EditCommand as EC;
     use KeyCode as KC;
@@ -61,6 +70,18 @@ pub fn default_emacs_keybindings() -> Keybindings {
             ReedlineEvent::Right,
         ]),
     );
+    // Search inside the buffer, pressing it again jumps to the next match
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('s'),
+        },
+        vec![],
+        ReedlineEvent::SearchBuffer {
+            backward: false,
+            select: false,
+        },
+    );
     // Undo/Redo
     kb.add_binding(
         KeyCombination {
@@ -85,39 +106,72 @@ pub fn default_emacs_keybindings() -> Keybindings {
             key_code: KC::Char('y'),
         },
         vec![],
-        edit_bind(EC::PasteCutBufferBefore),
+        edit_bind(EC::Yank),
     );
+    let ctrl_w = KeyCombination {
+        modifier: KM::CONTROL,
+        key_code: KC::Char('w'),
+    };
+    // Kills into the kill ring instead of the common word deletion
+    kb.remove_binding(ctrl_w.clone(), vec![]);
+    kb.add_binding(ctrl_w, vec![], edit_bind(EC::KillRegion));
     kb.add_binding(
         KeyCombination {
             modifier: KM::CONTROL,
-            key_code: KC::Char('w'),
+            key_code: KC::Char('k'),
         },
         vec![],
-        edit_bind(EC::CutWordLeft),
+        edit_bind(EC::CutToLineEnd),
     );
     kb.add_binding(
         KeyCombination {
-            modifier: KM::CONTROL,
-            key_code: KC::Char('k'),
+            modifier: KM::ALT,
+            key_code: KC::Char('d'),
         },
         vec![],
-        edit_bind(EC::CutToLineEnd),
+        edit_bind(EC::CutWordRight),
     );
+    // Mark and region
     kb.add_binding(
         KeyCombination {
             modifier: KM::CONTROL,
-            key_code: KC::Char('u'),
+            key_code: KC::Char(' '),
         },
         vec![],
-        edit_bind(EC::CutFromStart),
+        edit_bind(EC::SetMark),
     );
     kb.add_binding(
         KeyCombination {
-            modifier: KM::ALT,
-            key_code: KC::Char('d'),
+            modifier: KM::CONTROL,
+            key_code: KC::Char('x'),
         },
-        vec![],
-        edit_bind(EC::CutWordRight),
+        vec![KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('x'),
+        }],
+        edit_bind(EC::ExchangePointAndMark),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('x'),
+        },
+        vec![KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('u'),
+        }],
+        edit_bind(EC::UppercaseSelection),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('x'),
+        },
+        vec![KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('l'),
+        }],
+        edit_bind(EC::LowercaseSelection),
     );
     // Edits
     kb.add_binding(
@@ -128,6 +182,15 @@ pub fn default_emacs_keybindings() -> Keybindings {
         vec![],
         edit_bind(EC::SwapGraphemes),
     );
+    // Numeric arguments
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('u'),
+        },
+        vec![],
+        ReedlineEvent::Emacs(EmacsEvent::UniversalArgument),
+    );
 
     // *** ALT ***
     // Moves
@@ -169,7 +232,41 @@ pub fn default_emacs_keybindings() -> Keybindings {
             edit_bind(EC::MoveWordRight { select: false }),
         ]),
     );
-    // Edits
+    // Kill ring
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::ALT,
+            key_code: KC::Char('y'),
+        },
+        vec![],
+        edit_bind(EC::YankPop),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::ALT,
+            key_code: KC::Char('w'),
+        },
+        vec![],
+        edit_bind(EC::CopySelection),
+    );
+    // History arguments
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::ALT,
+            key_code: KC::Char('.'),
+        },
+        vec![],
+        ReedlineEvent::YankLastArg,
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL | KM::ALT,
+            key_code: KC::Char('y'),
+        },
+        vec![],
+        ReedlineEvent::YankNthArg(1),
+    );
+    // Word deletion
     kb.add_binding(
         KeyCombination {
             modifier: KM::ALT,
@@ -219,14 +316,66 @@ pub fn default_emacs_keybindings() -> Keybindings {
         vec![],
         edit_bind(EC::CapitalizeChar),
     );
+    // Numeric arguments
+    for digit in 0..=9 {
+        kb.add_binding(
+            KeyCombination {
+                modifier: KM::ALT,
+                key_code: KC::Char(char::from(b'0' + digit)),
+            },
+            vec![],
+            ReedlineEvent::Emacs(EmacsEvent::DigitArgument(digit)),
+        );
+    }
 
     kb
 }
 
+/// Numeric argument for the next command, typed with `C-u` and `M-<digit>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Argument {
+    /// Only `C-u` was typed so far, each one multiplying the argument by four
+    Universal(usize),
+    /// Digits typed so far, further digits extend the number
+    Digits(usize),
+    /// Digits ended by another `C-u`, further digits are inserted as text
+    Done(usize),
+}
+
+impl Argument {
+    fn value(self) -> usize {
+        match self {
+            Argument::Universal(value) | Argument::Digits(value) | Argument::Done(value) => value,
+        }
+    }
+
+    /// The argument after typing `C-u`
+    fn universal(argument: Option<Self>) -> Self {
+        match argument {
+            None => Argument::Universal(4),
+            Some(Argument::Universal(value)) => {
+                Argument::Universal(value.saturating_mul(4).min(MAX_ARGUMENT))
+            }
+            Some(argument) => Argument::Done(argument.value()),
+        }
+    }
+
+    /// The argument after typing the `digit`
+    fn digit(argument: Option<Self>, digit: u8) -> Self {
+        match argument {
+            Some(Argument::Digits(value)) => {
+                Argument::Digits((value * 10 + usize::from(digit)).min(MAX_ARGUMENT))
+            }
+            _ => Argument::Digits(usize::from(digit).min(MAX_ARGUMENT)),
+        }
+    }
+}
+
 /// This parses the incoming Events like a emacs style-editor
 pub struct Emacs {
     keybindings: Keybindings,
     partial_key_sequence: Option<PartialKeySequence>,
+    argument: Option<Argument>,
 }
 
 impl Default for Emacs {
@@ -234,6 +383,7 @@ impl Default for Emacs {
         Emacs {
             keybindings: default_emacs_keybindings(),
             partial_key_sequence: None,
+            argument: None,
         }
     }
 }
@@ -244,6 +394,63 @@ impl Emacs {
         Emacs {
             keybindings,
             partial_key_sequence: None,
+            argument: None,
+        }
+    }
+
+    /// Repeat the event bound to `kc` by the numeric argument, or update the argument
+    fn handle_key(&mut self, kc: KeyCombination) -> ReedlineEvent {
+        // Plain digits extend an argument started with `C-u` or `M-<digit>`
+        if let KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char(c @ '0'..='9'),
+        } = kc
+        {
+            if self.partial_key_sequence.is_none()
+                && matches!(
+                    self.argument,
+                    Some(Argument::Universal(_) | Argument::Digits(_))
+                )
+            {
+                self.argument = Some(Argument::digit(self.argument, c as u8 - b'0'));
+                return ReedlineEvent::Repaint;
+            }
+        }
+
+        match self.handle_binding(kc) {
+            Some(ReedlineEvent::Emacs(EmacsEvent::UniversalArgument)) => {
+                self.argument = Some(Argument::universal(self.argument));
+                ReedlineEvent::Repaint
+            }
+            Some(ReedlineEvent::Emacs(EmacsEvent::DigitArgument(digit))) => {
+                self.argument = Some(Argument::digit(self.argument, digit));
+                ReedlineEvent::Repaint
+            }
+            Some(event) => self.apply_argument(event),
+            // The argument applies to the whole key sequence
+            None if self.partial_key_sequence.is_some() => ReedlineEvent::None,
+            None => match self.argument.take() {
+                Some(_) => ReedlineEvent::Repaint,
+                None => ReedlineEvent::None,
+            },
+        }
+    }
+
+    /// Repeat `event` by the pending argument if there is one
+    ///
+    /// [`ReedlineEvent::YankNthArg`] takes the argument as the index of the history
+    /// argument instead, like `yank-nth-arg` in readline.
+    fn apply_argument(&mut self, event: ReedlineEvent) -> ReedlineEvent {
+        match (self.argument.take(), event) {
+            (Some(argument), ReedlineEvent::YankNthArg(_)) => ReedlineEvent::Multiple(vec![
+                ReedlineEvent::YankNthArg(argument.value()),
+                ReedlineEvent::Repaint,
+            ]),
+            (Some(argument), event) => ReedlineEvent::Multiple(vec![
+                apply_multiplier(event, argument.value()),
+                ReedlineEvent::Repaint,
+            ]),
+            (None, event) => event,
         }
     }
 
@@ -261,7 +468,8 @@ impl Emacs {
                         .find_binding(kc.modifier, to_lowercase_key_code(kc.key_code))?
                     {
                         KeyNode::Event(event) => Some(event),
-                        KeyNode::Sequence(_) => unreachable!(""),
+                        // A prefix on its own doesn't do anything
+                        KeyNode::Sequence(_) => None,
                     },
                 })
                 .collect(),
@@ -278,10 +486,25 @@ impl Emacs {
                     })
                 })
         }) else {
-            return if let KeyCode::Char(c) = kc.key_code {
-                Some(ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)]))
-            } else {
-                None
+            return match kc {
+                KeyCombination {
+                    modifier,
+                    key_code: KeyCode::Char(c),
+                } if modifier == KeyModifiers::NONE
+                    || modifier == KeyModifiers::SHIFT
+                    || modifier == KeyModifiers::CONTROL | KeyModifiers::ALT
+                    || modifier
+                        == KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT =>
+                {
+                    // Terminals usually report the shifted character already
+                    let mut upper = c.to_uppercase();
+                    let c = match (modifier == KeyModifiers::SHIFT, upper.next(), upper.next()) {
+                        (true, Some(upper), None) => upper,
+                        _ => c,
+                    };
+                    Some(ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)]))
+                }
+                _ => None,
             };
         };
 
@@ -303,12 +526,10 @@ impl EditMode for Emacs {
         match event.into() {
             Event::Key(KeyEvent {
                 code, modifiers, ..
-            }) => self
-                .handle_binding(KeyCombination {
-                    modifier: modifiers,
-                    key_code: code,
-                })
-                .unwrap_or(ReedlineEvent::None),
+            }) => self.handle_key(KeyCombination {
+                modifier: modifiers,
+                key_code: code,
+            }),
             Event::Mouse(_) => ReedlineEvent::Mouse,
             Event::Resize(width, height) => ReedlineEvent::Resize(width, height),
             Event::FocusGained => ReedlineEvent::None,
@@ -320,7 +541,37 @@ impl EditMode for Emacs {
     }
 
     fn edit_mode(&self) -> PromptEditMode {
-        PromptEditMode::Emacs
+        match self.argument {
+            Some(argument) => PromptEditMode::EmacsArgument(argument.value()),
+            None => PromptEditMode::Emacs,
+        }
+    }
+
+    fn has_pending_sequence(&self) -> bool {
+        self.partial_key_sequence.is_some()
+    }
+
+    fn cancel_pending_sequence(&mut self) -> ReedlineEvent {
+        match self.partial_key_sequence.take() {
+            Some(partial_key_sequence) => {
+                let event = self.cancel_key_sequence(partial_key_sequence.cancel());
+                self.apply_argument(event)
+            }
+            None => ReedlineEvent::None,
+        }
+    }
+
+    fn provisional_text(&self) -> Option<String> {
+        self.partial_key_sequence
+            .as_ref()
+            .and_then(PartialKeySequence::typed_text)
+    }
+
+    fn sequence_continuations(&self) -> Vec<(KeyCombination, Option<ReedlineEvent>)> {
+        self.partial_key_sequence
+            .as_ref()
+            .map(PartialKeySequence::continuations)
+            .unwrap_or_default()
     }
 }
 
@@ -431,4 +682,237 @@ mod test {
             ReedlineEvent::Edit(vec![EditCommand::InsertChar('😀')])
         );
     }
+
+    #[test]
+    fn mark_bindings_work() {
+        let mut emacs = Emacs::default();
+        let mut ctrl = |c| {
+            emacs.parse_event(
+                ReedlineRawEvent::try_from(Event::Key(KeyEvent::new(
+                    KeyCode::Char(c),
+                    KeyModifiers::CONTROL,
+                )))
+                .unwrap(),
+            )
+        };
+
+        assert_eq!(ctrl(' '), ReedlineEvent::Edit(vec![EditCommand::SetMark]));
+        assert_eq!(ctrl('x'), ReedlineEvent::None);
+        assert_eq!(
+            ctrl('x'),
+            ReedlineEvent::Edit(vec![EditCommand::ExchangePointAndMark])
+        );
+        assert_eq!(
+            ctrl('w'),
+            ReedlineEvent::Edit(vec![EditCommand::KillRegion])
+        );
+    }
+
+    fn key(emacs: &mut Emacs, modifier: KeyModifiers, c: char) -> ReedlineEvent {
+        emacs.parse_event(
+            ReedlineRawEvent::try_from(Event::Key(KeyEvent::new(KeyCode::Char(c), modifier)))
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn digit_argument_repeats_the_next_event() {
+        let mut emacs = Emacs::default();
+
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::ALT, '1'),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::NONE, '2'),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(emacs.edit_mode(), PromptEditMode::EmacsArgument(12));
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::CONTROL, 'd'),
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Multiple(vec![ReedlineEvent::CtrlD; 12]),
+                ReedlineEvent::Repaint
+            ])
+        );
+        assert_eq!(emacs.edit_mode(), PromptEditMode::Emacs);
+    }
+
+    #[test]
+    fn universal_argument_multiplies_by_four() {
+        let mut emacs = Emacs::default();
+        key(&mut emacs, KeyModifiers::CONTROL, 'u');
+        key(&mut emacs, KeyModifiers::CONTROL, 'u');
+
+        assert_eq!(emacs.edit_mode(), PromptEditMode::EmacsArgument(16));
+        let ReedlineEvent::Multiple(events) = key(&mut emacs, KeyModifiers::CONTROL, 'f') else {
+            panic!("the argument is not applied");
+        };
+        let ReedlineEvent::Multiple(moves) = &events[0] else {
+            panic!("the event is not repeated");
+        };
+        assert_eq!(moves.len(), 16);
+    }
+
+    #[test]
+    fn argument_is_capped() {
+        let mut emacs = Emacs::default();
+        key(&mut emacs, KeyModifiers::ALT, '9');
+        for _ in 0..6 {
+            key(&mut emacs, KeyModifiers::NONE, '9');
+        }
+        assert_eq!(
+            emacs.edit_mode(),
+            PromptEditMode::EmacsArgument(MAX_ARGUMENT)
+        );
+
+        let mut emacs = Emacs::default();
+        for _ in 0..12 {
+            key(&mut emacs, KeyModifiers::CONTROL, 'u');
+        }
+        assert_eq!(
+            emacs.edit_mode(),
+            PromptEditMode::EmacsArgument(MAX_ARGUMENT)
+        );
+    }
+
+    #[test]
+    fn universal_argument_ends_the_digits() {
+        let mut emacs = Emacs::default();
+        key(&mut emacs, KeyModifiers::CONTROL, 'u');
+        key(&mut emacs, KeyModifiers::NONE, '3');
+        key(&mut emacs, KeyModifiers::CONTROL, 'u');
+
+        assert_eq!(emacs.edit_mode(), PromptEditMode::EmacsArgument(3));
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::NONE, '0'),
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Multiple(vec![
+                    ReedlineEvent::Edit(vec![EditCommand::InsertChar(
+                        '0'
+                    )]);
+                    3
+                ]),
+                ReedlineEvent::Repaint
+            ])
+        );
+    }
+
+    #[test]
+    fn digit_argument_is_the_index_of_the_yanked_argument() {
+        let mut emacs = Emacs::default();
+        key(&mut emacs, KeyModifiers::ALT, '3');
+
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::CONTROL | KeyModifiers::ALT, 'y'),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::YankNthArg(3), ReedlineEvent::Repaint])
+        );
+    }
+
+    #[test]
+    fn unbound_key_cancels_the_argument() {
+        let mut emacs = Emacs::default();
+        key(&mut emacs, KeyModifiers::ALT, '5');
+
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::ALT, 'q'),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(emacs.edit_mode(), PromptEditMode::Emacs);
+    }
+
+    #[test]
+    fn unbound_key_after_a_prefix_runs_the_key() {
+        let mut keybindings = default_emacs_keybindings();
+        keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('x'),
+            },
+            vec![KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('r'),
+            }],
+            ReedlineEvent::Esc,
+        );
+        let mut emacs = Emacs::new(keybindings);
+
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::CONTROL, 'x'),
+            ReedlineEvent::None
+        );
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::CONTROL, 'f'),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::UntilFound(vec![
+                ReedlineEvent::HistoryHintComplete,
+                ReedlineEvent::MenuRight,
+                ReedlineEvent::Right,
+            ])])
+        );
+        assert!(!emacs.has_pending_sequence());
+    }
+
+    #[test]
+    fn timed_out_sequence_inserts_the_typed_keys() {
+        let mut keybindings = default_emacs_keybindings();
+        keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('j'),
+            },
+            vec![KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('k'),
+            }],
+            ReedlineEvent::Esc,
+        );
+        let mut emacs = Emacs::new(keybindings);
+
+        assert_eq!(
+            key(&mut emacs, KeyModifiers::NONE, 'j'),
+            ReedlineEvent::None
+        );
+        assert!(emacs.has_pending_sequence());
+        assert_eq!(emacs.provisional_text(), Some("j".to_string()));
+        assert_eq!(
+            emacs.cancel_pending_sequence(),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::InsertChar(
+                'j'
+            )])])
+        );
+        assert!(!emacs.has_pending_sequence());
+        assert_eq!(emacs.provisional_text(), None);
+    }
+
+    #[test]
+    fn timed_out_nested_sequence_replays_the_keys() {
+        let mut keybindings = default_emacs_keybindings();
+        keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('x'),
+            },
+            vec![
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('t'),
+                },
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('b'),
+                },
+            ],
+            ReedlineEvent::Esc,
+        );
+        let mut emacs = Emacs::new(keybindings);
+
+        key(&mut emacs, KeyModifiers::CONTROL, 'x');
+        key(&mut emacs, KeyModifiers::CONTROL, 't');
+        assert!(emacs.has_pending_sequence());
+        assert_eq!(
+            emacs.cancel_pending_sequence(),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::SwapGraphemes])])
+        );
+        assert!(!emacs.has_pending_sequence());
+    }
 }
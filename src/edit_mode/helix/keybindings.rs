@@ -19,14 +19,13 @@ pub fn default_helix_normal_keybindings() -> Keybindings {
     add_common_navigation_bindings(&mut kb);
     add_common_selection_bindings(&mut kb);
 
-    kb.add_binding(
-        KeyCombination {
-            modifier: KeyModifiers::NONE,
-            key_code: KeyCode::Esc,
-        },
-        vec![],
-        ReedlineEvent::Helix(HelixEvent::NormalMode),
-    );
+    let esc = KeyCombination {
+        modifier: KeyModifiers::NONE,
+        key_code: KeyCode::Esc,
+    };
+    // Replaces the common binding, Esc only ever returns to normal mode
+    kb.remove_binding(esc.clone(), vec![]);
+    kb.add_binding(esc, vec![], ReedlineEvent::Helix(HelixEvent::NormalMode));
 
     kb.add_binding(
         KeyCombination {
@@ -136,6 +135,308 @@ pub fn default_helix_normal_keybindings() -> Keybindings {
         ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::MoveNextLongWordEnd)),
     );
 
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('d'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Delete)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('c'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Change)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('y'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Yank)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('p'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::PasteAfter)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::SHIFT,
+            key_code: KeyCode::Char('p'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::PasteBefore)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('r'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Replace)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::SHIFT,
+            key_code: KeyCode::Char('r'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::ReplaceWithYanked)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('f'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::FindNextChar)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('t'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::TillNextChar)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::SHIFT,
+            key_code: KeyCode::Char('f'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::FindPrevChar)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::SHIFT,
+            key_code: KeyCode::Char('t'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::TillPrevChar)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::ALT,
+            key_code: KeyCode::Char('.'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::RepeatLastMotion)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::SHIFT,
+            key_code: KeyCode::Char('c'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::CopySelectionOnNextLine)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('s'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SelectRegex)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char(','),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::KeepPrimarySelection)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('/'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SearchForward)),
+    );
+
+    // Not every terminal reports Shift for symbols
+    for modifier in [KeyModifiers::NONE, KeyModifiers::SHIFT] {
+        kb.add_binding(
+            KeyCombination {
+                modifier,
+                key_code: KeyCode::Char('?'),
+            },
+            vec![],
+            ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SearchBackward)),
+        );
+    }
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('n'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SearchNext)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::SHIFT,
+            key_code: KeyCode::Char('n'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SearchPrevious)),
+    );
+
+    // Goto mode
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('h'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::GotoLineStart)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('l'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::GotoLineEnd)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('s'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::GotoFirstNonWhitespace)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('e'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::GotoLastLine)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::GotoFileStart)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('k'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::MoveLineUp)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('g'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('j'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::MoveLineDown)),
+    );
+
+    // View mode
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('z'),
+        },
+        vec![KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('t'),
+        }],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::AlignViewTop)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('m'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::MatchMode)),
+    );
+
+    let ctrl_a = KeyCombination {
+        modifier: KeyModifiers::CONTROL,
+        key_code: KeyCode::Char('a'),
+    };
+    // Increments like in Helix instead of the common move to the line start
+    kb.remove_binding(ctrl_a.clone(), vec![]);
+    kb.add_binding(
+        ctrl_a,
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Increment)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::CONTROL,
+            key_code: KeyCode::Char('x'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Decrement)),
+    );
+
     kb
 }
 
@@ -148,13 +449,73 @@ pub fn default_helix_insert_keybindings() -> Keybindings {
     add_common_edit_bindings(&mut kb);
     add_common_selection_bindings(&mut kb);
 
+    let esc = KeyCombination {
+        modifier: KeyModifiers::NONE,
+        key_code: KeyCode::Esc,
+    };
+    // Replaces the common binding, Esc only ever returns to normal mode
+    kb.remove_binding(esc.clone(), vec![]);
+    kb.add_binding(esc, vec![], ReedlineEvent::Helix(HelixEvent::NormalMode));
+
+    kb
+}
+
+/// Default Helix match mode keybindings, active after `m` for a single command
+pub fn default_helix_match_keybindings() -> Keybindings {
+    let mut kb = Keybindings::new();
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('m'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::MatchBracket)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('s'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SurroundAdd)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('r'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SurroundReplace)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('d'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SurroundDelete)),
+    );
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KeyModifiers::NONE,
+            key_code: KeyCode::Char('i'),
+        },
+        vec![],
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SelectTextObjectInside)),
+    );
+
     kb.add_binding(
         KeyCombination {
             modifier: KeyModifiers::NONE,
-            key_code: KeyCode::Esc,
+            key_code: KeyCode::Char('a'),
         },
         vec![],
-        ReedlineEvent::Helix(HelixEvent::NormalMode),
+        ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::SelectTextObjectAround)),
     );
 
     kb
@@ -2,20 +2,27 @@ mod commands;
 mod keybindings;
 use std::{collections::HashMap, num::NonZeroUsize};
 
-use itertools::Itertools;
-pub use keybindings::{default_helix_insert_keybindings, default_helix_normal_keybindings};
+pub use keybindings::{
+    default_helix_insert_keybindings, default_helix_match_keybindings,
+    default_helix_normal_keybindings,
+};
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 
 use super::{
+    base::apply_multiplier,
     keybindings::{
         to_lowercase_key_code, KeyNode, KeySequenceResult, PartialKeySequence, Sequence,
     },
+    vi::ViCharSearch,
     EditMode, KeyCombination,
 };
 use crate::{
     edit_mode::keybindings::Keybindings,
-    enums::{EditCommand, HelixEvent, HelixNormal, ReedlineEvent, ReedlineRawEvent},
+    enums::{
+        EditCommand, HelixEvent, HelixNormal, ReedlineEvent, ReedlineRawEvent, TextObject,
+        TextObjectKind,
+    },
     PromptEditMode, PromptHelixMode,
 };
 
@@ -23,6 +30,8 @@ use crate::{
 enum Mode {
     Normal(Option<MinorMode>),
     Insert,
+    /// Typing the term of a buffer search started with `/` or `?`
+    Search,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -31,12 +40,37 @@ enum MinorMode {
     Match,
 }
 
+/// Commands that wait for the next typed character as their argument
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum PendingChar {
+    Replace,
+    Surround,
+    ReplaceSurroundFrom,
+    ReplaceSurroundTo(char),
+    DeleteSurround,
+    SelectInside,
+    SelectAround,
+    FindNextChar(usize),
+    TillNextChar(usize),
+    FindPrevChar(usize),
+    TillPrevChar(usize),
+}
+
 /// This parses incoming input `Event`s like a Helix-Style editor
 pub struct Helix {
     insert_keybindings: Keybindings,
     normal_keybindings: Keybindings,
+    match_keybindings: Keybindings,
     mode: Mode,
+    /// Whether match mode returns to select mode
+    select_after_match: bool,
     count: Option<NonZeroUsize>,
+    pending_char: Option<PendingChar>,
+    /// Keys that entered match mode or the pending char command, as typed
+    command_keys: Vec<KeyCombination>,
+    /// Regex typed so far for selecting matches, confirmed with Enter
+    pending_regex: Option<String>,
+    last_char_search: Option<ViCharSearch>,
     partial_key_sequence: Option<PartialKeySequence>,
 }
 
@@ -45,8 +79,14 @@ impl Default for Helix {
         Self {
             insert_keybindings: Keybindings::default(),
             normal_keybindings: Keybindings::default(),
+            match_keybindings: Keybindings::default(),
             mode: Mode::Insert,
+            select_after_match: false,
             count: None,
+            pending_char: None,
+            command_keys: vec![],
+            pending_regex: None,
+            last_char_search: None,
             partial_key_sequence: None,
         }
     }
@@ -58,27 +98,39 @@ impl Helix {
         Self {
             insert_keybindings,
             normal_keybindings,
+            match_keybindings: default_helix_match_keybindings(),
             ..Default::default()
         }
     }
 
+    /// Replaces the keybindings of match mode, entered with `m` by default
+    #[must_use]
+    pub fn with_match_keybindings(mut self, match_keybindings: Keybindings) -> Self {
+        self.match_keybindings = match_keybindings;
+        self
+    }
+
     fn set_mode(&mut self, mode: Mode) {
         self.mode = mode;
         self.count = None;
+        self.pending_char = None;
+        self.pending_regex = None;
         self.partial_key_sequence = None;
     }
 
+    fn leave_match_mode(&mut self) {
+        self.mode = Mode::Normal(self.select_after_match.then_some(MinorMode::Select));
+    }
+
     fn active_bindings(&self) -> &Keybindings {
         match self.mode {
+            Mode::Normal(Some(MinorMode::Match)) => &self.match_keybindings,
             Mode::Normal(_) => &self.normal_keybindings,
-            Mode::Insert => &self.insert_keybindings,
+            Mode::Insert | Mode::Search => &self.insert_keybindings,
         }
     }
 
-    fn cancel_key_sequence(
-        &mut self,
-        keycombinations: Vec<KeyCombination>,
-    ) -> Option<ReedlineEvent> {
+    fn cancel_key_sequence(&mut self, keycombinations: Vec<KeyCombination>) -> ReedlineEvent {
         self.count = None;
         let events: Vec<ReedlineEvent> = keycombinations
             .into_iter()
@@ -86,26 +138,194 @@ impl Helix {
                 KeyCombination {
                     modifier: KeyModifiers::SHIFT | KeyModifiers::NONE,
                     key_code: KeyCode::Char(c),
-                } => matches!(self.mode, Mode::Insert)
-                    .then(|| ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)])),
+                } if matches!(self.mode, Mode::Insert) => {
+                    Some(ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)]))
+                }
                 _ => match self
                     .active_bindings()
                     .find_binding(kc.modifier, to_lowercase_key_code(kc.key_code))?
                 {
                     KeyNode::Event(ReedlineEvent::Helix(event)) => self.handle_helix_event(event),
                     KeyNode::Event(event) => Some(event),
-                    KeyNode::Sequence(_) => unreachable!(""),
+                    // A prefix on its own doesn't do anything
+                    KeyNode::Sequence(_) => None,
                 },
             })
             .collect();
 
-        (!events.is_empty()).then(|| ReedlineEvent::Multiple(events))
+        // Repaint even if nothing was replayed to clear the pending keys from the prompt
+        if events.is_empty() {
+            ReedlineEvent::Repaint
+        } else {
+            ReedlineEvent::Multiple(events)
+        }
+    }
+
+    fn pending_keys(&self) -> String {
+        let command_keys =
+            if self.pending_char.is_some() || self.mode == Mode::Normal(Some(MinorMode::Match)) {
+                self.command_keys.as_slice()
+            } else {
+                &[]
+            };
+        let keys = command_keys
+            .iter()
+            .chain(
+                self.partial_key_sequence
+                    .iter()
+                    .flat_map(|partial| partial.keys()),
+            )
+            .map(|kc| match kc {
+                KeyCombination {
+                    modifier: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                    key_code: KeyCode::Char(c),
+                } => c.to_string(),
+                kc => format!("<{kc}>"),
+            });
+        let replaced = match self.pending_char {
+            Some(PendingChar::ReplaceSurroundTo(from)) => Some(from.to_string()),
+            _ => None,
+        };
+        keys.chain(replaced).collect()
+    }
+
+    fn handle_search_key(&mut self, kc: KeyCombination) -> ReedlineEvent {
+        match kc {
+            KeyCombination {
+                modifier: KeyModifiers::SHIFT | KeyModifiers::NONE,
+                key_code: KeyCode::Char(c),
+            } => ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)]),
+            KeyCombination {
+                key_code: KeyCode::Backspace,
+                ..
+            } => ReedlineEvent::Edit(vec![EditCommand::Backspace]),
+            KeyCombination {
+                key_code: key_code @ (KeyCode::Enter | KeyCode::Esc),
+                ..
+            } => {
+                self.set_mode(Mode::Normal(None));
+                let event = if key_code == KeyCode::Enter {
+                    ReedlineEvent::Enter
+                } else {
+                    ReedlineEvent::Esc
+                };
+                ReedlineEvent::Multiple(vec![event, ReedlineEvent::Repaint])
+            }
+            _ => match self
+                .insert_keybindings
+                .find_binding(kc.modifier, to_lowercase_key_code(kc.key_code))
+            {
+                Some(KeyNode::Event(event)) => event,
+                _ => ReedlineEvent::None,
+            },
+        }
+    }
+
+    fn handle_pending_regex(&mut self, mut regex: String, kc: KeyCombination) -> ReedlineEvent {
+        match kc {
+            KeyCombination {
+                key_code: KeyCode::Enter,
+                ..
+            } => ReedlineEvent::Edit(vec![EditCommand::SelectRegexMatches(regex)]),
+            KeyCombination {
+                modifier: KeyModifiers::SHIFT | KeyModifiers::NONE,
+                key_code: KeyCode::Char(c),
+            } => {
+                regex.push(c);
+                self.pending_regex = Some(regex);
+                ReedlineEvent::Repaint
+            }
+            KeyCombination {
+                key_code: KeyCode::Backspace,
+                ..
+            } => {
+                regex.pop();
+                self.pending_regex = Some(regex);
+                ReedlineEvent::Repaint
+            }
+            // Any other key cancels the prompt
+            _ => ReedlineEvent::Repaint,
+        }
+    }
+
+    fn handle_pending_char(&mut self, pending: PendingChar, c: char) -> ReedlineEvent {
+        match pending {
+            PendingChar::Replace => {
+                self.set_mode(Mode::Normal(None));
+                ReedlineEvent::Edit(vec![EditCommand::ReplaceSelectionChars(c)])
+            }
+            PendingChar::Surround => ReedlineEvent::Edit(vec![EditCommand::SurroundSelection(c)]),
+            PendingChar::ReplaceSurroundFrom => {
+                self.pending_char = Some(PendingChar::ReplaceSurroundTo(c));
+                ReedlineEvent::Repaint
+            }
+            PendingChar::ReplaceSurroundTo(from) => {
+                ReedlineEvent::Edit(vec![EditCommand::ReplaceSurrounding(from, c)])
+            }
+            PendingChar::DeleteSurround => {
+                ReedlineEvent::Edit(vec![EditCommand::DeleteSurrounding(c)])
+            }
+            PendingChar::SelectInside | PendingChar::SelectAround => {
+                let kind = match c {
+                    'w' => TextObjectKind::Word,
+                    'W' => TextObjectKind::BigWord,
+                    c => TextObjectKind::Pair(c),
+                };
+                ReedlineEvent::Edit(vec![EditCommand::SelectTextObject(TextObject {
+                    kind,
+                    around: pending == PendingChar::SelectAround,
+                })])
+            }
+            PendingChar::FindNextChar(count) => self.char_search(ViCharSearch::ToRight(c), count),
+            PendingChar::TillNextChar(count) => self.char_search(ViCharSearch::TillRight(c), count),
+            PendingChar::FindPrevChar(count) => self.char_search(ViCharSearch::ToLeft(c), count),
+            PendingChar::TillPrevChar(count) => self.char_search(ViCharSearch::TillLeft(c), count),
+        }
+    }
+
+    /// Select up to the `count`th occurrence of the searched char
+    ///
+    /// Normal mode starts a new selection at the cursor, select mode extends the existing one.
+    fn char_search(&mut self, search: ViCharSearch, count: usize) -> ReedlineEvent {
+        let (c, right, before_char) = match search {
+            ViCharSearch::ToRight(c) => (c, true, false),
+            ViCharSearch::TillRight(c) => (c, true, true),
+            ViCharSearch::ToLeft(c) => (c, false, false),
+            ViCharSearch::TillLeft(c) => (c, false, true),
+        };
+        self.last_char_search = Some(search);
+        ReedlineEvent::Edit(vec![EditCommand::SelectToChar {
+            c,
+            right,
+            before_char,
+            count,
+            extend: self.mode == Mode::Normal(Some(MinorMode::Select)),
+        }])
     }
 
     fn handle_binding(&mut self, kc: KeyCombination) -> Option<ReedlineEvent> {
+        if let Mode::Search = self.mode {
+            return Some(self.handle_search_key(kc));
+        }
+
+        if let Some(regex) = self.pending_regex.take() {
+            return Some(self.handle_pending_regex(regex, kc));
+        }
+
+        if let Some(pending) = self.pending_char.take() {
+            // Any key other than a plain character cancels the pending command
+            return match kc {
+                KeyCombination {
+                    modifier: KeyModifiers::SHIFT | KeyModifiers::NONE,
+                    key_code: KeyCode::Char(c),
+                } => Some(self.handle_pending_char(pending, c)),
+                _ => Some(ReedlineEvent::Repaint),
+            };
+        }
+
         if matches!(kc.key_code, KeyCode::Esc) {
             return if let Some(partial) = self.partial_key_sequence.take() {
-                self.cancel_key_sequence(partial.cancel())
+                Some(self.cancel_key_sequence(partial.cancel()))
             } else {
                 self.handle_helix_event(HelixEvent::NormalMode)
             };
@@ -120,6 +340,12 @@ impl Helix {
                     })
                 })
         }) else {
+            // A key without a match command leaves match mode
+            if self.mode == Mode::Normal(Some(MinorMode::Match)) {
+                self.leave_match_mode();
+                return Some(ReedlineEvent::Repaint);
+            }
+
             if let KeyCode::Char(c) = kc.key_code {
                 if let Mode::Insert = self.mode {
                     return Some(ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)]));
@@ -144,17 +370,40 @@ impl Helix {
 
             return None;
         };
-        match partial_key_sequence.advance(kc) {
+        let in_match_mode = self.mode == Mode::Normal(Some(MinorMode::Match));
+        let result = partial_key_sequence.advance(kc);
+        if let KeySequenceResult::Matched(_) = result {
+            // Match mode keeps the keys that entered it
+            if !in_match_mode {
+                self.command_keys.clear();
+            }
+            self.command_keys
+                .extend_from_slice(partial_key_sequence.keys());
+        }
+        match result {
             KeySequenceResult::Pending => {
                 self.partial_key_sequence = Some(partial_key_sequence);
-                None
+                // Let the prompt show the pending keys
+                Some(ReedlineEvent::Repaint)
+            }
+            // Match mode only lasts for a single command
+            KeySequenceResult::Matched(event) if in_match_mode => {
+                self.leave_match_mode();
+                match event {
+                    ReedlineEvent::Helix(event) => self.handle_helix_event(event),
+                    event => Some(event),
+                }
             }
             KeySequenceResult::Matched(ReedlineEvent::Helix(event)) => {
                 self.handle_helix_event(event)
             }
             KeySequenceResult::Matched(reedline_event) => Some(reedline_event),
             KeySequenceResult::Cancelled(keycombinations) => {
-                self.cancel_key_sequence(keycombinations)
+                let event = self.cancel_key_sequence(keycombinations);
+                if in_match_mode {
+                    self.leave_match_mode();
+                }
+                Some(event)
             }
         }
     }
@@ -236,6 +485,150 @@ impl Helix {
                             ])),
                             count,
                         ),
+                        HelixNormal::Delete => {
+                            self.set_mode(Mode::Normal(None));
+                            ReedlineEvent::Edit(cut_counted_selection(count))
+                        }
+                        HelixNormal::Change => {
+                            self.set_mode(Mode::Insert);
+                            ReedlineEvent::Multiple(vec![
+                                ReedlineEvent::Edit(cut_counted_selection(count)),
+                                ReedlineEvent::Repaint,
+                            ])
+                        }
+                        HelixNormal::Yank => {
+                            self.set_mode(Mode::Normal(None));
+                            ReedlineEvent::Edit(vec![
+                                EditCommand::EnsureSelection,
+                                EditCommand::CopySelection,
+                            ])
+                        }
+                        HelixNormal::PasteAfter => apply_multiplier(
+                            ReedlineEvent::Edit(vec![EditCommand::PasteAfterSelection]),
+                            count,
+                        ),
+                        HelixNormal::PasteBefore => apply_multiplier(
+                            ReedlineEvent::Edit(vec![EditCommand::PasteBeforeSelection]),
+                            count,
+                        ),
+                        HelixNormal::Replace => {
+                            self.pending_char = Some(PendingChar::Replace);
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::ReplaceWithYanked => {
+                            self.set_mode(Mode::Normal(None));
+                            ReedlineEvent::Edit(vec![
+                                EditCommand::EnsureSelection,
+                                EditCommand::Paste,
+                            ])
+                        }
+                        HelixNormal::GotoLineStart => {
+                            ReedlineEvent::Edit(vec![EditCommand::MoveToLineStart { select }])
+                        }
+                        HelixNormal::GotoLineEnd => {
+                            ReedlineEvent::Edit(vec![EditCommand::MoveToLineEnd { select }])
+                        }
+                        HelixNormal::GotoFirstNonWhitespace => {
+                            ReedlineEvent::Edit(vec![EditCommand::MoveToLineNonBlankStart {
+                                select,
+                            }])
+                        }
+                        HelixNormal::GotoLastLine => {
+                            ReedlineEvent::Edit(vec![EditCommand::MoveToEnd { select }])
+                        }
+                        HelixNormal::GotoFileStart => {
+                            ReedlineEvent::Edit(vec![EditCommand::MoveToStart { select }])
+                        }
+                        HelixNormal::MoveLineUp => apply_multiplier(
+                            ReedlineEvent::Edit(vec![EditCommand::MoveLineUp { select }]),
+                            count,
+                        ),
+                        HelixNormal::MoveLineDown => apply_multiplier(
+                            ReedlineEvent::Edit(vec![EditCommand::MoveLineDown { select }]),
+                            count,
+                        ),
+                        HelixNormal::AlignViewTop => ReedlineEvent::ClearScreen,
+                        HelixNormal::MatchMode => {
+                            self.set_mode(Mode::Normal(Some(MinorMode::Match)));
+                            self.select_after_match = select;
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::MatchBracket => {
+                            ReedlineEvent::Edit(vec![EditCommand::MoveToMatchingBracket { select }])
+                        }
+                        HelixNormal::SurroundAdd => {
+                            self.pending_char = Some(PendingChar::Surround);
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::SurroundReplace => {
+                            self.pending_char = Some(PendingChar::ReplaceSurroundFrom);
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::SurroundDelete => {
+                            self.pending_char = Some(PendingChar::DeleteSurround);
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::SelectTextObjectInside => {
+                            self.pending_char = Some(PendingChar::SelectInside);
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::SelectTextObjectAround => {
+                            self.pending_char = Some(PendingChar::SelectAround);
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::FindNextChar => {
+                            self.pending_char = Some(PendingChar::FindNextChar(count));
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::TillNextChar => {
+                            self.pending_char = Some(PendingChar::TillNextChar(count));
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::FindPrevChar => {
+                            self.pending_char = Some(PendingChar::FindPrevChar(count));
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::TillPrevChar => {
+                            self.pending_char = Some(PendingChar::TillPrevChar(count));
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::CopySelectionOnNextLine => apply_multiplier(
+                            ReedlineEvent::Edit(vec![EditCommand::CopySelectionToNextLine]),
+                            count,
+                        ),
+                        HelixNormal::SelectRegex => {
+                            self.pending_regex = Some(String::new());
+                            ReedlineEvent::Repaint
+                        }
+                        HelixNormal::KeepPrimarySelection => {
+                            ReedlineEvent::Edit(vec![EditCommand::KeepPrimarySelection])
+                        }
+                        HelixNormal::SearchForward | HelixNormal::SearchBackward => {
+                            self.set_mode(Mode::Search);
+                            ReedlineEvent::Multiple(vec![
+                                ReedlineEvent::SearchBuffer {
+                                    backward: helix_normal == HelixNormal::SearchBackward,
+                                    select: true,
+                                },
+                                ReedlineEvent::Repaint,
+                            ])
+                        }
+                        HelixNormal::SearchNext => {
+                            apply_multiplier(ReedlineEvent::SearchBufferNext, count)
+                        }
+                        HelixNormal::SearchPrevious => {
+                            apply_multiplier(ReedlineEvent::SearchBufferPrevious, count)
+                        }
+                        HelixNormal::Increment => {
+                            ReedlineEvent::Edit(vec![EditCommand::IncrementNumber(count as i64)])
+                        }
+                        HelixNormal::Decrement => {
+                            ReedlineEvent::Edit(vec![EditCommand::DecrementNumber(count as i64)])
+                        }
+                        HelixNormal::RepeatLastMotion => match self.last_char_search.clone() {
+                            Some(search) => self.char_search(search, count),
+                            None => ReedlineEvent::None,
+                        },
                     }
                 } else {
                     ReedlineEvent::None
@@ -247,16 +640,20 @@ impl Helix {
     }
 }
 
+/// Cut the selection extended by `count - 1` graphemes at once, so the cut buffer keeps all of it
+fn cut_counted_selection(count: usize) -> Vec<EditCommand> {
+    std::iter::once(EditCommand::EnsureSelection)
+        .chain(std::iter::repeat(EditCommand::MoveRight { select: true }).take(count - 1))
+        .chain(std::iter::once(EditCommand::CutSelection))
+        .collect()
+}
+
 fn apply_select(events: &[EditCommand]) -> Vec<EditCommand> {
     let mut events = events.to_vec();
     events.insert(events.len() - 1, EditCommand::ClearSelection);
     events
 }
 
-fn apply_multiplier(event: ReedlineEvent, count: usize) -> ReedlineEvent {
-    ReedlineEvent::Multiple(std::iter::repeat(event).take(count).collect())
-}
-
 impl EditMode for Helix {
     fn parse_event(&mut self, event: ReedlineRawEvent) -> ReedlineEvent {
         match event.into() {
@@ -279,9 +676,649 @@ impl EditMode for Helix {
     }
 
     fn edit_mode(&self) -> PromptEditMode {
+        match (self.mode, &self.pending_regex) {
+            (Mode::Normal(_), Some(regex)) => {
+                PromptEditMode::Helix(PromptHelixMode::Pending(format!("select:{regex}")))
+            }
+            (Mode::Normal(minor_mode), None)
+                if self.partial_key_sequence.is_some()
+                    || self.pending_char.is_some()
+                    || minor_mode == Some(MinorMode::Match) =>
+            {
+                PromptEditMode::Helix(PromptHelixMode::Pending(self.pending_keys()))
+            }
+            (Mode::Normal(_), None) => PromptEditMode::Helix(PromptHelixMode::Normal),
+            (Mode::Insert | Mode::Search, _) => PromptEditMode::Helix(PromptHelixMode::Insert),
+        }
+    }
+
+    fn has_pending_sequence(&self) -> bool {
+        self.partial_key_sequence.is_some() || self.mode == Mode::Normal(Some(MinorMode::Match))
+    }
+
+    fn cancel_pending_sequence(&mut self) -> ReedlineEvent {
+        let event = match self.partial_key_sequence.take() {
+            Some(partial_key_sequence) => self.cancel_key_sequence(partial_key_sequence.cancel()),
+            None => ReedlineEvent::None,
+        };
+        if self.mode == Mode::Normal(Some(MinorMode::Match)) {
+            self.leave_match_mode();
+        }
+        event
+    }
+
+    fn provisional_text(&self) -> Option<String> {
         match self.mode {
-            Mode::Normal(_) => PromptEditMode::Helix(PromptHelixMode::Normal),
-            Mode::Insert => PromptEditMode::Helix(PromptHelixMode::Insert),
+            Mode::Insert => self
+                .partial_key_sequence
+                .as_ref()
+                .and_then(PartialKeySequence::typed_text),
+            _ => None,
+        }
+    }
+
+    fn sequence_continuations(&self) -> Vec<(KeyCombination, Option<ReedlineEvent>)> {
+        match (&self.partial_key_sequence, self.mode) {
+            (Some(partial_key_sequence), _) => partial_key_sequence.continuations(),
+            (None, Mode::Normal(Some(MinorMode::Match))) => {
+                PartialKeySequence::new(self.match_keybindings.bindings.clone()).continuations()
+            }
+            (None, _) => vec![],
+        }
+    }
+
+    fn exit_search(&mut self) {
+        if self.mode == Mode::Search {
+            self.set_mode(Mode::Normal(None));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{core_editor::Editor, UndoBehavior};
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn normal_mode() -> Helix {
+        Helix {
+            insert_keybindings: default_helix_insert_keybindings(),
+            normal_keybindings: default_helix_normal_keybindings(),
+            match_keybindings: default_helix_match_keybindings(),
+            mode: Mode::Normal(None),
+            ..Default::default()
+        }
+    }
+
+    fn key_press(helix: &mut Helix, modifier: KeyModifiers, key_code: KeyCode) -> ReedlineEvent {
+        helix.parse_event(
+            ReedlineRawEvent::try_from(Event::Key(KeyEvent::new(key_code, modifier))).unwrap(),
+        )
+    }
+
+    #[rstest]
+    #[case('h', false, EditCommand::MoveToLineStart { select: false })]
+    #[case('l', false, EditCommand::MoveToLineEnd { select: false })]
+    #[case('s', false, EditCommand::MoveToLineNonBlankStart { select: false })]
+    #[case('e', false, EditCommand::MoveToEnd { select: false })]
+    #[case('g', false, EditCommand::MoveToStart { select: false })]
+    #[case('h', true, EditCommand::MoveToLineStart { select: true })]
+    #[case('e', true, EditCommand::MoveToEnd { select: true })]
+    fn goto_mode(#[case] c: char, #[case] select_mode: bool, #[case] expected: EditCommand) {
+        let mut helix = normal_mode();
+        if select_mode {
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('v'));
+        }
+
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('g')),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Pending("g".to_string()))
+        );
+
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char(c));
+
+        assert_eq!(result, ReedlineEvent::Edit(vec![expected]));
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Normal)
+        );
+    }
+
+    #[test]
+    fn goto_line_down_respects_count() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('2'));
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('g'));
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('j'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Edit(vec![EditCommand::MoveLineDown { select: false }]),
+                ReedlineEvent::Edit(vec![EditCommand::MoveLineDown { select: false }]),
+            ])
+        );
+    }
+
+    #[rstest]
+    #[case('a', EditCommand::IncrementNumber(3))]
+    #[case('x', EditCommand::DecrementNumber(3))]
+    fn increment_respects_count(#[case] c: char, #[case] expected: EditCommand) {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('3'));
+        let result = key_press(&mut helix, KeyModifiers::CONTROL, KeyCode::Char(c));
+
+        assert_eq!(result, ReedlineEvent::Edit(vec![expected]));
+    }
+
+    #[test]
+    fn escape_cancels_pending_goto() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('g'));
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Esc);
+
+        assert_eq!(result, ReedlineEvent::Repaint);
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Normal)
+        );
+    }
+
+    #[test]
+    fn timeout_cancels_pending_goto() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('g'));
+
+        assert!(helix.has_pending_sequence());
+        assert_eq!(helix.provisional_text(), None);
+        assert_eq!(helix.cancel_pending_sequence(), ReedlineEvent::Repaint);
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Normal)
+        );
+    }
+
+    #[test]
+    fn unknown_goto_key_replays_its_binding() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('g'));
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('w'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(
+                vec![
+                    EditCommand::ClearSelection,
+                    EditCommand::MoveWordRight { select: true }
+                ]
+            )])])
+        );
+    }
+
+    #[test]
+    fn match_replace_surround_takes_two_chars() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Pending("m".to_string()))
+        );
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('r'));
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('('));
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Pending("mr(".to_string()))
+        );
+
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('['));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![EditCommand::ReplaceSurrounding('(', '[')])
+        );
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Normal)
+        );
+    }
+
+    #[test]
+    fn match_mode_returns_to_select_mode() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('v'));
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+        assert_eq!(helix.mode, Mode::Normal(Some(MinorMode::Match)));
+
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![EditCommand::MoveToMatchingBracket { select: true }])
+        );
+        assert_eq!(helix.mode, Mode::Normal(Some(MinorMode::Select)));
+    }
+
+    #[test]
+    fn unbound_key_leaves_match_mode() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+        assert!(helix.has_pending_sequence());
+        assert_eq!(helix.sequence_continuations().len(), 6);
+
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('x'));
+        assert_eq!(result, ReedlineEvent::Repaint);
+        assert_eq!(helix.mode, Mode::Normal(None));
+        assert!(!helix.has_pending_sequence());
+    }
+
+    #[rstest]
+    #[case('s', '"', EditCommand::SurroundSelection('"'))]
+    #[case('d', '{', EditCommand::DeleteSurrounding('{'))]
+    #[case('i', 'w', EditCommand::SelectTextObject(TextObject { kind: TextObjectKind::Word, around: false }))]
+    #[case('a', 'W', EditCommand::SelectTextObject(TextObject { kind: TextObjectKind::BigWord, around: true }))]
+    #[case('i', '(', EditCommand::SelectTextObject(TextObject { kind: TextObjectKind::Pair('('), around: false }))]
+    fn match_mode_with_char(#[case] command: char, #[case] c: char, #[case] expected: EditCommand) {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char(command));
+
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char(c));
+
+        assert_eq!(result, ReedlineEvent::Edit(vec![expected]));
+    }
+
+    #[test]
+    fn match_bracket_respects_select_mode() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('v'));
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('m'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![EditCommand::MoveToMatchingBracket { select: true }])
+        );
+    }
+
+    fn apply_event(editor: &mut Editor, event: ReedlineEvent) {
+        match event {
+            ReedlineEvent::Edit(commands) => {
+                for command in &commands {
+                    editor.run_edit_command(command);
+                }
+            }
+            ReedlineEvent::Multiple(events) => {
+                for event in events {
+                    apply_event(editor, event);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn type_keys(helix: &mut Helix, editor: &mut Editor, keys: &[(KeyModifiers, char)]) {
+        for (modifier, c) in keys {
+            let event = key_press(helix, *modifier, KeyCode::Char(*c));
+            apply_event(editor, event);
         }
     }
+
+    #[rstest]
+    #[case(&[(KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x')], Some((0, 5)))]
+    #[case(&[(KeyModifiers::NONE, '2'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x')], Some((0, 10)))]
+    #[case(&[(KeyModifiers::NONE, 't'), (KeyModifiers::NONE, 'x')], Some((0, 4)))]
+    #[case(&[(KeyModifiers::NONE, '3'), (KeyModifiers::NONE, 't'), (KeyModifiers::NONE, 'x')], Some((0, 14)))]
+    #[case(&[(KeyModifiers::NONE, 't'), (KeyModifiers::NONE, 'x'), (KeyModifiers::ALT, '.')], Some((3, 9)))]
+    #[case(&[(KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x'), (KeyModifiers::ALT, '.')], Some((4, 10)))]
+    #[case(&[(KeyModifiers::NONE, 'v'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x'), (KeyModifiers::ALT, '.')], Some((0, 10)))]
+    #[case(&[(KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'q')], None)]
+    #[case(&[(KeyModifiers::NONE, 'v'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'q')], Some((0, 5)))]
+    #[case(&[(KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'q')], Some((0, 5)))]
+    #[case(&[(KeyModifiers::NONE, '2'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x'), (KeyModifiers::SHIFT, 'F'), (KeyModifiers::NONE, 'a')], Some((5, 10)))]
+    #[case(&[(KeyModifiers::NONE, '2'), (KeyModifiers::NONE, 'f'), (KeyModifiers::NONE, 'x'), (KeyModifiers::SHIFT, 'T'), (KeyModifiers::NONE, 'a')], Some((6, 10)))]
+    fn char_search_selects(
+        #[case] keys: &[(KeyModifiers, char)],
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        let mut helix = normal_mode();
+        let mut editor = Editor::default();
+        editor.set_buffer("abcdxabcdxabcdx".to_string(), UndoBehavior::CreateUndoPoint);
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+
+        type_keys(&mut helix, &mut editor, keys);
+
+        assert_eq!(editor.get_selection(), expected);
+    }
+
+    #[test]
+    fn backwards_char_search_extends_in_select_mode() {
+        let mut helix = normal_mode();
+        let mut editor = Editor::default();
+        editor.set_buffer("(a b) c d".to_string(), UndoBehavior::CreateUndoPoint);
+
+        type_keys(
+            &mut helix,
+            &mut editor,
+            &[
+                (KeyModifiers::SHIFT, 'T'),
+                (KeyModifiers::NONE, 'c'),
+                (KeyModifiers::NONE, 'v'),
+                (KeyModifiers::SHIFT, 'F'),
+                (KeyModifiers::NONE, '('),
+            ],
+        );
+
+        assert_eq!(editor.get_selection(), Some((0, 9)));
+    }
+
+    #[test]
+    fn view_align_top_clears_screen() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('z'));
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('t'));
+
+        assert_eq!(result, ReedlineEvent::ClearScreen);
+    }
+
+    #[rstest]
+    #[case(KeyModifiers::NONE, 'p', vec![EditCommand::PasteAfterSelection])]
+    #[case(KeyModifiers::SHIFT, 'P', vec![EditCommand::PasteBeforeSelection])]
+    fn operators_respect_count(
+        #[case] modifier: KeyModifiers,
+        #[case] c: char,
+        #[case] commands: Vec<EditCommand>,
+    ) {
+        let mut helix = normal_mode();
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('2')),
+            ReedlineEvent::None
+        );
+        let result = key_press(&mut helix, modifier, KeyCode::Char(c));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Edit(commands.clone()),
+                ReedlineEvent::Edit(commands)
+            ])
+        );
+        assert_eq!(helix.mode, Mode::Normal(None));
+    }
+
+    #[rstest]
+    #[case('d', "def", Mode::Normal(None))]
+    #[case('c', "def", Mode::Insert)]
+    fn counted_cut_takes_the_extended_selection(
+        #[case] c: char,
+        #[case] expected: &str,
+        #[case] expected_mode: Mode,
+    ) {
+        let mut helix = normal_mode();
+        let mut editor = Editor::default();
+        editor.set_buffer("abcdef".to_string(), UndoBehavior::CreateUndoPoint);
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+
+        type_keys(
+            &mut helix,
+            &mut editor,
+            &[(KeyModifiers::NONE, '3'), (KeyModifiers::NONE, c)],
+        );
+        assert_eq!(editor.get_buffer(), expected);
+        assert_eq!(helix.mode, expected_mode);
+
+        editor.run_edit_command(&EditCommand::PasteCutBufferBefore);
+        assert_eq!(editor.get_buffer(), "abcdef");
+    }
+
+    #[test]
+    fn yank_leaves_select_mode() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('v'));
+        assert_eq!(helix.mode, Mode::Normal(Some(MinorMode::Select)));
+
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('y'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![
+                EditCommand::EnsureSelection,
+                EditCommand::CopySelection
+            ])
+        );
+        assert_eq!(helix.mode, Mode::Normal(None));
+    }
+
+    #[test]
+    fn change_enters_insert_mode() {
+        let mut helix = normal_mode();
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('c'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Edit(vec![
+                    EditCommand::EnsureSelection,
+                    EditCommand::CutSelection
+                ]),
+                ReedlineEvent::Repaint
+            ])
+        );
+        assert_eq!(helix.mode, Mode::Insert);
+    }
+
+    #[test]
+    fn pending_keys_follow_the_keybindings() {
+        let mut helix = normal_mode();
+        helix.normal_keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('x'),
+            },
+            vec![KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('r'),
+            }],
+            ReedlineEvent::Helix(HelixEvent::Normal(HelixNormal::Replace)),
+        );
+
+        key_press(&mut helix, KeyModifiers::CONTROL, KeyCode::Char('x'));
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('r'));
+
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Pending("<C-x>r".to_string()))
+        );
+    }
+
+    #[test]
+    fn replace_waits_for_char() {
+        let mut helix = normal_mode();
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('r')),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Pending("r".to_string()))
+        );
+        let result = key_press(&mut helix, KeyModifiers::SHIFT, KeyCode::Char('X'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![EditCommand::ReplaceSelectionChars('X')])
+        );
+        // The following key is handled normally again
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('i')),
+            ReedlineEvent::Repaint
+        );
+    }
+
+    #[test]
+    fn replace_is_cancelled_by_non_char_key() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('r'));
+
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Esc),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(helix.pending_char, None);
+    }
+
+    #[test]
+    fn replace_with_yanked_pastes_over_selection() {
+        let mut helix = normal_mode();
+        let result = key_press(&mut helix, KeyModifiers::SHIFT, KeyCode::Char('R'));
+
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![EditCommand::EnsureSelection, EditCommand::Paste])
+        );
+    }
+
+    #[test]
+    fn select_regex_prompts_for_pattern() {
+        let mut helix = normal_mode();
+        let mut editor = Editor::default();
+        editor.set_buffer("foo bar foo".to_string(), UndoBehavior::CreateUndoPoint);
+        editor.run_edit_command(&EditCommand::SelectAll);
+
+        type_keys(
+            &mut helix,
+            &mut editor,
+            &[
+                (KeyModifiers::NONE, 's'),
+                (KeyModifiers::NONE, 'f'),
+                (KeyModifiers::NONE, 'o'),
+                (KeyModifiers::NONE, 'x'),
+            ],
+        );
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Backspace);
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Pending("select:fo".to_string()))
+        );
+        let result = key_press(&mut helix, KeyModifiers::NONE, KeyCode::Enter);
+        assert_eq!(
+            result,
+            ReedlineEvent::Edit(vec![EditCommand::SelectRegexMatches("fo".to_string())])
+        );
+        apply_event(&mut editor, result);
+
+        assert_eq!(editor.get_selection(), Some((8, 10)));
+        assert_eq!(editor.get_secondary_selections(), vec![(0, 2)]);
+        assert_eq!(
+            helix.edit_mode(),
+            PromptEditMode::Helix(PromptHelixMode::Normal)
+        );
+    }
+
+    #[test]
+    fn select_regex_is_cancelled_by_escape() {
+        let mut helix = normal_mode();
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('s'));
+
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Esc),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(helix.pending_regex, None);
+    }
+
+    #[test]
+    fn copy_selection_and_keep_primary() {
+        let mut helix = normal_mode();
+        let mut editor = Editor::default();
+        editor.set_buffer("ab\ncd\nef".to_string(), UndoBehavior::CreateUndoPoint);
+        editor.run_edit_command(&EditCommand::MoveToStart { select: false });
+
+        type_keys(
+            &mut helix,
+            &mut editor,
+            &[(KeyModifiers::NONE, '2'), (KeyModifiers::SHIFT, 'C')],
+        );
+        assert_eq!(editor.insertion_point(), 6);
+        assert_eq!(editor.get_secondary_selections(), vec![(0, 1), (3, 4)]);
+
+        type_keys(&mut helix, &mut editor, &[(KeyModifiers::NONE, ',')]);
+        assert_eq!(editor.insertion_point(), 6);
+        assert_eq!(editor.get_secondary_selections(), vec![]);
+    }
+
+    #[test]
+    fn search_types_term_until_enter() {
+        let mut helix = normal_mode();
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::SHIFT, KeyCode::Char('?')),
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::SearchBuffer {
+                    backward: true,
+                    select: true
+                },
+                ReedlineEvent::Repaint
+            ])
+        );
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('n')),
+            ReedlineEvent::Edit(vec![EditCommand::InsertChar('n')])
+        );
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Enter),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Enter, ReedlineEvent::Repaint])
+        );
+        assert_eq!(helix.mode, Mode::Normal(None));
+
+        key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('2'));
+        assert_eq!(
+            key_press(&mut helix, KeyModifiers::NONE, KeyCode::Char('n')),
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::SearchBufferNext,
+                ReedlineEvent::SearchBufferNext
+            ])
+        );
+    }
+
+    #[test]
+    fn timed_out_nested_sequence_replays_the_keys() {
+        let mut helix = normal_mode();
+        helix.normal_keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('x'),
+            },
+            vec![
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('t'),
+                },
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('b'),
+                },
+            ],
+            ReedlineEvent::Esc,
+        );
+        helix.normal_keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('t'),
+            },
+            vec![],
+            ReedlineEvent::Edit(vec![EditCommand::SwapGraphemes]),
+        );
+
+        key_press(&mut helix, KeyModifiers::CONTROL, KeyCode::Char('x'));
+        key_press(&mut helix, KeyModifiers::CONTROL, KeyCode::Char('t'));
+        assert!(helix.has_pending_sequence());
+        assert_eq!(
+            helix.cancel_pending_sequence(),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::SwapGraphemes])])
+        );
+        assert!(!helix.has_pending_sequence());
+    }
 }
@@ -0,0 +1,216 @@
+use {
+    super::{KeyCombination, KeyNode, KeySequence, Sequence},
+    crate::enums::ReedlineEvent,
+    crossterm::event::{KeyCode, KeyModifiers},
+    std::fmt::{Display, Formatter},
+};
+
+/// Problem with a set of [`Keybindings`](super::Keybindings), reported by
+/// [`Keybindings::conflicts`](super::Keybindings::conflicts)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeybindingConflict {
+    /// Binding the same keys again replaced the earlier event
+    Shadowed {
+        /// Keys bound twice
+        keys: KeySequence,
+        /// Event of the earlier binding, which is lost
+        hidden: ReedlineEvent,
+        /// Event the keys are bound to now
+        event: ReedlineEvent,
+    },
+    /// Binding a longer sequence starting with `prefix` replaced the binding of the
+    /// prefix itself
+    PrefixHidesBinding {
+        /// Keys that now only start the longer sequences
+        prefix: KeySequence,
+        /// Event the prefix was bound to before
+        hidden: ReedlineEvent,
+    },
+    /// Binding `keys` to an event replaced the longer sequences starting with them
+    BindingHidesSequences {
+        /// Keys bound to an event now
+        keys: KeySequence,
+        /// Sequences that were bound before
+        hidden: Vec<KeySequence>,
+    },
+    /// Without the kitty keyboard protocol, terminals send the same input for `key` as
+    /// for `reported_as`, so the binding can't be triggered as intended
+    Indistinguishable {
+        /// Keys of the binding containing the key
+        keys: KeySequence,
+        /// Key as it is bound
+        key: KeyCombination,
+        /// Key that is received instead
+        reported_as: KeyCombination,
+    },
+}
+
+impl Display for KeybindingConflict {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            KeybindingConflict::Shadowed {
+                keys,
+                hidden,
+                event,
+            } => write!(f, "`{keys}` is bound to {event:?}, replacing {hidden:?}"),
+            KeybindingConflict::PrefixHidesBinding { prefix, hidden } => write!(
+                f,
+                "`{prefix}` starts a key sequence, its binding to {hidden:?} is lost"
+            ),
+            KeybindingConflict::BindingHidesSequences { keys, hidden } => {
+                let hidden: Vec<String> = hidden.iter().map(|keys| format!("`{keys}`")).collect();
+                write!(
+                    f,
+                    "`{keys}` is bound to an event, replacing the sequences {}",
+                    hidden.join(", ")
+                )
+            }
+            KeybindingConflict::Indistinguishable {
+                keys,
+                key,
+                reported_as,
+            } => {
+                write!(f, "`{key}` ")?;
+                if keys.0.len() > 1 {
+                    write!(f, "in `{keys}` ")?;
+                }
+                write!(
+                    f,
+                    "is received as `{reported_as}` without the kitty protocol"
+                )
+            }
+        }
+    }
+}
+
+/// Record the bindings lost when `node` at `keys` gets replaced by `other`
+pub(super) fn replacement_conflicts(
+    keys: &[KeyCombination],
+    node: &KeyNode,
+    other: &KeyNode,
+    conflicts: &mut Vec<KeybindingConflict>,
+) {
+    let keys = KeySequence(keys.to_vec());
+    match (node, other) {
+        (KeyNode::Event(hidden), KeyNode::Event(event)) if hidden != event => {
+            conflicts.push(KeybindingConflict::Shadowed {
+                keys,
+                hidden: hidden.clone(),
+                event: event.clone(),
+            });
+        }
+        (KeyNode::Event(_), KeyNode::Event(_)) => {}
+        (KeyNode::Event(hidden), KeyNode::Sequence(_)) => {
+            conflicts.push(KeybindingConflict::PrefixHidesBinding {
+                prefix: keys,
+                hidden: hidden.clone(),
+            });
+        }
+        (KeyNode::Sequence(sequence), _) => {
+            let mut hidden = vec![];
+            collect_sequences(sequence, &mut keys.0.clone(), &mut hidden);
+            hidden.sort_by_cached_key(ToString::to_string);
+            conflicts.push(KeybindingConflict::BindingHidesSequences { keys, hidden });
+        }
+    }
+}
+
+fn collect_sequences(
+    sequence: &Sequence,
+    prefix: &mut Vec<KeyCombination>,
+    sequences: &mut Vec<KeySequence>,
+) {
+    for (key_combination, node) in &sequence.map {
+        prefix.push(key_combination.clone());
+        match node {
+            KeyNode::Event(_) => sequences.push(KeySequence(prefix.clone())),
+            KeyNode::Sequence(inner) => collect_sequences(inner, prefix, sequences),
+        }
+        prefix.pop();
+    }
+}
+
+/// Find the bound keys a terminal without the kitty protocol reports as another key
+pub(super) fn indistinguishable_keys(
+    sequence: &Sequence,
+    prefix: &mut Vec<KeyCombination>,
+    conflicts: &mut Vec<KeybindingConflict>,
+) {
+    for (key, node) in &sequence.map {
+        prefix.push(key.clone());
+        if let Some(reported_as) = legacy_key(key) {
+            conflicts.push(KeybindingConflict::Indistinguishable {
+                keys: KeySequence(prefix.clone()),
+                key: key.clone(),
+                reported_as,
+            });
+        }
+        if let KeyNode::Sequence(inner) = node {
+            indistinguishable_keys(inner, prefix, conflicts);
+        }
+        prefix.pop();
+    }
+}
+
+/// The key a terminal without the kitty protocol reports for `key`, if it differs
+///
+/// Control characters only exist for some keys and can't carry Shift, special keys
+/// mostly lose their modifiers. Alt is sent as a leading escape and keeps working.
+fn legacy_key(key: &KeyCombination) -> Option<KeyCombination> {
+    use KeyCode as KC;
+    use KeyModifiers as KM;
+
+    let alt = key.modifier & KM::ALT;
+    let modifier = key.modifier - KM::ALT;
+    let (modifier, key_code) = match (modifier, key.key_code) {
+        (KM::CONTROL, KC::Char('i')) => (KM::NONE, KC::Tab),
+        (KM::CONTROL, KC::Char('m')) => (KM::NONE, KC::Enter),
+        (KM::CONTROL, KC::Char('[')) => (KM::NONE, KC::Esc),
+        (KM::CONTROL, KC::Char('@' | '2')) => (KM::CONTROL, KC::Char(' ')),
+        (KM::CONTROL, KC::Char('\\')) => (KM::CONTROL, KC::Char('4')),
+        (KM::CONTROL, KC::Char(']')) => (KM::CONTROL, KC::Char('5')),
+        (KM::CONTROL, KC::Char('^')) => (KM::CONTROL, KC::Char('6')),
+        (KM::CONTROL, KC::Char('_' | '/')) => (KM::CONTROL, KC::Char('7')),
+        (KM::CONTROL, KC::Backspace) => (KM::CONTROL, KC::Char('h')),
+        (modifier, KC::Char(c))
+            if modifier == KM::CONTROL | KM::SHIFT && c.is_ascii_alphabetic() =>
+        {
+            (KM::CONTROL, KC::Char(c.to_ascii_lowercase()))
+        }
+        (KM::SHIFT, KC::Char(' ')) => (KM::NONE, KC::Char(' ')),
+        (KM::SHIFT, KC::Tab) => (KM::SHIFT, KC::BackTab),
+        (modifier, KC::Enter | KC::Esc | KC::Backspace | KC::Tab) if !modifier.is_empty() => {
+            (KM::NONE, key.key_code)
+        }
+        _ => return None,
+    };
+    Some(KeyCombination {
+        modifier: modifier | alt,
+        key_code,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("C-i", Some("Tab"))]
+    #[case("M-C-m", Some("M-Enter"))]
+    #[case("C-[", Some("Esc"))]
+    #[case("C-]", Some("C-5"))]
+    #[case("C-S-a", Some("C-a"))]
+    #[case("S-Enter", Some("Enter"))]
+    #[case("C-Backspace", Some("C-h"))]
+    #[case("C-a", None)]
+    #[case("M-Enter", None)]
+    #[case("BackTab", None)]
+    fn test_legacy_key(#[case] key: &str, #[case] expected: Option<&str>) {
+        let key: KeyCombination = key.parse().unwrap();
+        let expected = expected.map(|expected| expected.parse().unwrap());
+
+        assert_eq!(legacy_key(&key), expected);
+    }
+}
@@ -0,0 +1,860 @@
+use {
+    super::{edit_bind, KeyCombination, Keybindings},
+    crate::{
+        edit_mode::{
+            default_emacs_keybindings, default_vi_insert_keybindings,
+            default_vi_normal_keybindings, EditMode, Emacs, Vi,
+        },
+        enums::{EditCommand, EmacsEvent, ReedlineEvent},
+    },
+    crossterm::event::{KeyCode, KeyModifiers},
+    std::{
+        fmt::{Display, Formatter},
+        iter::Peekable,
+        str::Chars,
+    },
+};
+
+/// Edit mode selected in an inputrc file with `set editing-mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputrcEditingMode {
+    /// `set editing-mode emacs`, the default of readline
+    #[default]
+    Emacs,
+    /// `set editing-mode vi`
+    Vi,
+}
+
+/// A line of an inputrc file that couldn't be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputrcIssue {
+    /// Number of the line, starting at 1
+    pub line: usize,
+    /// Why the line was skipped
+    pub reason: String,
+}
+
+impl Display for InputrcIssue {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Keybindings read from a GNU readline `.inputrc` file
+///
+/// Key sequences like `"\C-x\C-e"` or `Meta-Rubout` are bound to the readline functions
+/// reedline has an equivalent for, or to a macro inserting plain text. The bindings are added to the
+/// keymap selected with `set editing-mode` or `set keymap`, `$if mode=...` and `$if term=...`
+/// blocks are evaluated like readline does. `complete` opens the menu named `completion_menu`.
+///
+/// Lines that can't be applied, like unsupported functions or variables, are collected in
+/// [`Inputrc::issues`] instead of failing the whole file.
+///
+/// ## Example
+/// ```rust
+/// use reedline::{Inputrc, Reedline};
+///
+/// let inputrc = Inputrc::parse(
+///     r#"
+/// set editing-mode vi
+/// $if mode=vi
+/// "\C-x\C-e": edit-and-execute-command
+/// $endif
+/// "#,
+/// );
+/// for issue in &inputrc.issues {
+///     eprintln!("{issue}");
+/// }
+/// let line_editor = Reedline::create().with_edit_mode(inputrc.into_edit_mode());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Inputrc {
+    /// Edit mode selected with `set editing-mode`
+    pub editing_mode: InputrcEditingMode,
+    /// Bindings of the `emacs` keymap
+    pub emacs: Keybindings,
+    /// Bindings of the `vi-insert` keymap
+    pub vi_insert: Keybindings,
+    /// Bindings of the `vi-command` keymap
+    pub vi_normal: Keybindings,
+    /// Lines that couldn't be applied
+    pub issues: Vec<InputrcIssue>,
+}
+
+impl Default for Inputrc {
+    /// The default keybindings of reedline, without any inputrc lines applied
+    fn default() -> Self {
+        Inputrc {
+            editing_mode: InputrcEditingMode::default(),
+            emacs: default_emacs_keybindings(),
+            vi_insert: default_vi_insert_keybindings(),
+            vi_normal: default_vi_normal_keybindings(),
+            issues: vec![],
+        }
+    }
+}
+
+/// Readline keymap the bindings are added to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keymap {
+    Emacs,
+    /// Emacs bindings prefixed with `ESC`
+    EmacsMeta,
+    /// Emacs bindings prefixed with `C-x`
+    EmacsCtlx,
+    ViInsert,
+    ViCommand,
+}
+
+/// `$if` block the parser is in
+struct Condition {
+    /// The surrounding block is applied
+    parent_active: bool,
+    matched: bool,
+    in_else: bool,
+}
+
+/// State while reading the lines of a file
+struct ParserState {
+    keymap: Keymap,
+    conditions: Vec<Condition>,
+}
+
+impl ParserState {
+    fn active(&self) -> bool {
+        self.conditions.last().map_or(true, |condition| {
+            condition.parent_active && condition.matched != condition.in_else
+        })
+    }
+}
+
+impl Inputrc {
+    /// Read the content of an inputrc file on top of the default keybindings of reedline
+    pub fn parse(content: &str) -> Self {
+        let mut inputrc = Inputrc::default();
+        inputrc.read(content);
+        inputrc
+    }
+
+    /// Apply the lines of an inputrc file to the current keybindings
+    pub fn read(&mut self, content: &str) {
+        let mut state = ParserState {
+            keymap: self.default_keymap(),
+            conditions: vec![],
+        };
+        let mut line_count = 0;
+        for (idx, line) in content.lines().enumerate() {
+            line_count = idx + 1;
+            if let Err(reason) = self.read_line(&mut state, line) {
+                self.issues.push(InputrcIssue {
+                    line: line_count,
+                    reason,
+                });
+            }
+        }
+        if !state.conditions.is_empty() {
+            self.issues.push(InputrcIssue {
+                line: line_count,
+                reason: "missing $endif".to_string(),
+            });
+        }
+    }
+
+    /// The edit mode selected with `set editing-mode`, using the read keybindings
+    pub fn into_edit_mode(self) -> Box<dyn EditMode> {
+        match self.editing_mode {
+            InputrcEditingMode::Emacs => Box::new(Emacs::new(self.emacs)),
+            InputrcEditingMode::Vi => Box::new(Vi::new(self.vi_insert, self.vi_normal)),
+        }
+    }
+
+    fn default_keymap(&self) -> Keymap {
+        match self.editing_mode {
+            InputrcEditingMode::Emacs => Keymap::Emacs,
+            InputrcEditingMode::Vi => Keymap::ViInsert,
+        }
+    }
+
+    fn read_line(&mut self, state: &mut ParserState, line: &str) -> Result<(), String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        if let Some(directive) = line.strip_prefix('$') {
+            let (name, args) = directive
+                .split_once(char::is_whitespace)
+                .unwrap_or((directive, ""));
+            return match name {
+                "if" => {
+                    let parent_active = state.active();
+                    state.conditions.push(Condition {
+                        parent_active,
+                        matched: self.condition(args.trim()),
+                        in_else: false,
+                    });
+                    Ok(())
+                }
+                "else" => match state.conditions.last_mut() {
+                    Some(condition) => {
+                        condition.in_else = true;
+                        Ok(())
+                    }
+                    None => Err("$else without $if".to_string()),
+                },
+                "endif" => match state.conditions.pop() {
+                    Some(_) => Ok(()),
+                    None => Err("$endif without $if".to_string()),
+                },
+                "include" if state.active() => Err(format!(
+                    "$include is not supported, read {} separately",
+                    args.trim()
+                )),
+                "include" => Ok(()),
+                _ => Err(format!("unknown directive ${name}")),
+            };
+        }
+
+        if !state.active() {
+            return Ok(());
+        }
+        match line.split_once(char::is_whitespace) {
+            Some(("set", setting)) => self.set(state, setting.trim()),
+            _ => self.bind(state.keymap, line),
+        }
+    }
+
+    /// Whether the test of an `$if` holds
+    ///
+    /// Application names and versions never match, reedline is neither of them.
+    fn condition(&self, test: &str) -> bool {
+        if let Some(mode) = test.strip_prefix("mode=") {
+            match mode {
+                "emacs" => self.editing_mode == InputrcEditingMode::Emacs,
+                "vi" => self.editing_mode == InputrcEditingMode::Vi,
+                _ => false,
+            }
+        } else if let Some(term) = test.strip_prefix("term=") {
+            std::env::var("TERM").map_or(false, |current| {
+                current == term || current.split('-').next() == Some(term)
+            })
+        } else {
+            false
+        }
+    }
+
+    /// Apply a `set variable value` line
+    fn set(&mut self, state: &mut ParserState, setting: &str) -> Result<(), String> {
+        let (variable, value) = setting
+            .split_once(char::is_whitespace)
+            .unwrap_or((setting, ""));
+        let value = value.trim();
+        match variable.to_ascii_lowercase().as_str() {
+            "editing-mode" => {
+                self.editing_mode = match value {
+                    "emacs" => InputrcEditingMode::Emacs,
+                    "vi" => InputrcEditingMode::Vi,
+                    _ => return Err(format!("unknown editing mode {value}")),
+                };
+                state.keymap = self.default_keymap();
+            }
+            "keymap" => {
+                state.keymap = match value {
+                    "emacs" | "emacs-standard" => Keymap::Emacs,
+                    "emacs-meta" => Keymap::EmacsMeta,
+                    "emacs-ctlx" => Keymap::EmacsCtlx,
+                    "vi" | "vi-command" | "vi-move" => Keymap::ViCommand,
+                    "vi-insert" => Keymap::ViInsert,
+                    _ => return Err(format!("unknown keymap {value}")),
+                };
+            }
+            _ => return Err(format!("unsupported variable {variable}")),
+        }
+        Ok(())
+    }
+
+    /// Apply a `keyseq: function-name` or `keyseq: "macro"` line
+    fn bind(&mut self, keymap: Keymap, line: &str) -> Result<(), String> {
+        let (mut keys, value) = split_binding(line)?;
+        match keymap {
+            Keymap::EmacsMeta => keys.insert(0, RawKey::plain('\x1b')),
+            Keymap::EmacsCtlx => keys.insert(0, RawKey::plain('\x18')),
+            _ => {}
+        }
+        let keys = to_key_combinations(&keys)?;
+
+        let event = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let text = value[1..]
+                    .strip_suffix(quote)
+                    .ok_or_else(|| format!("unterminated macro {value}"))?;
+                let keys = parse_keys(text)?;
+                // Readline replays macros as typed keys, only plain text inserts the same here
+                if keys.iter().any(|key| key.meta || key.c.is_control()) {
+                    return Err(format!(
+                        "macros with control or meta keys are not supported: {value}"
+                    ));
+                }
+                edit_bind(EditCommand::InsertString(
+                    keys.into_iter().map(|key| key.c).collect(),
+                ))
+            }
+            _ => {
+                let name = value.split_whitespace().next().unwrap_or_default();
+                if name == "vi-movement-mode"
+                    && matches!(
+                        keymap,
+                        Keymap::Emacs | Keymap::EmacsMeta | Keymap::EmacsCtlx
+                    )
+                {
+                    return Err(format!("{name} only works in the vi keymaps"));
+                }
+                readline_function(name, &keys)
+                    .ok_or_else(|| format!("unsupported readline function {name}"))?
+            }
+        };
+
+        let (first, rest) = keys
+            .split_first()
+            .ok_or_else(|| "empty key sequence".to_string())?;
+        let keybindings = match keymap {
+            Keymap::Emacs | Keymap::EmacsMeta | Keymap::EmacsCtlx => &mut self.emacs,
+            Keymap::ViInsert => &mut self.vi_insert,
+            Keymap::ViCommand => &mut self.vi_normal,
+        };
+        keybindings.add_binding(first.clone(), rest.to_vec(), event);
+        Ok(())
+    }
+}
+
+/// A key as written in an inputrc file, before it is mapped to what the terminal reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RawKey {
+    meta: bool,
+    c: char,
+}
+
+impl RawKey {
+    const fn plain(c: char) -> Self {
+        RawKey { meta: false, c }
+    }
+}
+
+/// Split a binding line into its keys and the function name or macro
+fn split_binding(line: &str) -> Result<(Vec<RawKey>, &str), String> {
+    let (keys, rest) = match line.strip_prefix('"') {
+        Some(quoted) => {
+            let mut escaped = false;
+            let end = quoted
+                .char_indices()
+                .find(|&(_, c)| {
+                    let end = c == '"' && !escaped;
+                    escaped = c == '\\' && !escaped;
+                    end
+                })
+                .map(|(idx, _)| idx)
+                .ok_or_else(|| format!("unterminated key sequence {line}"))?;
+            (parse_keys(&quoted[..end])?, &quoted[end + 1..])
+        }
+        None => {
+            let (name, _) = line
+                .split_once(':')
+                .ok_or_else(|| format!("missing ':' in {line}"))?;
+            (vec![parse_key_name(name.trim())?], &line[name.len()..])
+        }
+    };
+
+    let value = rest
+        .trim_start()
+        .strip_prefix(':')
+        .ok_or_else(|| format!("missing ':' in {line}"))?;
+    Ok((keys, value.trim()))
+}
+
+/// Parse the escapes of a quoted key sequence or macro like `\C-x\M-e`
+fn parse_keys(text: &str) -> Result<Vec<RawKey>, String> {
+    let mut chars = text.chars().peekable();
+    let mut keys = vec![];
+    while let Some(key) = next_key(&mut chars)? {
+        keys.push(key);
+    }
+    Ok(keys)
+}
+
+fn next_key(chars: &mut Peekable<Chars>) -> Result<Option<RawKey>, String> {
+    let Some(c) = chars.next() else {
+        return Ok(None);
+    };
+    if c != '\\' {
+        return Ok(Some(RawKey::plain(c)));
+    }
+
+    match chars.next() {
+        Some(prefix @ ('C' | 'M')) if chars.peek() == Some(&'-') => {
+            chars.next();
+            let key = next_key(chars)?.ok_or_else(|| format!("missing key after \\{prefix}-"))?;
+            Ok(Some(match prefix {
+                'C' => RawKey {
+                    c: control(key.c),
+                    ..key
+                },
+                _ => RawKey { meta: true, ..key },
+            }))
+        }
+        Some(escaped) => Ok(Some(RawKey::plain(unescape(escaped, chars)?))),
+        None => Err("trailing backslash".to_string()),
+    }
+}
+
+/// The character of the escape `\<escaped>`, reading the digits of numeric escapes
+fn unescape(escaped: char, chars: &mut Peekable<Chars>) -> Result<char, String> {
+    let mut number = |radix: u32, first: Option<char>, max_digits: usize| {
+        let mut digits: String = first.into_iter().collect();
+        while digits.len() < max_digits {
+            match chars.peek() {
+                Some(c) if c.is_digit(radix) => digits.push(*c),
+                _ => break,
+            }
+            chars.next();
+        }
+        u32::from_str_radix(&digits, radix)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("invalid escape \\{escaped}{digits}"))
+    };
+
+    Ok(match escaped {
+        'a' => '\x07',
+        'b' => '\x08',
+        'd' => '\x7f',
+        'e' => '\x1b',
+        'f' => '\x0c',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\x0b',
+        '0'..='7' => number(8, Some(escaped), 3)?,
+        'x' => number(16, None, 2)?,
+        c => c,
+    })
+}
+
+/// The control character typed with `Control` and the key `c`
+fn control(c: char) -> char {
+    match c {
+        '?' => '\x7f',
+        ' ' => '\0',
+        c if c.is_ascii_alphabetic() || matches!(c, '@' | '[' | '\\' | ']' | '^' | '_') => {
+            char::from(c.to_ascii_uppercase() as u8 & 0x1f)
+        }
+        c => c,
+    }
+}
+
+/// Parse an unquoted key name like `Control-u` or `Meta-Rubout`
+fn parse_key_name(name: &str) -> Result<RawKey, String> {
+    let mut key = name;
+    let mut control_key = false;
+    let mut meta = false;
+    loop {
+        let lowercase = key.to_ascii_lowercase();
+        let prefix = ["control-", "c-", "meta-", "m-"]
+            .into_iter()
+            .find(|prefix| lowercase.starts_with(prefix) && key.len() > prefix.len());
+        match prefix {
+            Some(prefix) => {
+                control_key |= prefix.starts_with('c');
+                meta |= prefix.starts_with('m');
+                key = &key[prefix.len()..];
+            }
+            None => break,
+        }
+    }
+
+    let c = match key.to_ascii_lowercase().as_str() {
+        "del" | "rubout" => '\x7f',
+        "esc" | "escape" => '\x1b',
+        "lfd" | "newline" => '\n',
+        "ret" | "return" => '\r',
+        "space" | "spc" => ' ',
+        "tab" => '\t',
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(format!("unknown key name {key}")),
+            }
+        }
+    };
+    Ok(RawKey {
+        meta,
+        c: if control_key { control(c) } else { c },
+    })
+}
+
+/// Map the keys to the key combinations the terminal reports for them
+///
+/// `ESC` followed by a key is how terminals send `Meta`, and `ESC [` starts the escape
+/// sequences of keys like the arrows.
+fn to_key_combinations(keys: &[RawKey]) -> Result<Vec<KeyCombination>, String> {
+    let mut combinations = vec![];
+    let mut idx = 0;
+    while let Some(&key) = keys.get(idx) {
+        idx += 1;
+        match keys.get(idx) {
+            Some(next) if key == RawKey::plain('\x1b') => {
+                if matches!(next.c, '[' | 'O') && !next.meta && idx + 1 < keys.len() {
+                    let (combination, len) = escape_sequence(&keys[idx..])?;
+                    combinations.push(combination);
+                    idx += len;
+                } else {
+                    combinations.push(key_combination(RawKey {
+                        meta: true,
+                        ..*next
+                    }));
+                    idx += 1;
+                }
+            }
+            _ => combinations.push(key_combination(key)),
+        }
+    }
+    Ok(combinations)
+}
+
+fn key_combination(key: RawKey) -> KeyCombination {
+    let mut modifier = if key.meta {
+        KeyModifiers::ALT
+    } else {
+        KeyModifiers::NONE
+    };
+    let key_code = match key.c {
+        '\r' => KeyCode::Enter,
+        '\t' => KeyCode::Tab,
+        '\x7f' => KeyCode::Backspace,
+        '\x1b' => KeyCode::Esc,
+        '\0' => {
+            modifier |= KeyModifiers::CONTROL;
+            KeyCode::Char(' ')
+        }
+        c @ '\x01'..='\x1a' => {
+            modifier |= KeyModifiers::CONTROL;
+            KeyCode::Char(char::from(c as u8 - 1 + b'a'))
+        }
+        c @ '\x1c'..='\x1f' => {
+            modifier |= KeyModifiers::CONTROL;
+            KeyCode::Char(char::from(c as u8 - 0x1c + b'4'))
+        }
+        c if c.is_ascii_uppercase() => {
+            modifier |= KeyModifiers::SHIFT;
+            KeyCode::Char(c.to_ascii_lowercase())
+        }
+        c => KeyCode::Char(c),
+    };
+    KeyCombination { modifier, key_code }
+}
+
+/// Parse the escape sequence following `ESC`, starting at its `[` or `O`
+///
+/// Returns the key and the number of keys the sequence took.
+fn escape_sequence(keys: &[RawKey]) -> Result<(KeyCombination, usize), String> {
+    let introducer = keys[0].c;
+    let mut params = String::new();
+    for (idx, key) in keys.iter().enumerate().skip(1) {
+        if key.c.is_ascii_digit() || key.c == ';' {
+            params.push(key.c);
+            continue;
+        }
+
+        let mut params = params.split(';');
+        let number = params.next().and_then(|number| number.parse::<u8>().ok());
+        // The modifier parameter is one more than the bits of Shift, Alt and Control
+        let mut modifier = KeyModifiers::NONE;
+        if let Some(bits) = params.next().and_then(|bits| bits.parse::<u8>().ok()) {
+            let bits = bits.saturating_sub(1);
+            if bits & 1 != 0 {
+                modifier |= KeyModifiers::SHIFT;
+            }
+            if bits & 2 != 0 {
+                modifier |= KeyModifiers::ALT;
+            }
+            if bits & 4 != 0 {
+                modifier |= KeyModifiers::CONTROL;
+            }
+        }
+
+        let key_code = match (introducer, key.c, number) {
+            (_, 'A', _) => KeyCode::Up,
+            (_, 'B', _) => KeyCode::Down,
+            (_, 'C', _) => KeyCode::Right,
+            (_, 'D', _) => KeyCode::Left,
+            (_, 'H', _) => KeyCode::Home,
+            (_, 'F', _) => KeyCode::End,
+            ('O', c @ 'P'..='S', _) => KeyCode::F(c as u8 - b'P' + 1),
+            ('[', 'Z', _) => {
+                modifier |= KeyModifiers::SHIFT;
+                KeyCode::BackTab
+            }
+            ('[', '~', Some(number)) => match number {
+                1 | 7 => KeyCode::Home,
+                2 => KeyCode::Insert,
+                3 => KeyCode::Delete,
+                4 | 8 => KeyCode::End,
+                5 => KeyCode::PageUp,
+                6 => KeyCode::PageDown,
+                11..=15 => KeyCode::F(number - 10),
+                17..=21 => KeyCode::F(number - 11),
+                23 | 24 => KeyCode::F(number - 12),
+                _ => return Err(format!("unsupported escape sequence \\e[{number}~")),
+            },
+            _ => return Err(format!("unsupported escape sequence ending in {}", key.c)),
+        };
+        return Ok((KeyCombination { modifier, key_code }, idx + 1));
+    }
+    Err("incomplete escape sequence".to_string())
+}
+
+/// The event for the readline function `name` bound to the `keys`
+fn readline_function(name: &str, keys: &[KeyCombination]) -> Option<ReedlineEvent> {
+    use EditCommand as EC;
+
+    Some(match name.to_ascii_lowercase().as_str() {
+        // Moving
+        "beginning-of-line" => edit_bind(EC::MoveToLineStart { select: false }),
+        "end-of-line" => edit_bind(EC::MoveToLineEnd { select: false }),
+        "forward-char" => edit_bind(EC::MoveRight { select: false }),
+        "backward-char" => edit_bind(EC::MoveLeft { select: false }),
+        "forward-word" => edit_bind(EC::MoveWordRight { select: false }),
+        "backward-word" => edit_bind(EC::MoveWordLeft { select: false }),
+        "clear-screen" => ReedlineEvent::ClearScreen,
+        "clear-display" => ReedlineEvent::ClearScrollback,
+        // History
+        "accept-line" => ReedlineEvent::Enter,
+        "previous-history" => ReedlineEvent::PreviousHistory,
+        "next-history" => ReedlineEvent::NextHistory,
+        "history-search-backward" => ReedlineEvent::Up,
+        "history-search-forward" => ReedlineEvent::Down,
+        "reverse-search-history" => ReedlineEvent::SearchHistory,
+        "yank-last-arg" => ReedlineEvent::YankLastArg,
+        "yank-nth-arg" => ReedlineEvent::YankNthArg(1),
+        // Changing text
+        "end-of-file" => ReedlineEvent::CtrlD,
+        "delete-char" => edit_bind(EC::Delete),
+        "backward-delete-char" => edit_bind(EC::Backspace),
+        "tab-insert" => edit_bind(EC::InsertChar('\t')),
+        "transpose-chars" => edit_bind(EC::SwapGraphemes),
+        "transpose-words" => edit_bind(EC::SwapWords),
+        "upcase-word" => edit_bind(EC::UppercaseWord),
+        "downcase-word" => edit_bind(EC::LowercaseWord),
+        "capitalize-word" => edit_bind(EC::CapitalizeWord),
+        // Killing and yanking
+        "kill-line" => edit_bind(EC::CutToLineEnd),
+        "backward-kill-line" => edit_bind(EC::CutFromLineStart),
+        "unix-line-discard" => edit_bind(EC::CutFromStart),
+        "kill-whole-line" => edit_bind(EC::CutCurrentLine),
+        "kill-word" => edit_bind(EC::CutWordRight),
+        "backward-kill-word" => edit_bind(EC::CutWordLeft),
+        "unix-word-rubout" => edit_bind(EC::CutBigWordLeft),
+        "kill-region" => edit_bind(EC::KillRegion),
+        "copy-region-as-kill" => edit_bind(EC::CopySelection),
+        "yank" => edit_bind(EC::Yank),
+        "yank-pop" => edit_bind(EC::YankPop),
+        "set-mark" => edit_bind(EC::SetMark),
+        "exchange-point-and-mark" => edit_bind(EC::ExchangePointAndMark),
+        // Numeric arguments
+        "universal-argument" => ReedlineEvent::Emacs(EmacsEvent::UniversalArgument),
+        "digit-argument" => match keys.last()?.key_code {
+            KeyCode::Char(c @ '0'..='9') => {
+                ReedlineEvent::Emacs(EmacsEvent::DigitArgument(c as u8 - b'0'))
+            }
+            _ => return None,
+        },
+        // Completing
+        "complete" => ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::Menu("completion_menu".to_string()),
+            ReedlineEvent::MenuNext,
+        ]),
+        "menu-complete" => ReedlineEvent::MenuNext,
+        "menu-complete-backward" => ReedlineEvent::MenuPrevious,
+        // Miscellaneous
+        "undo" => edit_bind(EC::Undo),
+        "vi-movement-mode" => ReedlineEvent::Esc,
+        "abort" => ReedlineEvent::Esc,
+        "edit-and-execute-command" => ReedlineEvent::OpenEditor,
+        "do-nothing" => ReedlineEvent::None,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::edit_mode::keybindings::KeyNode;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn key(modifier: KeyModifiers, key_code: KeyCode) -> KeyCombination {
+        KeyCombination { modifier, key_code }
+    }
+
+    /// The event bound to the sequence of `keys`
+    fn lookup(keybindings: &Keybindings, keys: &[KeyCombination]) -> Option<ReedlineEvent> {
+        let mut node = keybindings.bindings.map.get(&keys[0])?;
+        for key in &keys[1..] {
+            match node {
+                KeyNode::Sequence(sequence) => node = sequence.map.get(key)?,
+                KeyNode::Event(_) => return None,
+            }
+        }
+        match node {
+            KeyNode::Event(event) => Some(event.clone()),
+            KeyNode::Sequence(_) => None,
+        }
+    }
+
+    #[rstest]
+    #[case(r"\C-x\C-e", vec![key(KeyModifiers::CONTROL, KeyCode::Char('x')), key(KeyModifiers::CONTROL, KeyCode::Char('e'))])]
+    #[case(r"\M-.", vec![key(KeyModifiers::ALT, KeyCode::Char('.'))])]
+    #[case(r"\ef", vec![key(KeyModifiers::ALT, KeyCode::Char('f'))])]
+    #[case(r"\M-\C-y", vec![key(KeyModifiers::ALT | KeyModifiers::CONTROL, KeyCode::Char('y'))])]
+    #[case(r"\C-?", vec![key(KeyModifiers::NONE, KeyCode::Backspace)])]
+    #[case(r"\e[A", vec![key(KeyModifiers::NONE, KeyCode::Up)])]
+    #[case(r"\e[1;5C", vec![key(KeyModifiers::CONTROL, KeyCode::Right)])]
+    #[case(r"\e[3~", vec![key(KeyModifiers::NONE, KeyCode::Delete)])]
+    #[case(r"\eOP", vec![key(KeyModifiers::NONE, KeyCode::F(1))])]
+    #[case(r"\t\e", vec![key(KeyModifiers::NONE, KeyCode::Tab), key(KeyModifiers::NONE, KeyCode::Esc)])]
+    #[case(r"gG\033", vec![key(KeyModifiers::NONE, KeyCode::Char('g')), key(KeyModifiers::SHIFT, KeyCode::Char('g')), key(KeyModifiers::NONE, KeyCode::Esc)])]
+    fn test_key_sequences(#[case] keys: &str, #[case] expected: Vec<KeyCombination>) {
+        assert_eq!(
+            to_key_combinations(&parse_keys(keys).unwrap()),
+            Ok(expected)
+        );
+    }
+
+    #[rstest]
+    #[case("Control-u", key(KeyModifiers::CONTROL, KeyCode::Char('u')))]
+    #[case("Meta-Rubout", key(KeyModifiers::ALT, KeyCode::Backspace))]
+    #[case("C-M-space", key(KeyModifiers::CONTROL | KeyModifiers::ALT, KeyCode::Char(' ')))]
+    #[case("RET", key(KeyModifiers::NONE, KeyCode::Enter))]
+    #[case("m", key(KeyModifiers::NONE, KeyCode::Char('m')))]
+    fn test_key_names(#[case] name: &str, #[case] expected: KeyCombination) {
+        assert_eq!(parse_key_name(name).map(key_combination), Ok(expected));
+    }
+
+    #[test]
+    fn bindings_are_added_to_the_keymap() {
+        let inputrc = Inputrc::parse(
+            r#"
+# Open the editor
+"\C-x\C-e": edit-and-execute-command
+Meta-Rubout: backward-kill-word
+"\C-xr": "reverse\"d"
+
+set keymap vi-insert
+"jk": vi-movement-mode
+
+set keymap vi-command
+"\e[A": history-search-backward
+"#,
+        );
+
+        assert_eq!(inputrc.issues, vec![]);
+        assert_eq!(
+            lookup(
+                &inputrc.emacs,
+                &[
+                    key(KeyModifiers::CONTROL, KeyCode::Char('x')),
+                    key(KeyModifiers::CONTROL, KeyCode::Char('e'))
+                ]
+            ),
+            Some(ReedlineEvent::OpenEditor)
+        );
+        assert_eq!(
+            lookup(
+                &inputrc.emacs,
+                &[key(KeyModifiers::ALT, KeyCode::Backspace)]
+            ),
+            Some(edit_bind(EditCommand::CutWordLeft))
+        );
+        assert_eq!(
+            lookup(
+                &inputrc.emacs,
+                &[
+                    key(KeyModifiers::CONTROL, KeyCode::Char('x')),
+                    key(KeyModifiers::NONE, KeyCode::Char('r'))
+                ]
+            ),
+            Some(edit_bind(EditCommand::InsertString(
+                "reverse\"d".to_string()
+            )))
+        );
+        assert_eq!(
+            lookup(
+                &inputrc.vi_insert,
+                &[
+                    key(KeyModifiers::NONE, KeyCode::Char('j')),
+                    key(KeyModifiers::NONE, KeyCode::Char('k'))
+                ]
+            ),
+            Some(ReedlineEvent::Esc)
+        );
+        assert_eq!(
+            lookup(&inputrc.vi_normal, &[key(KeyModifiers::NONE, KeyCode::Up)]),
+            Some(ReedlineEvent::Up)
+        );
+        assert_eq!(inputrc.editing_mode, InputrcEditingMode::Emacs);
+    }
+
+    #[test]
+    fn conditions_follow_the_editing_mode() {
+        let inputrc = Inputrc::parse(
+            r#"
+set editing-mode vi
+$if mode=emacs
+"\C-a": end-of-line
+$else
+$if Bash
+"\C-a": kill-line
+$endif
+"\C-a": beginning-of-line
+$endif
+"#,
+        );
+        let ctrl_a = [key(KeyModifiers::CONTROL, KeyCode::Char('a'))];
+
+        assert_eq!(inputrc.issues, vec![]);
+        assert_eq!(inputrc.editing_mode, InputrcEditingMode::Vi);
+        assert_eq!(
+            lookup(&inputrc.vi_insert, &ctrl_a),
+            Some(edit_bind(EditCommand::MoveToLineStart { select: false }))
+        );
+        assert_eq!(
+            lookup(&inputrc.emacs, &ctrl_a),
+            lookup(&default_emacs_keybindings(), &ctrl_a)
+        );
+    }
+
+    #[test]
+    fn unsupported_lines_are_reported() {
+        let inputrc = Inputrc::parse(
+            "set bell-style none\n\"\\C-t\": dump-functions\n$include /etc/inputrc\n$endif\n\"\\C-t: yank\n\"\\C-xl\": \"ls\\n\"\n\"jk\": vi-movement-mode\n$if mode=vi",
+        );
+
+        assert_eq!(
+            inputrc
+                .issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "line 1: unsupported variable bell-style",
+                "line 2: unsupported readline function dump-functions",
+                "line 3: $include is not supported, read /etc/inputrc separately",
+                "line 4: $endif without $if",
+                "line 5: unterminated key sequence \"\\C-t: yank",
+                "line 6: macros with control or meta keys are not supported: \"ls\\n\"",
+                "line 7: vi-movement-mode only works in the vi keymaps",
+                "line 8: missing $endif",
+            ]
+        );
+    }
+}
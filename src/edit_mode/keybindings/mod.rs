@@ -0,0 +1,906 @@
+mod analysis;
+mod inputrc;
+mod notation;
+
+pub use analysis::KeybindingConflict;
+pub use inputrc::{Inputrc, InputrcEditingMode, InputrcIssue};
+pub(crate) use notation::modifier_names;
+pub use notation::{KeyNotationError, KeySequence, KeybindingEntry, KeybindingFile};
+
+use {
+    crate::{enums::ReedlineEvent, EditCommand},
+    crossterm::event::{KeyCode, KeyModifiers},
+    serde::{Deserialize, Serialize},
+    std::collections::{hash_map::Entry, HashMap},
+};
+
+#[derive(Debug)]
+pub enum KeySequenceResult {
+    Pending,
+    Matched(ReedlineEvent),
+    Cancelled(Vec<KeyCombination>),
+}
+
+#[derive(Debug)]
+/// Tracks completion of a key sequence
+pub struct PartialKeySequence {
+    sequence: Sequence,
+    history: Vec<KeyCombination>,
+}
+
+impl PartialKeySequence {
+    pub fn new(sequence: Sequence) -> Self {
+        Self {
+            sequence,
+            history: vec![],
+        }
+    }
+
+    pub fn advance(&mut self, kc: KeyCombination) -> KeySequenceResult {
+        self.history.push(kc.clone());
+        match self.sequence.map.remove(&kc) {
+            Some(KeyNode::Event(reedline_event)) => KeySequenceResult::Matched(reedline_event),
+            Some(KeyNode::Sequence(sequence)) => {
+                self.sequence = sequence;
+                KeySequenceResult::Pending
+            }
+            None => KeySequenceResult::Cancelled(std::mem::take(&mut self.history)),
+        }
+    }
+
+    /// Key combinations that were pressed so far
+    pub fn keys(&self) -> &[KeyCombination] {
+        &self.history
+    }
+
+    /// Keys that can follow with the event each one triggers, `None` if the key
+    /// starts a longer sequence
+    pub fn continuations(&self) -> Vec<(KeyCombination, Option<ReedlineEvent>)> {
+        let mut continuations: Vec<_> = self
+            .sequence
+            .map
+            .iter()
+            .map(|(kc, key_node)| {
+                let event = match key_node {
+                    KeyNode::Event(event) => Some(event.clone()),
+                    KeyNode::Sequence(_) => None,
+                };
+                (kc.clone(), event)
+            })
+            .collect();
+        continuations.sort_by_cached_key(|(kc, _)| kc.to_string());
+        continuations
+    }
+
+    /// Plain characters pressed so far, inserted if the sequence gets cancelled
+    pub fn typed_text(&self) -> Option<String> {
+        let text: String = self
+            .history
+            .iter()
+            .filter_map(|kc| match kc {
+                KeyCombination {
+                    modifier: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                    key_code: KeyCode::Char(c),
+                } => Some(*c),
+                _ => None,
+            })
+            .collect();
+        (!text.is_empty()).then_some(text)
+    }
+
+    pub fn cancel(self) -> Vec<KeyCombination> {
+        self.history
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+/// Represents a modifier/key combination
+pub struct KeyCombination {
+    /// Modifier (Shift, Control, etc.) of the key combination
+    pub modifier: KeyModifiers,
+
+    /// Key
+    pub key_code: KeyCode,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum KeyNode {
+    Sequence(Sequence),
+    Event(ReedlineEvent),
+}
+
+impl KeyNode {
+    fn new(key_combinations: Vec<KeyCombination>, command: ReedlineEvent) -> Self {
+        let mut prev = KeyNode::Event(command);
+        for key_combination in key_combinations.into_iter().rev() {
+            prev = KeyNode::Sequence(Sequence {
+                map: HashMap::from([(key_combination, prev)]),
+            });
+        }
+        prev
+    }
+
+    /// Add the bindings of `other` to the ones of this node at `keys`
+    ///
+    /// Where one of them binds an event and the other a sequence or a different event,
+    /// `other` wins and the lost bindings are recorded in `conflicts`.
+    fn merge(
+        &mut self,
+        other: Self,
+        keys: &mut Vec<KeyCombination>,
+        conflicts: &mut Vec<KeybindingConflict>,
+    ) {
+        match (&mut *self, other) {
+            (KeyNode::Sequence(sequence), KeyNode::Sequence(mut other_sequence)) => {
+                for (k, v) in sequence.map.iter_mut() {
+                    if let Some(o) = other_sequence.map.remove(k) {
+                        keys.push(k.clone());
+                        v.merge(o, keys, conflicts);
+                        keys.pop();
+                    }
+                }
+                sequence.map.extend(other_sequence.map.drain());
+            }
+            (_, other) => {
+                analysis::replacement_conflicts(keys, self, &other, conflicts);
+                *self = other;
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Sequence {
+    // name: Option<String>,
+    pub map: HashMap<KeyCombination, KeyNode>,
+}
+
+/// Main definition of editor keybindings
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Keybindings {
+    /// Defines a keybinding for a reedline event
+    pub bindings: Sequence,
+    /// Bindings that were lost when adding others
+    #[serde(skip)]
+    replaced: Vec<KeybindingConflict>,
+    /// Indistinguishable keys that are bound on purpose, with the binding they were accepted for
+    #[serde(skip)]
+    accepted: Vec<(KeySequence, KeyNode)>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Keybindings {
+    /// New keybining
+    pub fn new() -> Self {
+        Self {
+            bindings: Sequence::default(),
+            replaced: vec![],
+            accepted: vec![],
+        }
+    }
+
+    /// Defines an empty keybinding object
+    pub fn empty() -> Self {
+        Self::new()
+    }
+
+    /// Adds a keybinding
+    ///
+    /// # Panics
+    ///
+    /// If `command` is an empty [`ReedlineEvent::UntilFound`]
+    pub fn add_binding(
+        &mut self,
+        start_key_combination: KeyCombination,
+        key_combinations: Vec<KeyCombination>,
+        command: ReedlineEvent,
+    ) {
+        if let ReedlineEvent::UntilFound(subcommands) = &command {
+            assert!(
+                !subcommands.is_empty(),
+                "UntilFound should contain a series of potential events to handle"
+            );
+        }
+
+        let key_node = KeyNode::new(key_combinations, command);
+        match self.bindings.map.entry(start_key_combination) {
+            Entry::Occupied(mut occupied_entry) => {
+                let mut keys = vec![occupied_entry.key().clone()];
+                occupied_entry
+                    .get_mut()
+                    .merge(key_node, &mut keys, &mut self.replaced);
+            }
+            Entry::Vacant(vacant_entry) => _ = vacant_entry.insert(key_node),
+        }
+    }
+
+    /// Find a keybinding based on the modifier and keycode
+    pub fn find_binding(&self, modifier: KeyModifiers, key_code: KeyCode) -> Option<KeyNode> {
+        let key_combo = KeyCombination { modifier, key_code };
+        self.bindings.map.get(&key_combo).cloned()
+    }
+
+    /// Remove a keybinding
+    ///
+    /// Returns `Some(ReedlineEvent)` if the key combination was previously bound to a particular [`ReedlineEvent`]
+    pub fn remove_binding(
+        &mut self,
+        start_key_combination: KeyCombination,
+        key_combinations: Vec<KeyCombination>,
+    ) -> Option<ReedlineEvent> {
+        let mut keys = vec![start_key_combination];
+        keys.extend(key_combinations);
+        remove_key_node(&mut self.bindings, &keys)
+    }
+
+    /// Report the problems with these keybindings
+    ///
+    /// Lists the bindings that were replaced by adding others for the same keys, and the
+    /// keys that terminals can't tell apart from others without the kitty protocol.
+    ///
+    /// Replaced bindings are only tracked by [`Keybindings::add_binding`], neither changes
+    /// made to [`Keybindings::bindings`] directly nor a serialization keep them, the same
+    /// goes for the keys accepted with [`Keybindings::clear_conflicts`].
+    pub fn conflicts(&self) -> Vec<KeybindingConflict> {
+        let mut indistinguishable = vec![];
+        analysis::indistinguishable_keys(&self.bindings, &mut vec![], &mut indistinguishable);
+        indistinguishable.retain(|conflict| match conflict {
+            KeybindingConflict::Indistinguishable { keys, .. } => !self.is_accepted(&keys.0),
+            _ => true,
+        });
+        indistinguishable.sort_by_cached_key(ToString::to_string);
+
+        let mut conflicts = self.replaced.clone();
+        conflicts.extend(indistinguishable);
+        conflicts
+    }
+
+    /// Accept the conflicts reported so far, like the defaults overridden on purpose or the
+    /// keys bound for terminals with the kitty protocol
+    ///
+    /// Indistinguishable keys are reported again once they are bound differently.
+    pub fn clear_conflicts(&mut self) {
+        self.replaced.clear();
+        let mut indistinguishable = vec![];
+        analysis::indistinguishable_keys(&self.bindings, &mut vec![], &mut indistinguishable);
+        for conflict in indistinguishable {
+            if let KeybindingConflict::Indistinguishable { keys, .. } = conflict {
+                self.accept_indistinguishable(&keys.0);
+            }
+        }
+    }
+
+    /// Don't report the `keys` as indistinguishable while they stay bound the same way
+    fn accept_indistinguishable(&mut self, keys: &[KeyCombination]) {
+        if let Some(node) = find_key_node(&self.bindings, keys) {
+            let node = node.clone();
+            self.accepted.push((KeySequence(keys.to_vec()), node));
+        }
+    }
+
+    fn is_accepted(&self, keys: &[KeyCombination]) -> bool {
+        let node = find_key_node(&self.bindings, keys);
+        self.accepted
+            .iter()
+            .any(|(accepted, accepted_node)| accepted.0 == keys && node == Some(accepted_node))
+    }
+
+    /// Get assigned keybindings, each with the full sequence of key combinations
+    pub fn get_keybindings(&self) -> Vec<(Vec<KeyCombination>, ReedlineEvent)> {
+        let mut keybindings = vec![];
+        collect_key_nodes(&self.bindings, &mut vec![], &mut keybindings);
+        keybindings
+    }
+}
+
+/// The event or sequence bound to the `keys`
+fn find_key_node<'a>(sequence: &'a Sequence, keys: &[KeyCombination]) -> Option<&'a KeyNode> {
+    let (first, rest) = keys.split_first()?;
+    match sequence.map.get(first)? {
+        node if rest.is_empty() => Some(node),
+        KeyNode::Sequence(inner) => find_key_node(inner, rest),
+        KeyNode::Event(_) => None,
+    }
+}
+
+/// Remove the event bound to the `keys`, dropping the sequences that become empty
+fn remove_key_node(sequence: &mut Sequence, keys: &[KeyCombination]) -> Option<ReedlineEvent> {
+    let (first, rest) = keys.split_first()?;
+    if rest.is_empty() {
+        return match sequence.map.remove(first)? {
+            KeyNode::Event(event) => Some(event),
+            node => {
+                sequence.map.insert(first.clone(), node);
+                None
+            }
+        };
+    }
+
+    let KeyNode::Sequence(inner) = sequence.map.get_mut(first)? else {
+        return None;
+    };
+    let event = remove_key_node(inner, rest)?;
+    if inner.map.is_empty() {
+        sequence.map.remove(first);
+    }
+    Some(event)
+}
+
+fn collect_key_nodes(
+    sequence: &Sequence,
+    prefix: &mut Vec<KeyCombination>,
+    keybindings: &mut Vec<(Vec<KeyCombination>, ReedlineEvent)>,
+) {
+    for (key_combination, node) in &sequence.map {
+        prefix.push(key_combination.clone());
+        match node {
+            KeyNode::Event(event) => keybindings.push((prefix.clone(), event.clone())),
+            KeyNode::Sequence(inner) => collect_key_nodes(inner, prefix, keybindings),
+        }
+        prefix.pop();
+    }
+}
+
+pub fn to_lowercase_key_code(key_code: KeyCode) -> KeyCode {
+    if let KeyCode::Char(c) = key_code {
+        KeyCode::Char(c.to_ascii_lowercase())
+    } else {
+        key_code
+    }
+}
+
+pub fn edit_bind(command: EditCommand) -> ReedlineEvent {
+    ReedlineEvent::Edit(vec![command])
+}
+
+/// Add the basic special keybindings
+///
+/// `Ctrl-C`, `Ctrl-D`, `Ctrl-O`, `Ctrl-R`
+/// + `Esc`
+/// + `Ctrl-O` to open the external editor
+pub fn add_common_control_bindings(kb: &mut Keybindings) {
+    use KeyCode as KC;
+    use KeyModifiers as KM;
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Esc,
+        },
+        vec![],
+        ReedlineEvent::Esc,
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('c'),
+        },
+        vec![],
+        ReedlineEvent::CtrlC,
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('d'),
+        },
+        vec![],
+        ReedlineEvent::CtrlD,
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('l'),
+        },
+        vec![],
+        ReedlineEvent::ClearScreen,
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('r'),
+        },
+        vec![],
+        ReedlineEvent::SearchHistory,
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('o'),
+        },
+        vec![],
+        ReedlineEvent::OpenEditor,
+    );
+}
+/// Add the arrow navigation and its `Ctrl` variants
+pub fn add_common_navigation_bindings(kb: &mut Keybindings) {
+    use EditCommand as EC;
+    use KeyCode as KC;
+    use KeyModifiers as KM;
+
+    // Arrow keys without modifier
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Up,
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![ReedlineEvent::MenuUp, ReedlineEvent::Up]),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Down,
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![ReedlineEvent::MenuDown, ReedlineEvent::Down]),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Left,
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![ReedlineEvent::MenuLeft, ReedlineEvent::Left]),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Right,
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::HistoryHintComplete,
+            ReedlineEvent::MenuRight,
+            ReedlineEvent::Right,
+        ]),
+    );
+
+    // Ctrl Left and Right
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Left,
+        },
+        vec![],
+        edit_bind(EC::MoveWordLeft { select: false }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Right,
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::HistoryHintWordComplete,
+            edit_bind(EC::MoveWordRight { select: false }),
+        ]),
+    );
+    // Home/End & ctrl+a/ctrl+e
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Home,
+        },
+        vec![],
+        edit_bind(EC::MoveToLineStart { select: false }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('a'),
+        },
+        vec![],
+        edit_bind(EC::MoveToLineStart { select: false }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::End,
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::HistoryHintComplete,
+            edit_bind(EC::MoveToLineEnd { select: false }),
+        ]),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('e'),
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![
+            ReedlineEvent::HistoryHintComplete,
+            edit_bind(EC::MoveToLineEnd { select: false }),
+        ]),
+    );
+    // Ctrl Home/End
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Home,
+        },
+        vec![],
+        edit_bind(EC::MoveToStart { select: false }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::End,
+        },
+        vec![],
+        edit_bind(EC::MoveToEnd { select: false }),
+    );
+    // EMACS arrows
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('p'),
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![ReedlineEvent::MenuUp, ReedlineEvent::Up]),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('n'),
+        },
+        vec![],
+        ReedlineEvent::UntilFound(vec![ReedlineEvent::MenuDown, ReedlineEvent::Down]),
+    );
+}
+
+/// Add basic functionality to edit
+///
+/// `Delete`, `Backspace` and the basic variants do delete words
+pub fn add_common_edit_bindings(kb: &mut Keybindings) {
+    use EditCommand as EC;
+    use KeyCode as KC;
+    use KeyModifiers as KM;
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Backspace,
+        },
+        vec![],
+        edit_bind(EC::Backspace),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::NONE,
+            key_code: KC::Delete,
+        },
+        vec![],
+        edit_bind(EC::Delete),
+    );
+    let ctrl_backspace = KeyCombination {
+        modifier: KM::CONTROL,
+        key_code: KC::Backspace,
+    };
+    kb.add_binding(ctrl_backspace.clone(), vec![], edit_bind(EC::BackspaceWord));
+    // Only works with the kitty protocol, other terminals send `C-h`
+    kb.accept_indistinguishable(&[ctrl_backspace]);
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Delete,
+        },
+        vec![],
+        edit_bind(EC::DeleteWord),
+    );
+    // Base commands should not affect cut buffer
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('h'),
+        },
+        vec![],
+        edit_bind(EC::Backspace),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('w'),
+        },
+        vec![],
+        edit_bind(EC::BackspaceWord),
+    );
+    #[cfg(feature = "system_clipboard")]
+    for (c, command) in [
+        ('x', EC::CutSelectionSystem),
+        ('c', EC::CopySelectionSystem),
+        ('v', EC::PasteSystem),
+    ] {
+        let key = KeyCombination {
+            modifier: KM::CONTROL | KM::SHIFT,
+            key_code: KC::Char(c),
+        };
+        kb.add_binding(key.clone(), vec![], edit_bind(command));
+        // Only works with the kitty protocol, other terminals send the key without Shift
+        kb.accept_indistinguishable(&[key]);
+    }
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::ALT,
+            key_code: KC::Enter,
+        },
+        vec![],
+        edit_bind(EC::InsertNewline),
+    );
+    let shift_enter = KeyCombination {
+        modifier: KM::SHIFT,
+        key_code: KC::Enter,
+    };
+    kb.add_binding(shift_enter.clone(), vec![], edit_bind(EC::InsertNewline));
+    // Only works with the kitty protocol, other terminals send `Enter`
+    kb.accept_indistinguishable(&[shift_enter]);
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('j'),
+        },
+        vec![],
+        ReedlineEvent::Enter,
+    );
+}
+
+pub fn add_common_selection_bindings(kb: &mut Keybindings) {
+    use EditCommand as EC;
+    use KeyCode as KC;
+    use KeyModifiers as KM;
+
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT,
+            key_code: KC::Left,
+        },
+        vec![],
+        edit_bind(EC::MoveLeft { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT,
+            key_code: KC::Right,
+        },
+        vec![],
+        edit_bind(EC::MoveRight { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT | KM::CONTROL,
+            key_code: KC::Left,
+        },
+        vec![],
+        edit_bind(EC::MoveWordLeft { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT | KM::CONTROL,
+            key_code: KC::Right,
+        },
+        vec![],
+        edit_bind(EC::MoveWordRight { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT,
+            key_code: KC::End,
+        },
+        vec![],
+        edit_bind(EC::MoveToLineEnd { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT | KM::CONTROL,
+            key_code: KC::End,
+        },
+        vec![],
+        edit_bind(EC::MoveToEnd { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT,
+            key_code: KC::Home,
+        },
+        vec![],
+        edit_bind(EC::MoveToLineStart { select: true }),
+    );
+    kb.add_binding(
+        KeyCombination {
+            modifier: KM::SHIFT | KM::CONTROL,
+            key_code: KC::Home,
+        },
+        vec![],
+        edit_bind(EC::MoveToStart { select: true }),
+    );
+    let ctrl_shift_a = KeyCombination {
+        modifier: KM::CONTROL | KM::SHIFT,
+        key_code: KC::Char('a'),
+    };
+    kb.add_binding(ctrl_shift_a.clone(), vec![], edit_bind(EC::SelectAll));
+    // Only works with the kitty protocol, other terminals send `C-a`
+    kb.accept_indistinguishable(&[ctrl_shift_a]);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn removing_a_binding_drops_empty_sequences() {
+        let ctrl = |c| KeyCombination {
+            modifier: KeyModifiers::CONTROL,
+            key_code: KeyCode::Char(c),
+        };
+        let mut keybindings = Keybindings::new();
+        keybindings.add_binding(ctrl('x'), vec![ctrl('e')], ReedlineEvent::OpenEditor);
+        keybindings.add_binding(ctrl('x'), vec![ctrl('u')], edit_bind(EditCommand::Undo));
+
+        assert_eq!(keybindings.remove_binding(ctrl('x'), vec![]), None);
+        assert_eq!(
+            keybindings.remove_binding(ctrl('x'), vec![ctrl('e')]),
+            Some(ReedlineEvent::OpenEditor)
+        );
+        assert_eq!(
+            keybindings.get_keybindings(),
+            vec![(vec![ctrl('x'), ctrl('u')], edit_bind(EditCommand::Undo))]
+        );
+        assert_eq!(
+            keybindings.remove_binding(ctrl('x'), vec![ctrl('u')]),
+            Some(edit_bind(EditCommand::Undo))
+        );
+        assert!(keybindings.bindings.map.is_empty());
+    }
+
+    #[test]
+    fn pending_sequence_lists_its_continuations() {
+        let ctrl = |c| KeyCombination {
+            modifier: KeyModifiers::CONTROL,
+            key_code: KeyCode::Char(c),
+        };
+        let mut keybindings = Keybindings::new();
+        keybindings.add_binding(ctrl('x'), vec![ctrl('u')], edit_bind(EditCommand::Undo));
+        keybindings.add_binding(ctrl('x'), vec![ctrl('e')], ReedlineEvent::OpenEditor);
+        keybindings.add_binding(ctrl('x'), vec![ctrl('r'), ctrl('r')], ReedlineEvent::Submit);
+
+        let mut partial = PartialKeySequence::new(Sequence {
+            map: HashMap::from([(
+                ctrl('x'),
+                keybindings
+                    .find_binding(KeyModifiers::CONTROL, KeyCode::Char('x'))
+                    .unwrap(),
+            )]),
+        });
+        assert!(matches!(
+            partial.advance(ctrl('x')),
+            KeySequenceResult::Pending
+        ));
+        assert_eq!(
+            partial.continuations(),
+            vec![
+                (ctrl('e'), Some(ReedlineEvent::OpenEditor)),
+                (ctrl('r'), None),
+                (ctrl('u'), Some(edit_bind(EditCommand::Undo))),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaced_bindings_are_reported() {
+        let ctrl = |c| KeyCombination {
+            modifier: KeyModifiers::CONTROL,
+            key_code: KeyCode::Char(c),
+        };
+        let mut keybindings = Keybindings::new();
+        keybindings.add_binding(ctrl('x'), vec![], ReedlineEvent::OpenEditor);
+        keybindings.add_binding(ctrl('x'), vec![ctrl('e')], ReedlineEvent::OpenEditor);
+        keybindings.add_binding(ctrl('x'), vec![ctrl('e')], ReedlineEvent::Submit);
+        keybindings.add_binding(ctrl('x'), vec![ctrl('u')], edit_bind(EditCommand::Undo));
+        keybindings.add_binding(ctrl('x'), vec![ctrl('u')], edit_bind(EditCommand::Undo));
+        keybindings.add_binding(ctrl('x'), vec![], ReedlineEvent::ClearScreen);
+
+        let keys = |keys: &[char]| KeySequence(keys.iter().map(|c| ctrl(*c)).collect());
+        assert_eq!(
+            keybindings.conflicts(),
+            vec![
+                KeybindingConflict::PrefixHidesBinding {
+                    prefix: keys(&['x']),
+                    hidden: ReedlineEvent::OpenEditor,
+                },
+                KeybindingConflict::Shadowed {
+                    keys: keys(&['x', 'e']),
+                    hidden: ReedlineEvent::OpenEditor,
+                    event: ReedlineEvent::Submit,
+                },
+                KeybindingConflict::BindingHidesSequences {
+                    keys: keys(&['x']),
+                    hidden: vec![keys(&['x', 'e']), keys(&['x', 'u'])],
+                },
+            ]
+        );
+        assert_eq!(
+            keybindings.get_keybindings(),
+            vec![(vec![ctrl('x')], ReedlineEvent::ClearScreen)]
+        );
+
+        keybindings.clear_conflicts();
+        assert_eq!(keybindings.conflicts(), vec![]);
+    }
+
+    #[rstest]
+    #[case::emacs(crate::default_emacs_keybindings())]
+    #[case::vi_normal(crate::default_vi_normal_keybindings())]
+    #[case::vi_insert(crate::default_vi_insert_keybindings())]
+    #[case::helix_normal(crate::default_helix_normal_keybindings())]
+    #[case::helix_insert(crate::default_helix_insert_keybindings())]
+    #[case::helix_match(crate::default_helix_match_keybindings())]
+    fn default_keybindings_have_no_conflicts(#[case] keybindings: Keybindings) {
+        assert_eq!(keybindings.conflicts(), vec![]);
+    }
+
+    fn conflict_messages(keybindings: &Keybindings) -> Vec<String> {
+        keybindings
+            .conflicts()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn rebinding_an_accepted_key_is_reported() {
+        let mut keybindings = crate::default_emacs_keybindings();
+        keybindings.add_binding("C-S-a".parse().unwrap(), vec![], ReedlineEvent::OpenEditor);
+
+        assert_eq!(
+            conflict_messages(&keybindings),
+            vec![
+                "`C-S-a` is bound to OpenEditor, replacing Edit([SelectAll])",
+                "`C-S-a` is received as `C-a` without the kitty protocol",
+            ]
+        );
+
+        keybindings.clear_conflicts();
+        assert_eq!(conflict_messages(&keybindings), Vec::<String>::new());
+
+        keybindings.add_binding("C-S-a".parse().unwrap(), vec![], ReedlineEvent::Submit);
+        assert_eq!(
+            conflict_messages(&keybindings),
+            vec![
+                "`C-S-a` is bound to Submit, replacing OpenEditor",
+                "`C-S-a` is received as `C-a` without the kitty protocol",
+            ]
+        );
+    }
+
+    #[test]
+    fn indistinguishable_keys_are_reported() {
+        let mut keybindings = Keybindings::new();
+        keybindings.add_binding(
+            "C-x".parse().unwrap(),
+            vec!["C-i".parse().unwrap()],
+            ReedlineEvent::OpenEditor,
+        );
+        keybindings.add_binding("Tab".parse().unwrap(), vec![], ReedlineEvent::MenuNext);
+
+        let conflicts = keybindings.conflicts();
+        assert_eq!(
+            conflicts,
+            vec![KeybindingConflict::Indistinguishable {
+                keys: "C-x C-i".parse().unwrap(),
+                key: "C-i".parse().unwrap(),
+                reported_as: "Tab".parse().unwrap(),
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "`C-i` in `C-x C-i` is received as `Tab` without the kitty protocol"
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use {
+    super::{KeyCombination, Keybindings},
+    crate::enums::ReedlineEvent,
+    crossterm::event::{KeyCode, KeyModifiers, MediaKeyCode, ModifierKeyCode},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{Display, Formatter},
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+/// Names of the modifiers in key notation, the first one is used when formatting
+const MODIFIER_NAMES: [(KeyModifiers, &[&str]); 6] = [
+    (KeyModifiers::CONTROL, &["C", "Ctrl", "Control"]),
+    (KeyModifiers::ALT, &["M", "A", "Alt"]),
+    (KeyModifiers::SHIFT, &["S", "Shift"]),
+    (KeyModifiers::SUPER, &["Super"]),
+    (KeyModifiers::HYPER, &["Hyper"]),
+    (KeyModifiers::META, &["Meta"]),
+];
+
+/// Names of the keys in key notation, the first one is used when formatting
+const KEY_NAMES: [(KeyCode, &[&str]); 51] = [
+    (KeyCode::Enter, &["Enter", "Return", "Ret", "CR"]),
+    (KeyCode::Esc, &["Esc", "Escape"]),
+    (KeyCode::Tab, &["Tab"]),
+    (KeyCode::BackTab, &["BackTab"]),
+    (KeyCode::Backspace, &["Backspace", "BS"]),
+    (KeyCode::Delete, &["Delete", "Del"]),
+    (KeyCode::Insert, &["Insert", "Ins"]),
+    (KeyCode::Home, &["Home"]),
+    (KeyCode::End, &["End"]),
+    (KeyCode::PageUp, &["PageUp", "PgUp"]),
+    (KeyCode::PageDown, &["PageDown", "PgDn"]),
+    (KeyCode::Up, &["Up"]),
+    (KeyCode::Down, &["Down"]),
+    (KeyCode::Left, &["Left"]),
+    (KeyCode::Right, &["Right"]),
+    (KeyCode::Char(' '), &["Space", "Spc"]),
+    (KeyCode::Null, &["Null"]),
+    (KeyCode::CapsLock, &["CapsLock"]),
+    (KeyCode::ScrollLock, &["ScrollLock"]),
+    (KeyCode::NumLock, &["NumLock"]),
+    (KeyCode::PrintScreen, &["PrintScreen"]),
+    (KeyCode::Pause, &["Pause"]),
+    (KeyCode::Menu, &["Menu"]),
+    (KeyCode::KeypadBegin, &["KeypadBegin"]),
+    (KeyCode::Media(MediaKeyCode::Play), &["MediaPlay"]),
+    (KeyCode::Media(MediaKeyCode::Pause), &["MediaPause"]),
+    (KeyCode::Media(MediaKeyCode::PlayPause), &["MediaPlayPause"]),
+    (KeyCode::Media(MediaKeyCode::Reverse), &["MediaReverse"]),
+    (KeyCode::Media(MediaKeyCode::Stop), &["MediaStop"]),
+    (
+        KeyCode::Media(MediaKeyCode::FastForward),
+        &["MediaFastForward"],
+    ),
+    (KeyCode::Media(MediaKeyCode::Rewind), &["MediaRewind"]),
+    (KeyCode::Media(MediaKeyCode::TrackNext), &["MediaTrackNext"]),
+    (
+        KeyCode::Media(MediaKeyCode::TrackPrevious),
+        &["MediaTrackPrevious"],
+    ),
+    (KeyCode::Media(MediaKeyCode::Record), &["MediaRecord"]),
+    (
+        KeyCode::Media(MediaKeyCode::LowerVolume),
+        &["MediaLowerVolume"],
+    ),
+    (
+        KeyCode::Media(MediaKeyCode::RaiseVolume),
+        &["MediaRaiseVolume"],
+    ),
+    (
+        KeyCode::Media(MediaKeyCode::MuteVolume),
+        &["MediaMuteVolume"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::LeftShift),
+        &["LeftShift"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::LeftControl),
+        &["LeftControl"],
+    ),
+    (KeyCode::Modifier(ModifierKeyCode::LeftAlt), &["LeftAlt"]),
+    (
+        KeyCode::Modifier(ModifierKeyCode::LeftSuper),
+        &["LeftSuper"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::LeftHyper),
+        &["LeftHyper"],
+    ),
+    (KeyCode::Modifier(ModifierKeyCode::LeftMeta), &["LeftMeta"]),
+    (
+        KeyCode::Modifier(ModifierKeyCode::RightShift),
+        &["RightShift"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::RightControl),
+        &["RightControl"],
+    ),
+    (KeyCode::Modifier(ModifierKeyCode::RightAlt), &["RightAlt"]),
+    (
+        KeyCode::Modifier(ModifierKeyCode::RightSuper),
+        &["RightSuper"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::RightHyper),
+        &["RightHyper"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::RightMeta),
+        &["RightMeta"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift),
+        &["IsoLevel3Shift"],
+    ),
+    (
+        KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift),
+        &["IsoLevel5Shift"],
+    ),
+];
+
+/// Error while parsing key notation
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeyNotationError {
+    /// The notation doesn't contain any key
+    #[error("empty key sequence")]
+    Empty,
+
+    /// A key or modifier that isn't known
+    #[error("unknown key {0}")]
+    UnknownKey(String),
+}
+
+impl FromStr for KeyCombination {
+    type Err = KeyNotationError;
+
+    /// Parse a single key like `C-x`, `<A-Enter>`, `ctrl+shift+k` or `G`
+    ///
+    /// Modifiers are separated by `-` or `+`, an uppercase letter implies Shift.
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let notation = notation.trim();
+        let unknown = || KeyNotationError::UnknownKey(notation.to_string());
+        let mut key = match notation.strip_prefix('<').and_then(|n| n.strip_suffix('>')) {
+            Some(inner) if !inner.is_empty() => inner,
+            _ => notation,
+        };
+        if key.is_empty() {
+            return Err(KeyNotationError::Empty);
+        }
+
+        let mut modifier = KeyModifiers::NONE;
+        while let Some((found, rest)) = strip_modifier(key) {
+            modifier |= found;
+            key = rest;
+        }
+
+        let key_code = KEY_NAMES
+            .iter()
+            .find(|(_, names)| names.iter().any(|name| name.eq_ignore_ascii_case(key)))
+            .map(|(key_code, _)| *key_code);
+        let key_code = match key_code {
+            Some(key_code) => key_code,
+            None => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii_uppercase() => {
+                        modifier |= KeyModifiers::SHIFT;
+                        KeyCode::Char(c.to_ascii_lowercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    (Some('F' | 'f'), Some(_)) => key[1..]
+                        .parse()
+                        .ok()
+                        .filter(|number| (1..=24).contains(number))
+                        .map(KeyCode::F)
+                        .ok_or_else(unknown)?,
+                    _ => return Err(unknown()),
+                }
+            }
+        };
+        Ok(KeyCombination { modifier, key_code })
+    }
+}
+
+/// Split a leading modifier like `C-` or `ctrl+` off the `key`
+fn strip_modifier(key: &str) -> Option<(KeyModifiers, &str)> {
+    MODIFIER_NAMES.iter().find_map(|(modifier, names)| {
+        names.iter().find_map(|name| {
+            let rest = key
+                .get(..name.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(name))
+                .and_then(|_| key[name.len()..].strip_prefix(['-', '+']))?;
+            (!rest.is_empty()).then_some((*modifier, rest))
+        })
+    })
+}
+
+impl Display for KeyCombination {
+    /// Format the key in the Emacs style notation, e.g. `C-x` or `M-S-Enter`
+    ///
+    /// A shifted letter without other modifiers is written as the uppercase letter.
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let modifier = self.modifier;
+        if let KeyCode::Char(c) = self.key_code {
+            if modifier == KeyModifiers::SHIFT && c.is_ascii_alphabetic() {
+                return write!(f, "{}", c.to_ascii_uppercase());
+            }
+        }
+
+        for name in modifier_names(modifier) {
+            write!(f, "{name}-")?;
+        }
+        match KEY_NAMES
+            .iter()
+            .find(|(key_code, _)| *key_code == self.key_code)
+        {
+            Some((_, names)) => write!(f, "{}", names[0]),
+            None => match self.key_code {
+                KeyCode::Char(c) => write!(f, "{c}"),
+                KeyCode::F(number) => write!(f, "F{number}"),
+                // Every other key has a name
+                key_code => write!(f, "{key_code:?}"),
+            },
+        }
+    }
+}
+
+/// Names of the `modifier` flags in key notation, like `C` and `M` for `C-M-x`
+pub(crate) fn modifier_names(modifier: KeyModifiers) -> impl Iterator<Item = &'static str> {
+    MODIFIER_NAMES
+        .into_iter()
+        .filter(move |(flag, _)| modifier.contains(*flag))
+        .map(|(_, names)| names[0])
+}
+
+/// Sequence of keys written in key notation, like `C-x C-e` or `g g`
+///
+/// Keys are separated by whitespace, Vim style keys in angle brackets can also follow each
+/// other directly as in `<C-x><C-e>`. Serializes as a string in that notation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence(pub Vec<KeyCombination>);
+
+impl FromStr for KeySequence {
+    type Err = KeyNotationError;
+
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let mut keys = vec![];
+        for token in notation.split_whitespace() {
+            match token.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+                Some(inner) if inner.contains("><") => {
+                    for key in inner.split("><") {
+                        keys.push(key.parse()?);
+                    }
+                }
+                _ => keys.push(token.parse()?),
+            }
+        }
+        if keys.is_empty() {
+            return Err(KeyNotationError::Empty);
+        }
+        Ok(KeySequence(keys))
+    }
+}
+
+impl Display for KeySequence {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (idx, key) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{key}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for KeySequence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySequence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let notation = String::deserialize(deserializer)?;
+        notation.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A binding of a [`KeybindingFile`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeybindingEntry {
+    /// Keys to press, e.g. `"C-x C-e"`
+    pub keys: KeySequence,
+    /// Event the keys trigger
+    pub event: ReedlineEvent,
+}
+
+/// [`Keybindings`] in a form that can be written by hand, e.g. as TOML or JSON
+///
+/// ## Example
+/// ```toml
+/// [[bindings]]
+/// keys = "C-x C-e"
+/// event = "OpenEditor"
+///
+/// [[bindings]]
+/// keys = "<A-Enter>"
+/// event = { Edit = ["InsertNewline"] }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct KeybindingFile {
+    /// The bindings, later ones replace earlier ones with the same keys
+    pub bindings: Vec<KeybindingEntry>,
+}
+
+impl KeybindingFile {
+    /// Add the bindings of the file to the `keybindings`
+    ///
+    /// # Panics
+    ///
+    /// If an event is an empty [`ReedlineEvent::UntilFound`]
+    pub fn add_to(self, keybindings: &mut Keybindings) {
+        for KeybindingEntry { keys, event } in self.bindings {
+            let mut keys = keys.0.into_iter();
+            if let Some(first) = keys.next() {
+                keybindings.add_binding(first, keys.collect(), event);
+            }
+        }
+    }
+}
+
+impl From<KeybindingFile> for Keybindings {
+    fn from(file: KeybindingFile) -> Self {
+        let mut keybindings = Keybindings::new();
+        file.add_to(&mut keybindings);
+        keybindings
+    }
+}
+
+impl From<&Keybindings> for KeybindingFile {
+    /// The bindings sorted by their keys in key notation
+    fn from(keybindings: &Keybindings) -> Self {
+        let mut bindings: Vec<_> = keybindings
+            .get_keybindings()
+            .into_iter()
+            .map(|(keys, event)| KeybindingEntry {
+                keys: KeySequence(keys),
+                event,
+            })
+            .collect();
+        bindings.sort_by_cached_key(|binding| binding.keys.to_string());
+        KeybindingFile { bindings }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::default_emacs_keybindings;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn key(modifier: KeyModifiers, key_code: KeyCode) -> KeyCombination {
+        KeyCombination { modifier, key_code }
+    }
+
+    #[rstest]
+    #[case("C-x C-e", vec![key(KeyModifiers::CONTROL, KeyCode::Char('x')), key(KeyModifiers::CONTROL, KeyCode::Char('e'))])]
+    #[case("<A-Enter>", vec![key(KeyModifiers::ALT, KeyCode::Enter)])]
+    #[case("ctrl+shift+k", vec![key(KeyModifiers::CONTROL | KeyModifiers::SHIFT, KeyCode::Char('k'))])]
+    #[case("g g", vec![key(KeyModifiers::NONE, KeyCode::Char('g')), key(KeyModifiers::NONE, KeyCode::Char('g'))])]
+    #[case("<C-x><C-e>", vec![key(KeyModifiers::CONTROL, KeyCode::Char('x')), key(KeyModifiers::CONTROL, KeyCode::Char('e'))])]
+    #[case("G", vec![key(KeyModifiers::SHIFT, KeyCode::Char('g'))])]
+    #[case("C-- M-+ ctrl++", vec![key(KeyModifiers::CONTROL, KeyCode::Char('-')), key(KeyModifiers::ALT, KeyCode::Char('+')), key(KeyModifiers::CONTROL, KeyCode::Char('+'))])]
+    #[case("< - c f12 S-tab", vec![key(KeyModifiers::NONE, KeyCode::Char('<')), key(KeyModifiers::NONE, KeyCode::Char('-')), key(KeyModifiers::NONE, KeyCode::Char('c')), key(KeyModifiers::NONE, KeyCode::F(12)), key(KeyModifiers::SHIFT, KeyCode::Tab)])]
+    fn test_parse_key_sequence(#[case] notation: &str, #[case] expected: Vec<KeyCombination>) {
+        assert_eq!(notation.parse(), Ok(KeySequence(expected)));
+    }
+
+    #[rstest]
+    #[case("", KeyNotationError::Empty)]
+    #[case("C-x gg", KeyNotationError::UnknownKey("gg".to_string()))]
+    #[case("Foo-x", KeyNotationError::UnknownKey("Foo-x".to_string()))]
+    #[case("F25", KeyNotationError::UnknownKey("F25".to_string()))]
+    fn test_parse_errors(#[case] notation: &str, #[case] expected: KeyNotationError) {
+        assert_eq!(notation.parse::<KeySequence>(), Err(expected));
+    }
+
+    #[rstest]
+    #[case("C-x C-e", "C-x C-e")]
+    #[case("<A-Enter>", "M-Enter")]
+    #[case("ctrl+shift+k", "C-S-k")]
+    #[case("S-g", "G")]
+    #[case("C-Space super+f3", "C-Space Super-F3")]
+    fn test_format_key_sequence(#[case] notation: &str, #[case] expected: &str) {
+        let keys: KeySequence = notation.parse().unwrap();
+
+        assert_eq!(keys.to_string(), expected);
+        assert_eq!(expected.parse(), Ok(keys));
+    }
+
+    #[test]
+    fn every_named_key_round_trips() {
+        for (key_code, _) in KEY_NAMES {
+            let keys = KeySequence(vec![
+                key(KeyModifiers::NONE, key_code),
+                key(KeyModifiers::CONTROL | KeyModifiers::ALT, key_code),
+            ]);
+
+            assert_eq!(keys.to_string().parse(), Ok(keys));
+        }
+    }
+
+    #[test]
+    fn binding_file_round_trips() {
+        let keybindings = default_emacs_keybindings();
+        let file = KeybindingFile::from(&keybindings);
+        let json = serde_json::to_string(&file).unwrap();
+        let parsed: KeybindingFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, file);
+        assert_eq!(
+            KeybindingFile::from(&Keybindings::from(parsed)),
+            KeybindingFile::from(&keybindings)
+        );
+    }
+
+    #[test]
+    fn binding_file_is_written_in_key_notation() {
+        let file: KeybindingFile = serde_json::from_str(
+            r#"{"bindings": [
+                {"keys": "C-x C-e", "event": "OpenEditor"},
+                {"keys": "<A-Enter>", "event": {"Edit": ["InsertNewline"]}}
+            ]}"#,
+        )
+        .unwrap();
+        let keybindings = Keybindings::from(file);
+
+        assert_eq!(
+            serde_json::to_string(&KeybindingFile::from(&keybindings)).unwrap(),
+            r#"{"bindings":[{"keys":"C-x C-e","event":"OpenEditor"},{"keys":"M-Enter","event":{"Edit":["InsertNewline"]}}]}"#
+        );
+    }
+}
@@ -8,6 +8,13 @@ mod vi;
 pub use base::EditMode;
 pub use cursors::CursorConfig;
 pub use emacs::{default_emacs_keybindings, Emacs};
-pub use helix::{default_helix_insert_keybindings, default_helix_normal_keybindings, Helix};
-pub use keybindings::{KeyCombination, Keybindings};
+pub use helix::{
+    default_helix_insert_keybindings, default_helix_match_keybindings,
+    default_helix_normal_keybindings, Helix,
+};
+pub(crate) use keybindings::modifier_names;
+pub use keybindings::{
+    Inputrc, InputrcEditingMode, InputrcIssue, KeyCombination, KeyNotationError, KeySequence,
+    KeybindingConflict, KeybindingEntry, KeybindingFile, Keybindings,
+};
 pub use vi::{default_vi_insert_keybindings, default_vi_normal_keybindings, Vi};
@@ -1,28 +1,65 @@
 use super::{motion::Motion, motion::ViCharSearch, parser::ReedlineOption};
-use crate::{EditCommand, ReedlineEvent, Vi};
+use crate::{
+    enums::{TextObject, TextObjectKind},
+    EditCommand, ReedlineEvent, Registers, Vi,
+};
 use std::iter::Peekable;
 
 pub fn parse_command<'iter, I>(input: &mut Peekable<I>) -> Option<Command>
 where
-    I: Iterator<Item = &'iter char>,
+    I: Iterator<Item = &'iter char> + Clone,
 {
     match input.peek() {
         Some('d') => {
             let _ = input.next();
-            if let Some('i') = input.peek() {
-                let _ = input.next();
-                match input.next() {
-                    Some(c)
-                        if is_valid_change_inside_left(c) || is_valid_change_inside_right(c) =>
-                    {
-                        Some(Command::DeleteInside(*c))
-                    }
-                    _ => None,
+            match input.peek() {
+                Some('i') | Some('a') => {
+                    parse_text_object_command(input, Command::DeleteTextObject)
                 }
-            } else {
-                Some(Command::Delete)
+                _ => Some(Command::Delete),
             }
         }
+        Some('y') => {
+            let _ = input.next();
+            match input.peek() {
+                Some('i') | Some('a') => parse_text_object_command(input, Command::YankTextObject),
+                _ => Some(Command::Yank),
+            }
+        }
+        Some('g') => {
+            // An unknown key after `g` leaves it to the motions, which reject it
+            let mut following = input.clone();
+            let _ = following.next();
+            let (command, text_object_command): (_, fn(TextObject) -> Command) =
+                match following.peek() {
+                    Some('u') => (Command::Lowercase, Command::LowercaseTextObject),
+                    Some('U') => (Command::Uppercase, Command::UppercaseTextObject),
+                    Some('~') => (Command::SwitchcaseOperator, Command::SwitchcaseTextObject),
+                    Some(_) => return None,
+                    None => {
+                        let _ = input.next();
+                        return Some(Command::Incomplete);
+                    }
+                };
+            let _ = input.next();
+            let _ = input.next();
+            match input.peek() {
+                Some('i') | Some('a') => parse_text_object_command(input, text_object_command),
+                _ => Some(command),
+            }
+        }
+        Some('>') => {
+            let _ = input.next();
+            Some(Command::Indent)
+        }
+        Some('<') => {
+            let _ = input.next();
+            Some(Command::Dedent)
+        }
+        Some('J') => {
+            let _ = input.next();
+            Some(Command::JoinLines)
+        }
         Some('p') => {
             let _ = input.next();
             Some(Command::PasteAfter)
@@ -33,11 +70,18 @@ where
         }
         Some('i') => {
             let _ = input.next();
-            Some(Command::EnterViInsert)
+            // Only reachable in visual mode, where `i` is waiting for a text object
+            match parse_text_object(input, false) {
+                Some(text_object) => Some(Command::SelectTextObject(text_object)),
+                None => Some(Command::EnterViInsert),
+            }
         }
         Some('a') => {
             let _ = input.next();
-            Some(Command::EnterViAppend)
+            match parse_text_object(input, true) {
+                Some(text_object) => Some(Command::SelectTextObject(text_object)),
+                None => Some(Command::EnterViAppend),
+            }
         }
         Some('u') => {
             let _ = input.next();
@@ -45,18 +89,11 @@ where
         }
         Some('c') => {
             let _ = input.next();
-            if let Some('i') = input.peek() {
-                let _ = input.next();
-                match input.next() {
-                    Some(c)
-                        if is_valid_change_inside_left(c) || is_valid_change_inside_right(c) =>
-                    {
-                        Some(Command::ChangeInside(*c))
-                    }
-                    _ => None,
+            match input.peek() {
+                Some('i') | Some('a') => {
+                    parse_text_object_command(input, Command::ChangeTextObject)
                 }
-            } else {
-                Some(Command::Change)
+                _ => Some(Command::Change),
             }
         }
         Some('x') => {
@@ -74,9 +111,47 @@ where
             let _ = input.next();
             Some(Command::SubstituteCharWithInsert)
         }
+        Some('/') => {
+            let _ = input.next();
+            Some(Command::SearchForward)
+        }
         Some('?') => {
             let _ = input.next();
-            Some(Command::HistorySearch)
+            Some(Command::SearchBackward)
+        }
+        Some('n') => {
+            let _ = input.next();
+            Some(Command::SearchNext)
+        }
+        Some('N') => {
+            let _ = input.next();
+            Some(Command::SearchPrevious)
+        }
+        Some(':') => {
+            let _ = input.next();
+            Some(Command::ExCommandLine)
+        }
+        Some('q') => {
+            let _ = input.next();
+            match input.peek() {
+                Some(&&name) if Registers::is_valid(name) => {
+                    let _ = input.next();
+                    Some(Command::RecordMacro(name))
+                }
+                Some(_) => None,
+                None => Some(Command::Incomplete),
+            }
+        }
+        Some('@') => {
+            let _ = input.next();
+            match input.peek() {
+                Some(&&name) if name == '@' || Registers::is_valid(name) => {
+                    let _ = input.next();
+                    Some(Command::ReplayMacro(name))
+                }
+                Some(_) => None,
+                None => Some(Command::Incomplete),
+            }
         }
         Some('C') => {
             let _ = input.next();
@@ -110,6 +185,37 @@ where
     }
 }
 
+/// Parse the `i` or `a` following an operator together with the text object it selects
+fn parse_text_object_command<'iter, I>(
+    input: &mut Peekable<I>,
+    command: fn(TextObject) -> Command,
+) -> Option<Command>
+where
+    I: Iterator<Item = &'iter char>,
+{
+    let around = input.next() == Some(&'a');
+    match parse_text_object(input, around) {
+        Some(text_object) => Some(command(text_object)),
+        None if input.peek().is_none() => Some(Command::Incomplete),
+        None => None,
+    }
+}
+
+/// Parse the character naming a text object, e.g. the `w` in `diw`
+fn parse_text_object<'iter, I>(input: &mut Peekable<I>, around: bool) -> Option<TextObject>
+where
+    I: Iterator<Item = &'iter char>,
+{
+    let kind = match input.peek() {
+        Some('w') => TextObjectKind::Word,
+        Some('W') => TextObjectKind::BigWord,
+        Some(c) if is_valid_text_object_pair(c) => TextObjectKind::Pair(**c),
+        _ => return None,
+    };
+    let _ = input.next();
+    Some(TextObject { kind, around })
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Command {
     Incomplete,
@@ -128,11 +234,36 @@ pub enum Command {
     PrependToStart,
     RewriteCurrentLine,
     Change,
-    HistorySearch,
+    Yank,
+    SearchForward,
+    SearchBackward,
+    SearchNext,
+    SearchPrevious,
+    ExCommandLine,
+    /// `q` followed by the register the macro is recorded into
+    RecordMacro(char),
+    /// `@` followed by the register of the macro, `@` for the last replayed one
+    ReplayMacro(char),
     Switchcase,
+    /// `gu`, lowercase the text covered by a motion
+    Lowercase,
+    /// `gU`, uppercase the text covered by a motion
+    Uppercase,
+    /// `g~`, switch the case of the text covered by a motion
+    SwitchcaseOperator,
+    /// `>`, indent the lines covered by a motion
+    Indent,
+    /// `<`, dedent the lines covered by a motion
+    Dedent,
+    JoinLines,
     RepeatLastAction,
-    ChangeInside(char),
-    DeleteInside(char),
+    ChangeTextObject(TextObject),
+    DeleteTextObject(TextObject),
+    YankTextObject(TextObject),
+    SelectTextObject(TextObject),
+    LowercaseTextObject(TextObject),
+    UppercaseTextObject(TextObject),
+    SwitchcaseTextObject(TextObject),
 }
 
 impl Command {
@@ -140,12 +271,28 @@ impl Command {
         match self {
             Command::Delete => Some('d'),
             Command::Change => Some('c'),
+            Command::Yank => Some('y'),
+            Command::Lowercase => Some('u'),
+            Command::Uppercase => Some('U'),
+            Command::SwitchcaseOperator => Some('~'),
+            Command::Indent => Some('>'),
+            Command::Dedent => Some('<'),
             _ => None,
         }
     }
 
     pub fn requires_motion(&self) -> bool {
-        matches!(self, Command::Delete | Command::Change)
+        matches!(
+            self,
+            Command::Delete
+                | Command::Change
+                | Command::Yank
+                | Command::Lowercase
+                | Command::Uppercase
+                | Command::SwitchcaseOperator
+                | Command::Indent
+                | Command::Dedent
+        )
     }
 
     pub fn to_reedline(&self, vi_state: &mut Vi) -> Vec<ReedlineOption> {
@@ -171,55 +318,90 @@ impl Command {
                 vec![ReedlineOption::Edit(EditCommand::ReplaceChar(*c))]
             }
             Self::SubstituteCharWithInsert => vec![ReedlineOption::Edit(EditCommand::CutChar)],
-            Self::HistorySearch => vec![ReedlineOption::Event(ReedlineEvent::SearchHistory)],
+            Self::SearchForward => vec![ReedlineOption::Event(ReedlineEvent::SearchBuffer {
+                backward: false,
+                select: false,
+            })],
+            Self::SearchBackward => vec![ReedlineOption::Event(ReedlineEvent::SearchBuffer {
+                backward: true,
+                select: false,
+            })],
+            Self::SearchNext => vec![ReedlineOption::Event(ReedlineEvent::SearchBufferNext)],
+            Self::SearchPrevious => {
+                vec![ReedlineOption::Event(ReedlineEvent::SearchBufferPrevious)]
+            }
+            Self::ExCommandLine => vec![ReedlineOption::Event(ReedlineEvent::ExCommandLine)],
+            Self::RecordMacro(name) => {
+                vi_state.start_recording(*name);
+                vec![ReedlineOption::Event(ReedlineEvent::Repaint)]
+            }
+            Self::ReplayMacro(name) => vec![ReedlineOption::Event(vi_state.replay_macro(*name, 1))],
             Self::Switchcase => vec![ReedlineOption::Edit(EditCommand::SwitchcaseChar)],
+            Self::JoinLines => vec![ReedlineOption::Edit(EditCommand::JoinLines)],
             // Whenever a motion is required to finish the command we must be in visual mode
             Self::Delete | Self::Change => vec![ReedlineOption::Edit(EditCommand::CutSelection)],
+            Self::Yank => vec![ReedlineOption::Edit(EditCommand::CopySelection)],
+            Self::Lowercase => vec![ReedlineOption::Edit(EditCommand::LowercaseSelection)],
+            Self::Uppercase => vec![ReedlineOption::Edit(EditCommand::UppercaseSelection)],
+            Self::SwitchcaseOperator => {
+                vec![ReedlineOption::Edit(EditCommand::SwitchcaseSelection)]
+            }
+            Self::Indent => vec![ReedlineOption::Edit(EditCommand::IndentLines)],
+            Self::Dedent => vec![ReedlineOption::Edit(EditCommand::DedentLines)],
             Self::Incomplete => vec![ReedlineOption::Incomplete],
             Self::RepeatLastAction => match &vi_state.previous {
                 Some(event) => vec![ReedlineOption::Event(event.clone())],
                 None => vec![],
             },
-            Self::ChangeInside(left) if is_valid_change_inside_left(left) => {
-                let right = bracket_for(left);
-                vec![
-                    ReedlineOption::Edit(EditCommand::CutLeftBefore(*left)),
-                    ReedlineOption::Edit(EditCommand::CutRightBefore(right)),
-                ]
-            }
-            Self::ChangeInside(right) if is_valid_change_inside_right(right) => {
-                let left = bracket_for(right);
-                vec![
-                    ReedlineOption::Edit(EditCommand::CutLeftBefore(left)),
-                    ReedlineOption::Edit(EditCommand::CutRightBefore(*right)),
-                ]
-            }
-            Self::ChangeInside(_) => {
-                vec![]
+            Self::ChangeTextObject(text_object) | Self::DeleteTextObject(text_object) => {
+                vec![ReedlineOption::Edit(EditCommand::CutTextObject(
+                    *text_object,
+                ))]
             }
-            Self::DeleteInside(left) if is_valid_change_inside_left(left) => {
-                let right = bracket_for(left);
-                vec![
-                    ReedlineOption::Edit(EditCommand::CutLeftBefore(*left)),
-                    ReedlineOption::Edit(EditCommand::CutRightBefore(right)),
-                ]
+            Self::YankTextObject(text_object) => {
+                vec![ReedlineOption::Edit(EditCommand::CopyTextObject(
+                    *text_object,
+                ))]
             }
-            Self::DeleteInside(right) if is_valid_change_inside_right(right) => {
-                let left = bracket_for(right);
-                vec![
-                    ReedlineOption::Edit(EditCommand::CutLeftBefore(left)),
-                    ReedlineOption::Edit(EditCommand::CutRightBefore(*right)),
-                ]
-            }
-            Self::DeleteInside(_) => {
-                vec![]
+            Self::SelectTextObject(text_object) => {
+                vec![ReedlineOption::Edit(EditCommand::SelectTextObject(
+                    *text_object,
+                ))]
             }
+            Self::LowercaseTextObject(text_object) => vec![
+                ReedlineOption::Edit(EditCommand::SelectTextObject(*text_object)),
+                ReedlineOption::Edit(EditCommand::LowercaseSelection),
+            ],
+            Self::UppercaseTextObject(text_object) => vec![
+                ReedlineOption::Edit(EditCommand::SelectTextObject(*text_object)),
+                ReedlineOption::Edit(EditCommand::UppercaseSelection),
+            ],
+            Self::SwitchcaseTextObject(text_object) => vec![
+                ReedlineOption::Edit(EditCommand::SelectTextObject(*text_object)),
+                ReedlineOption::Edit(EditCommand::SwitchcaseSelection),
+            ],
         }
     }
 
+    /// Operators that apply once to the text covered by the counted motion, instead of being
+    /// repeated for the count
+    pub fn counts_motion(&self) -> bool {
+        matches!(
+            self,
+            Command::Lowercase
+                | Command::Uppercase
+                | Command::SwitchcaseOperator
+                | Command::Indent
+                | Command::Dedent
+        )
+    }
+
+    /// Events of the command followed by the `motion`, the `count` only applies to commands
+    /// that [count the motion](Self::counts_motion)
     pub fn to_reedline_with_motion(
         &self,
         motion: &Motion,
+        count: usize,
         vi_state: &mut Vi,
     ) -> Option<Vec<ReedlineOption>> {
         match self {
@@ -332,29 +514,82 @@ impl Command {
                     vec
                 })
             }
+            Self::Yank => match motion {
+                Motion::End => Some(vec![ReedlineOption::Edit(EditCommand::CopyToLineEnd)]),
+                Motion::Line => Some(vec![ReedlineOption::Edit(EditCommand::CopyCurrentLine)]),
+                Motion::NextWord => {
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyWordRightToNext)])
+                }
+                Motion::NextBigWord => Some(vec![ReedlineOption::Edit(
+                    EditCommand::CopyBigWordRightToNext,
+                )]),
+                Motion::NextWordEnd => Some(vec![ReedlineOption::Edit(EditCommand::CopyWordRight)]),
+                Motion::NextBigWordEnd => {
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyBigWordRight)])
+                }
+                Motion::PreviousWord => Some(vec![ReedlineOption::Edit(EditCommand::CopyWordLeft)]),
+                Motion::PreviousBigWord => {
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyBigWordLeft)])
+                }
+                Motion::RightUntil(c) => {
+                    vi_state.last_char_search = Some(ViCharSearch::ToRight(*c));
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyRightUntil(*c))])
+                }
+                Motion::RightBefore(c) => {
+                    vi_state.last_char_search = Some(ViCharSearch::TillRight(*c));
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyRightBefore(*c))])
+                }
+                Motion::LeftUntil(c) => {
+                    vi_state.last_char_search = Some(ViCharSearch::ToLeft(*c));
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyLeftUntil(*c))])
+                }
+                Motion::LeftBefore(c) => {
+                    vi_state.last_char_search = Some(ViCharSearch::TillLeft(*c));
+                    Some(vec![ReedlineOption::Edit(EditCommand::CopyLeftBefore(*c))])
+                }
+                Motion::Start => Some(vec![ReedlineOption::Edit(EditCommand::CopyFromLineStart)]),
+                Motion::Left => Some(vec![ReedlineOption::Edit(EditCommand::CopyLeft)]),
+                Motion::Right => Some(vec![ReedlineOption::Edit(EditCommand::CopyRight)]),
+                Motion::Up => None,
+                Motion::Down => None,
+                Motion::ReplayCharSearch => vi_state
+                    .last_char_search
+                    .as_ref()
+                    .map(|char_search| vec![ReedlineOption::Edit(char_search.to_copy())]),
+                Motion::ReverseCharSearch => vi_state
+                    .last_char_search
+                    .as_ref()
+                    .map(|char_search| vec![ReedlineOption::Edit(char_search.reverse().to_copy())]),
+            },
+            Self::Lowercase
+            | Self::Uppercase
+            | Self::SwitchcaseOperator
+            | Self::Indent
+            | Self::Dedent => {
+                let selection = motion.to_selection(vi_state, count)?;
+                let operation = match self {
+                    Self::Lowercase => EditCommand::LowercaseSelection,
+                    Self::Uppercase => EditCommand::UppercaseSelection,
+                    Self::SwitchcaseOperator => EditCommand::SwitchcaseSelection,
+                    Self::Indent => EditCommand::IndentLines,
+                    _ => EditCommand::DedentLines,
+                };
+                Some(
+                    selection
+                        .into_iter()
+                        .chain([operation])
+                        .map(ReedlineOption::Edit)
+                        .collect(),
+                )
+            }
             _ => None,
         }
     }
 }
 
-fn bracket_for(c: &char) -> char {
-    match *c {
-        '(' => ')',
-        '[' => ']',
-        '{' => '}',
-        '<' => '>',
-        ')' => '(',
-        ']' => '[',
-        '}' => '{',
-        '>' => '<',
-        _ => *c,
-    }
-}
-
-pub(crate) fn is_valid_change_inside_left(c: &char) -> bool {
-    matches!(c, '(' | '[' | '{' | '"' | '\'' | '`' | '<')
-}
-
-pub(crate) fn is_valid_change_inside_right(c: &char) -> bool {
-    matches!(c, ')' | ']' | '}' | '"' | '\'' | '`' | '>')
+fn is_valid_text_object_pair(c: &char) -> bool {
+    matches!(
+        c,
+        '(' | '[' | '{' | '<' | ')' | ']' | '}' | '>' | '"' | '\'' | '`'
+    )
 }
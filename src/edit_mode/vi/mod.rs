@@ -8,7 +8,7 @@ use std::collections::HashMap;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 pub use vi_keybindings::{default_vi_insert_keybindings, default_vi_normal_keybindings};
 
-use self::motion::ViCharSearch;
+pub(super) use self::motion::ViCharSearch;
 
 use super::{
     keybindings::{
@@ -18,7 +18,7 @@ use super::{
 };
 use crate::{
     edit_mode::{keybindings::Keybindings, vi::parser::parse},
-    enums::{EditCommand, ReedlineEvent, ReedlineRawEvent},
+    enums::{EditCommand, ReedlineEvent, ReedlineRawEvent, SelectionMode},
     PromptEditMode, PromptViMode,
 };
 
@@ -27,6 +27,23 @@ enum ViMode {
     Normal,
     Insert,
     Visual,
+    /// Visual mode started with `V`, selecting whole lines
+    VisualLine,
+    /// Visual mode started with `Ctrl-V`, selecting a block
+    VisualBlock,
+    /// Typing the term of a buffer search started with `/` or `?`
+    Search,
+    /// Typing a command on the ex command line started with `:`
+    ExCommand,
+}
+
+impl ViMode {
+    fn is_visual(self) -> bool {
+        matches!(
+            self,
+            ViMode::Visual | ViMode::VisualLine | ViMode::VisualBlock
+        )
+    }
 }
 
 /// This parses incoming input `Event`s like a Vi-Style editor
@@ -39,6 +56,13 @@ pub struct Vi {
     // last f, F, t, T motion for ; and ,
     last_char_search: Option<ViCharSearch>,
     partial_key_sequence: Option<PartialKeySequence>,
+    // Register and key events of the macro being recorded with `q`
+    recording: Option<(char, Vec<ReedlineRawEvent>)>,
+    // Recorded macros by register, replayed with `@`
+    macros: HashMap<char, Vec<ReedlineRawEvent>>,
+    // Register of the last replayed macro for `@@`
+    last_macro: Option<char>,
+    replaying: bool,
 }
 
 impl Default for Vi {
@@ -51,6 +75,10 @@ impl Default for Vi {
             previous: None,
             last_char_search: None,
             partial_key_sequence: None,
+            recording: None,
+            macros: HashMap::new(),
+            last_macro: None,
+            replaying: false,
         }
     }
 }
@@ -67,9 +95,131 @@ impl Vi {
 
     fn active_bindings(&self) -> &Keybindings {
         match self.mode {
-            ViMode::Normal => &self.normal_keybindings,
-            ViMode::Visual => &self.normal_keybindings,
-            ViMode::Insert => &self.insert_keybindings,
+            ViMode::Normal | ViMode::Visual | ViMode::VisualLine | ViMode::VisualBlock => {
+                &self.normal_keybindings
+            }
+            ViMode::Insert | ViMode::Search | ViMode::ExCommand => &self.insert_keybindings,
+        }
+    }
+
+    /// Enter, switch between or leave the visual modes with `v`, `V` and `Ctrl-V`
+    fn toggle_visual_mode(&mut self, modifier: KeyModifiers, c: char) -> Option<ReedlineEvent> {
+        let (mode, selection_mode) = match (modifier, c) {
+            (KeyModifiers::NONE, 'v') => (ViMode::Visual, SelectionMode::Char),
+            (KeyModifiers::SHIFT, 'v') => (ViMode::VisualLine, SelectionMode::Line),
+            (KeyModifiers::CONTROL, 'v') => (ViMode::VisualBlock, SelectionMode::Block),
+            _ => return None,
+        };
+        self.cache.clear();
+        let events = if self.mode == mode {
+            self.mode = ViMode::Normal;
+            vec![
+                ReedlineEvent::Edit(vec![EditCommand::ClearSelection]),
+                ReedlineEvent::Repaint,
+            ]
+        } else if self.mode == ViMode::Normal && mode == ViMode::Visual {
+            // The selection starts with the first motion
+            self.mode = mode;
+            vec![ReedlineEvent::Esc, ReedlineEvent::Repaint]
+        } else {
+            self.mode = mode;
+            vec![
+                ReedlineEvent::Edit(vec![EditCommand::SetSelectionMode(selection_mode)]),
+                ReedlineEvent::Repaint,
+            ]
+        };
+        Some(ReedlineEvent::Multiple(events))
+    }
+
+    /// Add the count typed before `Ctrl-A` to the number under or after the cursor,
+    /// or subtract it with `Ctrl-X`
+    fn increment_number(&mut self, modifier: KeyModifiers, c: char) -> Option<ReedlineEvent> {
+        let command: fn(i64) -> EditCommand = match (modifier, c) {
+            (KeyModifiers::CONTROL, 'a') => EditCommand::IncrementNumber,
+            (KeyModifiers::CONTROL, 'x') => EditCommand::DecrementNumber,
+            _ => return None,
+        };
+        let typed = std::mem::take(&mut self.cache);
+        // Only a count may come first, a pending register or operator cancels the command
+        if !typed.iter().all(char::is_ascii_digit) {
+            return Some(ReedlineEvent::None);
+        }
+        let count = typed.iter().collect::<String>().parse().unwrap_or(1);
+        let event = ReedlineEvent::Edit(vec![command(count)]);
+        self.previous = Some(event.clone());
+        Some(event)
+    }
+
+    /// Start recording the key events into the macro register `name`
+    ///
+    /// Like with text, an uppercase register appends to the macro already stored.
+    fn start_recording(&mut self, name: char) {
+        let events = if name.is_ascii_uppercase() {
+            self.macros
+                .remove(&name.to_ascii_lowercase())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        self.recording = Some((name.to_ascii_lowercase(), events));
+    }
+
+    /// Stop the recording on a `q` typed in normal or visual mode outside of a command
+    fn stop_recording(&mut self, event: &ReedlineRawEvent) -> bool {
+        let is_q = matches!(
+            Event::from(event.clone()),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('q'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            })
+        );
+        if !is_q
+            || !(self.mode == ViMode::Normal || self.mode.is_visual())
+            || !self.cache.is_empty()
+            || self.partial_key_sequence.is_some()
+        {
+            return false;
+        }
+        match self.recording.take() {
+            Some((name, events)) => {
+                self.macros.insert(name, events);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Parse the key events of the macro in register `name` again `count` times
+    ///
+    /// `@` replays the last replayed macro. Macros can't replay other macros.
+    fn replay_macro(&mut self, name: char, count: usize) -> ReedlineEvent {
+        let name = match name {
+            '@' => self.last_macro,
+            name => Some(name.to_ascii_lowercase()),
+        };
+        let Some(name) = name else {
+            return ReedlineEvent::None;
+        };
+        let Some(recorded) = self.macros.get(&name).filter(|_| !self.replaying).cloned() else {
+            return ReedlineEvent::None;
+        };
+        self.last_macro = Some(name);
+        self.cache.clear();
+
+        self.replaying = true;
+        let events: Vec<ReedlineEvent> = std::iter::repeat(recorded)
+            .take(count)
+            .flatten()
+            .map(|event| self.parse_key_event(event.into()))
+            .filter(|event| *event != ReedlineEvent::None)
+            .collect();
+        self.replaying = false;
+
+        if events.is_empty() {
+            ReedlineEvent::None
+        } else {
+            ReedlineEvent::Multiple(events)
         }
     }
 
@@ -78,12 +228,8 @@ impl Vi {
             self.active_bindings()
                 .find_binding(kc.modifier, to_lowercase_key_code(kc.key_code))
                 .map(|key_node| {
-                    PartialKeySequence::new(match key_node {
-                        KeyNode::Sequence(sequence) => sequence,
-                        KeyNode::Event(reedline_event) => Sequence {
-                            // TODO: really, really, REALLY hacky
-                            map: HashMap::from([(kc.clone(), KeyNode::Event(reedline_event))]),
-                        },
+                    PartialKeySequence::new(Sequence {
+                        map: HashMap::from([(kc.clone(), key_node)]),
                     })
                 })
         }) else {
@@ -95,37 +241,170 @@ impl Vi {
         };
 
         match partial_key_sequence.advance(kc) {
-            KeySequenceResult::Pending => None,
+            KeySequenceResult::Pending => {
+                self.partial_key_sequence = Some(partial_key_sequence);
+                None
+            }
+            // A sequence like `jk` bound to Esc leaves insert mode like the key itself
+            KeySequenceResult::Matched(ReedlineEvent::Esc) => Some(self.enter_normal_mode()),
             KeySequenceResult::Matched(reedline_event) => Some(reedline_event),
             KeySequenceResult::Cancelled(keycombinations) => {
-                let mut events = vec![];
-                for kc in keycombinations {
-                    if let KeyCode::Char(c) = kc.key_code {
-                        events.push(ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)]))
-                    } else {
-                        match self
-                            .active_bindings()
-                            .find_binding(kc.modifier, to_lowercase_key_code(kc.key_code))
-                        {
-                            Some(KeyNode::Event(event)) => events.push(event),
-                            Some(KeyNode::Sequence(_)) => unreachable!(""),
-                            None => {}
-                        }
+                Some(self.cancel_key_sequence(keycombinations))
+            }
+        }
+    }
+
+    /// Replay the keys of a cancelled sequence as if they weren't bound
+    fn cancel_key_sequence(&mut self, keycombinations: Vec<KeyCombination>) -> ReedlineEvent {
+        let events = keycombinations
+            .into_iter()
+            .filter_map(|kc| match kc {
+                KeyCombination {
+                    modifier: modifier @ (KeyModifiers::NONE | KeyModifiers::SHIFT),
+                    key_code: KeyCode::Char(c),
+                } => Some(match self.mode {
+                    ViMode::Insert | ViMode::Search | ViMode::ExCommand => {
+                        ReedlineEvent::Edit(vec![EditCommand::InsertChar(c)])
                     }
+                    _ => self.parse_command_char(modifier, c),
+                }),
+                _ => match self
+                    .active_bindings()
+                    .find_binding(kc.modifier, to_lowercase_key_code(kc.key_code))?
+                {
+                    KeyNode::Event(event) => Some(event),
+                    // A prefix on its own doesn't do anything
+                    KeyNode::Sequence(_) => None,
+                },
+            })
+            .collect();
+        ReedlineEvent::Multiple(events)
+    }
+
+    /// Parse a character typed in normal or visual mode that isn't bound to an event
+    fn parse_command_char(&mut self, modifier: KeyModifiers, c: char) -> ReedlineEvent {
+        if let Some(event) = self.toggle_visual_mode(modifier, c) {
+            event
+        } else if let Some(event) = self.increment_number(modifier, c) {
+            event
+        } else if modifier == KeyModifiers::NONE || modifier == KeyModifiers::SHIFT {
+            // TODO maybe motions, etc. could be modelled with `Sequence` instead
+            self.cache.push(if modifier == KeyModifiers::SHIFT {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            });
+
+            let res = parse(&mut self.cache.iter().peekable());
+
+            if !res.is_valid() {
+                self.cache.clear();
+                ReedlineEvent::None
+            } else if res.is_complete(self.mode) {
+                if let Some(mode) = res.changes_mode() {
+                    self.mode = mode;
                 }
-                Some(ReedlineEvent::Multiple(events))
+
+                let event = res.to_reedline_event(self);
+                self.cache.clear();
+                event
+            } else {
+                ReedlineEvent::None
             }
+        } else {
+            ReedlineEvent::None
+        }
+    }
+
+    /// Leave the current mode for normal mode with Esc
+    fn enter_normal_mode(&mut self) -> ReedlineEvent {
+        self.cache.clear();
+        self.partial_key_sequence = None;
+        let leaves_visual = self.mode.is_visual();
+        self.mode = ViMode::Normal;
+        if leaves_visual {
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Esc,
+                ReedlineEvent::Edit(vec![EditCommand::ClearSelection]),
+                ReedlineEvent::Repaint,
+            ])
+        } else {
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Esc, ReedlineEvent::Repaint])
         }
     }
 }
 
 impl EditMode for Vi {
     fn parse_event(&mut self, event: ReedlineRawEvent) -> ReedlineEvent {
-        match event.into() {
+        if self.stop_recording(&event) {
+            return ReedlineEvent::Repaint;
+        }
+        if let Some((_, events)) = self.recording.as_mut() {
+            events.push(event.clone());
+        }
+        self.parse_key_event(event.into())
+    }
+
+    fn edit_mode(&self) -> PromptEditMode {
+        let mode = match self.mode {
+            ViMode::Normal | ViMode::Visual => PromptViMode::Normal,
+            ViMode::VisualLine => PromptViMode::VisualLine,
+            ViMode::VisualBlock => PromptViMode::VisualBlock,
+            ViMode::Insert | ViMode::Search | ViMode::ExCommand => PromptViMode::Insert,
+        };
+        match &self.recording {
+            Some((name, _)) => PromptEditMode::Vi(PromptViMode::Recording(*name, Box::new(mode))),
+            None => PromptEditMode::Vi(mode),
+        }
+    }
+
+    fn has_pending_sequence(&self) -> bool {
+        self.partial_key_sequence.is_some()
+    }
+
+    fn cancel_pending_sequence(&mut self) -> ReedlineEvent {
+        match self.partial_key_sequence.take() {
+            Some(partial_key_sequence) => self.cancel_key_sequence(partial_key_sequence.cancel()),
+            None => ReedlineEvent::None,
+        }
+    }
+
+    fn provisional_text(&self) -> Option<String> {
+        match self.mode {
+            ViMode::Insert | ViMode::Search | ViMode::ExCommand => self
+                .partial_key_sequence
+                .as_ref()
+                .and_then(PartialKeySequence::typed_text),
+            _ => None,
+        }
+    }
+
+    fn sequence_continuations(&self) -> Vec<(KeyCombination, Option<ReedlineEvent>)> {
+        self.partial_key_sequence
+            .as_ref()
+            .map(PartialKeySequence::continuations)
+            .unwrap_or_default()
+    }
+
+    fn exit_search(&mut self) {
+        if matches!(self.mode, ViMode::Search | ViMode::ExCommand) {
+            self.partial_key_sequence = None;
+            self.mode = ViMode::Normal;
+        }
+    }
+}
+
+impl Vi {
+    fn parse_key_event(&mut self, event: Event) -> ReedlineEvent {
+        match event {
             Event::Key(KeyEvent {
                 code, modifiers, ..
             }) => match (self.mode, modifiers, code) {
-                (ViMode::Normal | ViMode::Visual, modifier, KeyCode::Char(c)) => {
+                (
+                    ViMode::Normal | ViMode::Visual | ViMode::VisualLine | ViMode::VisualBlock,
+                    modifier,
+                    KeyCode::Char(c),
+                ) => {
                     let c = c.to_ascii_lowercase();
 
                     if let Some(event) = self.handle_binding(KeyCombination {
@@ -133,42 +412,17 @@ impl EditMode for Vi {
                         key_code: KeyCode::Char(c),
                     }) {
                         event
-                    } else if self.mode == ViMode::Normal
-                        && modifier == KeyModifiers::NONE
-                        && matches!(code, KeyCode::Char('v'))
-                    {
-                        self.cache.clear();
-                        self.mode = ViMode::Visual;
-                        ReedlineEvent::Multiple(vec![ReedlineEvent::Esc, ReedlineEvent::Repaint])
-                    } else if modifier == KeyModifiers::NONE || modifier == KeyModifiers::SHIFT {
-                        // TODO maybe motions, etc. could be modelled with `Sequence` instead
-                        self.cache.push(if modifier == KeyModifiers::SHIFT {
-                            c.to_ascii_uppercase()
-                        } else {
-                            c
-                        });
-
-                        let res = parse(&mut self.cache.iter().peekable());
-
-                        if !res.is_valid() {
-                            self.cache.clear();
-                            ReedlineEvent::None
-                        } else if res.is_complete(self.mode) {
-                            if let Some(mode) = res.changes_mode() {
-                                self.mode = mode;
-                            }
-
-                            let event = res.to_reedline_event(self);
-                            self.cache.clear();
-                            event
-                        } else {
-                            ReedlineEvent::None
-                        }
-                    } else {
+                    } else if self.partial_key_sequence.is_some() {
                         ReedlineEvent::None
+                    } else {
+                        self.parse_command_char(modifier, c)
                     }
                 }
-                (ViMode::Insert, modifier, KeyCode::Char(c)) => {
+                (
+                    ViMode::Insert | ViMode::Search | ViMode::ExCommand,
+                    modifier,
+                    KeyCode::Char(c),
+                ) => {
                     // Note. The modifier can also be a combination of modifiers, for
                     // example:
                     //     KeyModifiers::CONTROL | KeyModifiers::ALT
@@ -182,7 +436,9 @@ impl EditMode for Vi {
                         key_code: KeyCode::Char(c),
                     })
                     .unwrap_or_else(|| {
-                        if modifier == KeyModifiers::NONE
+                        if self.partial_key_sequence.is_some() {
+                            ReedlineEvent::None
+                        } else if modifier == KeyModifiers::NONE
                             || modifier == KeyModifiers::SHIFT
                             || modifier == KeyModifiers::CONTROL | KeyModifiers::ALT
                             || modifier
@@ -194,23 +450,26 @@ impl EditMode for Vi {
                         }
                     })
                 }
-                (_, KeyModifiers::NONE, KeyCode::Esc) => {
-                    self.cache.clear();
-                    self.partial_key_sequence = None;
+                (_, KeyModifiers::NONE, KeyCode::Esc) => self.enter_normal_mode(),
+                (ViMode::Search | ViMode::ExCommand, KeyModifiers::NONE, KeyCode::Enter) => {
                     self.mode = ViMode::Normal;
-                    ReedlineEvent::Multiple(vec![ReedlineEvent::Esc, ReedlineEvent::Repaint])
+                    ReedlineEvent::Multiple(vec![ReedlineEvent::Enter, ReedlineEvent::Repaint])
                 }
                 (_, KeyModifiers::NONE, KeyCode::Enter) => {
                     self.mode = ViMode::Insert;
                     ReedlineEvent::Enter
                 }
-                (ViMode::Normal | ViMode::Visual, _, _) => self
+                (
+                    ViMode::Normal | ViMode::Visual | ViMode::VisualLine | ViMode::VisualBlock,
+                    _,
+                    _,
+                ) => self
                     .handle_binding(KeyCombination {
                         modifier: modifiers,
                         key_code: code,
                     })
                     .unwrap_or(ReedlineEvent::None),
-                (ViMode::Insert, _, _) => self
+                (ViMode::Insert | ViMode::Search | ViMode::ExCommand, _, _) => self
                     .handle_binding(KeyCombination {
                         modifier: modifiers,
                         key_code: code,
@@ -227,19 +486,14 @@ impl EditMode for Vi {
             )]),
         }
     }
-
-    fn edit_mode(&self) -> PromptEditMode {
-        match self.mode {
-            ViMode::Normal | ViMode::Visual => PromptEditMode::Vi(PromptViMode::Normal),
-            ViMode::Insert => PromptEditMode::Vi(PromptViMode::Insert),
-        }
-    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::enums::{TextObject, TextObjectKind};
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
     #[test]
     fn esc_leads_to_normal_mode_test() {
@@ -260,8 +514,11 @@ mod test {
     fn keybinding_without_modifier_test() {
         let mut keybindings = default_vi_normal_keybindings();
         keybindings.add_binding(
-            KeyModifiers::NONE,
-            KeyCode::Char('e'),
+            KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('e'),
+            },
+            vec![],
             ReedlineEvent::ClearScreen,
         );
 
@@ -286,8 +543,11 @@ mod test {
     fn keybinding_with_shift_modifier_test() {
         let mut keybindings = default_vi_normal_keybindings();
         keybindings.add_binding(
-            KeyModifiers::SHIFT,
-            KeyCode::Char('$'),
+            KeyCombination {
+                modifier: KeyModifiers::SHIFT,
+                key_code: KeyCode::Char('$'),
+            },
+            vec![],
             ReedlineEvent::CtrlD,
         );
 
@@ -312,8 +572,11 @@ mod test {
     fn keybinding_with_super_modifier_test() {
         let mut keybindings = default_vi_normal_keybindings();
         keybindings.add_binding(
-            KeyModifiers::SUPER,
-            KeyCode::Char('$'),
+            KeyCombination {
+                modifier: KeyModifiers::SUPER,
+                key_code: KeyCode::Char('$'),
+            },
+            vec![],
             ReedlineEvent::CtrlD,
         );
 
@@ -353,4 +616,312 @@ mod test {
 
         assert_eq!(result, ReedlineEvent::None);
     }
+
+    fn key_press(vi: &mut Vi, modifier: KeyModifiers, key_code: KeyCode) -> ReedlineEvent {
+        vi.parse_event(
+            ReedlineRawEvent::try_from(Event::Key(KeyEvent::new(key_code, modifier))).unwrap(),
+        )
+    }
+
+    #[test]
+    fn visual_text_object_selection_test() {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('v'));
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('i')),
+            ReedlineEvent::None
+        );
+        assert_eq!(vi.mode, ViMode::Visual);
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('w')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![
+                EditCommand::SelectTextObject(TextObject {
+                    kind: TextObjectKind::Word,
+                    around: false
+                })
+            ])])
+        );
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('y')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopySelection])])
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+    }
+
+    #[test]
+    fn visual_line_mode_test() {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::SHIFT, KeyCode::Char('V')),
+            ReedlineEvent::Multiple(vec![
+                ReedlineEvent::Edit(vec![EditCommand::SetSelectionMode(SelectionMode::Line)]),
+                ReedlineEvent::Repaint
+            ])
+        );
+        assert_eq!(vi.mode, ViMode::VisualLine);
+        assert_eq!(vi.edit_mode(), PromptEditMode::Vi(PromptViMode::VisualLine));
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('d')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutSelection])])
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+    }
+
+    #[rstest]
+    #[case(ViMode::Normal, KeyModifiers::CONTROL, ViMode::VisualBlock)]
+    #[case(ViMode::Visual, KeyModifiers::SHIFT, ViMode::VisualLine)]
+    #[case(ViMode::VisualLine, KeyModifiers::CONTROL, ViMode::VisualBlock)]
+    #[case(ViMode::VisualBlock, KeyModifiers::CONTROL, ViMode::Normal)]
+    #[case(ViMode::VisualLine, KeyModifiers::SHIFT, ViMode::Normal)]
+    fn visual_mode_switch_test(
+        #[case] initial_mode: ViMode,
+        #[case] modifier: KeyModifiers,
+        #[case] expected_mode: ViMode,
+    ) {
+        let mut vi = Vi {
+            mode: initial_mode,
+            ..Default::default()
+        };
+
+        let _ = key_press(&mut vi, modifier, KeyCode::Char('v'));
+
+        assert_eq!(vi.mode, expected_mode);
+    }
+
+    #[test]
+    fn search_returns_to_normal_mode_test() {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('/')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::SearchBuffer {
+                backward: false,
+                select: false
+            }])
+        );
+        assert_eq!(vi.mode, ViMode::Search);
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('n')),
+            ReedlineEvent::Edit(vec![EditCommand::InsertChar('n')])
+        );
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Enter),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Enter, ReedlineEvent::Repaint])
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::SHIFT, KeyCode::Char('N')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::SearchBufferPrevious])
+        );
+    }
+
+    #[test]
+    fn macro_recording_test() {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('q'));
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('a'));
+        assert_eq!(
+            vi.edit_mode(),
+            PromptEditMode::Vi(PromptViMode::Recording('a', Box::new(PromptViMode::Normal)))
+        );
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('x'));
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('i'));
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('y'));
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Esc);
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('q')),
+            ReedlineEvent::Repaint
+        );
+        assert_eq!(vi.edit_mode(), PromptEditMode::Vi(PromptViMode::Normal));
+
+        let replay = ReedlineEvent::Multiple(vec![
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutChar])]),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Repaint]),
+            ReedlineEvent::Edit(vec![EditCommand::InsertChar('y')]),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Esc, ReedlineEvent::Repaint]),
+        ]);
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('@'));
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('a')),
+            replay
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('2'));
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('@'));
+        let ReedlineEvent::Multiple(twice) =
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('@'))
+        else {
+            panic!("the macro is replayed");
+        };
+        assert_eq!(twice.len(), 8);
+    }
+
+    #[test]
+    fn ex_command_line_test() {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::SHIFT, KeyCode::Char(':')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::ExCommandLine])
+        );
+        assert_eq!(vi.mode, ViMode::ExCommand);
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('w')),
+            ReedlineEvent::Edit(vec![EditCommand::InsertChar('w')])
+        );
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Enter),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Enter, ReedlineEvent::Repaint])
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+    }
+
+    #[test]
+    fn increment_number_test() {
+        let mut vi = Vi {
+            mode: ViMode::Normal,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::CONTROL, KeyCode::Char('a')),
+            ReedlineEvent::Edit(vec![EditCommand::IncrementNumber(1)])
+        );
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('1'));
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('2'));
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::CONTROL, KeyCode::Char('x')),
+            ReedlineEvent::Edit(vec![EditCommand::DecrementNumber(12)])
+        );
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('.')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![
+                EditCommand::DecrementNumber(12)
+            ])])
+        );
+
+        // A pending register or operator isn't a count
+        for keys in [&['"', 'a'][..], &['d'], &['2', 'd']] {
+            for key in keys {
+                let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char(*key));
+            }
+            assert_eq!(
+                key_press(&mut vi, KeyModifiers::CONTROL, KeyCode::Char('a')),
+                ReedlineEvent::None
+            );
+        }
+        assert!(vi.cache.is_empty());
+    }
+
+    fn jk_escapes() -> Vi {
+        let mut insert_keybindings = default_vi_insert_keybindings();
+        insert_keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('j'),
+            },
+            vec![KeyCombination {
+                modifier: KeyModifiers::NONE,
+                key_code: KeyCode::Char('k'),
+            }],
+            ReedlineEvent::Esc,
+        );
+        Vi::new(insert_keybindings, default_vi_normal_keybindings())
+    }
+
+    #[test]
+    fn key_sequence_leaves_insert_mode_test() {
+        let mut vi = jk_escapes();
+
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('j')),
+            ReedlineEvent::None
+        );
+        assert_eq!(vi.provisional_text(), Some("j".to_string()));
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('k')),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Esc, ReedlineEvent::Repaint])
+        );
+        assert_eq!(vi.mode, ViMode::Normal);
+        assert!(!vi.has_pending_sequence());
+    }
+
+    #[test]
+    fn key_sequence_timeout_test() {
+        let mut vi = jk_escapes();
+
+        let _ = key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('j'));
+        assert!(vi.has_pending_sequence());
+        assert_eq!(
+            vi.cancel_pending_sequence(),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::InsertChar(
+                'j'
+            )])])
+        );
+        assert_eq!(vi.provisional_text(), None);
+        assert_eq!(
+            key_press(&mut vi, KeyModifiers::NONE, KeyCode::Char('k')),
+            ReedlineEvent::Edit(vec![EditCommand::InsertChar('k')])
+        );
+        assert_eq!(vi.mode, ViMode::Insert);
+    }
+
+    #[test]
+    fn timed_out_nested_sequence_replays_the_keys() {
+        let mut insert_keybindings = default_vi_insert_keybindings();
+        insert_keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('x'),
+            },
+            vec![
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('t'),
+                },
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('b'),
+                },
+            ],
+            ReedlineEvent::Esc,
+        );
+        insert_keybindings.add_binding(
+            KeyCombination {
+                modifier: KeyModifiers::CONTROL,
+                key_code: KeyCode::Char('t'),
+            },
+            vec![],
+            ReedlineEvent::Edit(vec![EditCommand::SwapGraphemes]),
+        );
+        let mut vi = Vi::new(insert_keybindings, default_vi_normal_keybindings());
+
+        key_press(&mut vi, KeyModifiers::CONTROL, KeyCode::Char('x'));
+        key_press(&mut vi, KeyModifiers::CONTROL, KeyCode::Char('t'));
+        assert!(vi.has_pending_sequence());
+        assert_eq!(
+            vi.cancel_pending_sequence(),
+            ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::SwapGraphemes])])
+        );
+        assert!(!vi.has_pending_sequence());
+    }
 }
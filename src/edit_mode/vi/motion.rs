@@ -1,6 +1,6 @@
 use std::iter::Peekable;
 
-use crate::{edit_mode::vi::ViMode, EditCommand, ReedlineEvent, Vi};
+use crate::{EditCommand, ReedlineEvent, Vi};
 
 use super::parser::{ParseResult, ReedlineOption};
 
@@ -142,7 +142,7 @@ pub enum Motion {
 
 impl Motion {
     pub fn to_reedline(&self, vi_state: &mut Vi) -> Vec<ReedlineOption> {
-        let select_mode = vi_state.mode == ViMode::Visual;
+        let select_mode = vi_state.mode.is_visual();
         match self {
             Motion::Left => vec![ReedlineOption::Event(ReedlineEvent::UntilFound(vec![
                 ReedlineEvent::MenuLeft,
@@ -242,6 +242,121 @@ impl Motion {
     }
 }
 
+impl Motion {
+    /// Edits selecting the text an operator like `gU` applies to when followed by this motion
+    /// repeated `count` times
+    ///
+    /// Forward motions like `w` and backward ones like `b` stop before the character they
+    /// land on, `e`, `f` and `$` include it. `j` and `k` select whole lines, as does the
+    /// doubled operator with `count` lines.
+    pub fn to_selection(&self, vi_state: &mut Vi, count: usize) -> Option<Vec<EditCommand>> {
+        let repeated = |command: EditCommand| std::iter::repeat(command).take(count);
+        let inclusive = |command| Some(repeated(command).collect());
+        let exclusive_forward = |command| {
+            Some(
+                repeated(command)
+                    .chain([EditCommand::MoveLeft { select: true }])
+                    .collect(),
+            )
+        };
+        // The anchor is placed on the character left of the cursor, so that the selection
+        // ends right before the character the motion started on
+        let backward = |moves: Vec<EditCommand>| {
+            Some(
+                [
+                    EditCommand::MoveLeft { select: false },
+                    EditCommand::EnsureSelection,
+                    EditCommand::MoveRight { select: true },
+                ]
+                .into_iter()
+                .chain(moves)
+                .collect(),
+            )
+        };
+        let exclusive_backward = |command| backward(repeated(command).collect());
+        // Only the last search stops in front of the char, the ones before move onto it
+        let char_search = |char_search: ViCharSearch| {
+            let (to, forward) = match char_search {
+                ViCharSearch::ToRight(c) | ViCharSearch::TillRight(c) => {
+                    (ViCharSearch::ToRight(c), true)
+                }
+                ViCharSearch::ToLeft(c) | ViCharSearch::TillLeft(c) => {
+                    (ViCharSearch::ToLeft(c), false)
+                }
+            };
+            let moves = std::iter::repeat(to.to_move(true))
+                .take(count - 1)
+                .chain([char_search.to_move(true)])
+                .collect();
+            if forward {
+                Some(moves)
+            } else {
+                backward(moves)
+            }
+        };
+
+        match self {
+            Motion::Left => exclusive_backward(EditCommand::MoveLeft { select: true }),
+            Motion::Right => exclusive_forward(EditCommand::MoveRight { select: true }),
+            Motion::Up => Some(
+                [EditCommand::MoveToLineEnd { select: false }]
+                    .into_iter()
+                    .chain(repeated(EditCommand::MoveLineUp { select: true }))
+                    .chain([EditCommand::MoveToLineStart { select: true }])
+                    .collect(),
+            ),
+            Motion::Down => Some(
+                [EditCommand::MoveToLineStart { select: false }]
+                    .into_iter()
+                    .chain(repeated(EditCommand::MoveLineDown { select: true }))
+                    .chain([EditCommand::MoveToLineEnd { select: true }])
+                    .collect(),
+            ),
+            Motion::NextWord => exclusive_forward(EditCommand::MoveWordRightStart { select: true }),
+            Motion::NextBigWord => {
+                exclusive_forward(EditCommand::MoveBigWordRightStart { select: true })
+            }
+            Motion::NextWordEnd => inclusive(EditCommand::MoveWordRightEnd { select: true }),
+            Motion::NextBigWordEnd => inclusive(EditCommand::MoveBigWordRightEnd { select: true }),
+            Motion::PreviousWord => exclusive_backward(EditCommand::MoveWordLeft { select: true }),
+            Motion::PreviousBigWord => {
+                exclusive_backward(EditCommand::MoveBigWordLeft { select: true })
+            }
+            Motion::Line => Some(
+                [EditCommand::MoveToLineStart { select: false }]
+                    .into_iter()
+                    .chain(repeated(EditCommand::MoveLineDown { select: true }).skip(1))
+                    .chain([EditCommand::MoveToLineEnd { select: true }])
+                    .collect(),
+            ),
+            Motion::Start => exclusive_backward(EditCommand::MoveToLineStart { select: true }),
+            Motion::End => inclusive(EditCommand::MoveToLineEnd { select: true }),
+            Motion::RightUntil(c) => {
+                vi_state.last_char_search = Some(ViCharSearch::ToRight(*c));
+                char_search(ViCharSearch::ToRight(*c))
+            }
+            Motion::RightBefore(c) => {
+                vi_state.last_char_search = Some(ViCharSearch::TillRight(*c));
+                char_search(ViCharSearch::TillRight(*c))
+            }
+            Motion::LeftUntil(c) => {
+                vi_state.last_char_search = Some(ViCharSearch::ToLeft(*c));
+                char_search(ViCharSearch::ToLeft(*c))
+            }
+            Motion::LeftBefore(c) => {
+                vi_state.last_char_search = Some(ViCharSearch::TillLeft(*c));
+                char_search(ViCharSearch::TillLeft(*c))
+            }
+            Motion::ReplayCharSearch => vi_state.last_char_search.clone().and_then(char_search),
+            Motion::ReverseCharSearch => vi_state
+                .last_char_search
+                .as_ref()
+                .map(ViCharSearch::reverse)
+                .and_then(char_search),
+        }
+    }
+}
+
 /// Vi left-right motions to or till a character.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ViCharSearch {
@@ -295,4 +410,13 @@ impl ViCharSearch {
             ViCharSearch::TillLeft(c) => EditCommand::CutLeftBefore(*c),
         }
     }
+
+    pub fn to_copy(&self) -> EditCommand {
+        match self {
+            ViCharSearch::ToRight(c) => EditCommand::CopyRightUntil(*c),
+            ViCharSearch::ToLeft(c) => EditCommand::CopyLeftUntil(*c),
+            ViCharSearch::TillRight(c) => EditCommand::CopyRightBefore(*c),
+            ViCharSearch::TillLeft(c) => EditCommand::CopyLeftBefore(*c),
+        }
+    }
 }
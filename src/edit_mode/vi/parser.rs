@@ -1,8 +1,6 @@
-use super::command::{
-    is_valid_change_inside_left, is_valid_change_inside_right, parse_command, Command,
-};
+use super::command::{parse_command, Command};
 use super::motion::{parse_motion, Motion};
-use crate::{edit_mode::vi::ViMode, EditCommand, ReedlineEvent, Vi};
+use crate::{edit_mode::vi::ViMode, EditCommand, ReedlineEvent, Registers, Vi};
 use std::iter::Peekable;
 
 #[derive(Debug, Clone)]
@@ -41,6 +39,7 @@ impl<T> ParseResult<T> {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParsedViSequence {
+    register: Option<char>,
     multiplier: Option<usize>,
     command: Option<Command>,
     count: Option<usize>,
@@ -48,18 +47,33 @@ pub struct ParsedViSequence {
 }
 
 impl ParsedViSequence {
+    /// Sequence that ended before reaching a command or motion
+    fn unfinished(motion: ParseResult<Motion>) -> Self {
+        Self {
+            register: None,
+            multiplier: None,
+            command: None,
+            count: None,
+            motion,
+        }
+    }
+
     pub fn is_valid(&self) -> bool {
         !self.motion.is_invalid()
     }
 
     pub fn is_complete(&self, mode: ViMode) -> bool {
-        assert!(mode == ViMode::Normal || mode == ViMode::Visual);
+        assert!(mode == ViMode::Normal || mode.is_visual());
         match (&self.command, &self.motion) {
             (None, ParseResult::Valid(_)) => true,
             (Some(Command::Incomplete), _) => false,
-            (Some(cmd), ParseResult::Incomplete)
-                if !cmd.requires_motion() || mode == ViMode::Visual =>
+            // In visual mode `i` and `a` start a text object instead of entering insert mode
+            (Some(Command::EnterViInsert | Command::EnterViAppend), ParseResult::Incomplete)
+                if mode.is_visual() =>
             {
+                false
+            }
+            (Some(cmd), ParseResult::Incomplete) if !cmd.requires_motion() || mode.is_visual() => {
                 true
             }
             (Some(_), ParseResult::Valid(_)) => true,
@@ -80,10 +94,29 @@ impl ParsedViSequence {
     }
 
     fn apply_multiplier(&self, raw_events: Option<Vec<ReedlineOption>>) -> ReedlineEvent {
+        self.repeat(raw_events, self.total_multiplier())
+    }
+
+    fn repeat(&self, raw_events: Option<Vec<ReedlineOption>>, times: usize) -> ReedlineEvent {
         if let Some(raw_events) = raw_events {
-            let events = std::iter::repeat(raw_events)
-                .take(self.total_multiplier())
-                .flatten()
+            let register = self.register.filter(|_| self.command.is_some());
+            let pastes = matches!(
+                self.command,
+                Some(Command::PasteAfter | Command::PasteBefore)
+            );
+            let events = (0..times)
+                .flat_map(|repetition| {
+                    // The register is reset after each use, so the following repetitions
+                    // append to a named register instead of overwriting the first one
+                    let register = register.map(|name| match name {
+                        'a'..='z' if repetition > 0 && !pastes => name.to_ascii_uppercase(),
+                        _ => name,
+                    });
+                    register
+                        .map(|name| ReedlineOption::Edit(EditCommand::SelectRegister(name)))
+                        .into_iter()
+                        .chain(raw_events.iter().cloned())
+                })
                 .filter_map(ReedlineOption::into_reedline_event)
                 .collect::<Vec<ReedlineEvent>>();
 
@@ -107,22 +140,42 @@ impl ParsedViSequence {
             | (Some(Command::PrependToStart), ParseResult::Incomplete)
             | (Some(Command::RewriteCurrentLine), ParseResult::Incomplete)
             | (Some(Command::SubstituteCharWithInsert), ParseResult::Incomplete)
-            | (Some(Command::HistorySearch), ParseResult::Incomplete)
-            | (Some(Command::Change), ParseResult::Valid(_)) => Some(ViMode::Insert),
-            (Some(Command::ChangeInside(char)), ParseResult::Incomplete)
-                if is_valid_change_inside_left(char) || is_valid_change_inside_right(char) =>
-            {
-                Some(ViMode::Insert)
-            }
-            (Some(Command::Delete), ParseResult::Incomplete) => Some(ViMode::Normal),
+            | (Some(Command::Change), _) => Some(ViMode::Insert),
+            (Some(Command::SearchForward), ParseResult::Incomplete)
+            | (Some(Command::SearchBackward), ParseResult::Incomplete) => Some(ViMode::Search),
+            (Some(Command::ExCommandLine), ParseResult::Incomplete) => Some(ViMode::ExCommand),
+            (Some(Command::ChangeTextObject(_)), ParseResult::Incomplete) => Some(ViMode::Insert),
+            (Some(Command::Delete), ParseResult::Incomplete)
+            | (Some(Command::Yank), ParseResult::Incomplete)
+            | (Some(Command::Lowercase), ParseResult::Incomplete)
+            | (Some(Command::Uppercase), ParseResult::Incomplete)
+            | (Some(Command::SwitchcaseOperator), ParseResult::Incomplete)
+            | (Some(Command::Indent), ParseResult::Incomplete)
+            | (Some(Command::Dedent), ParseResult::Incomplete)
+            | (Some(Command::JoinLines), ParseResult::Incomplete) => Some(ViMode::Normal),
             _ => None,
         }
     }
 
     pub fn to_reedline_event(&self, vi_state: &mut Vi) -> ReedlineEvent {
         match (&self.multiplier, &self.command, &self.count, &self.motion) {
+            // Neither is a change `.` could repeat, a replay uses the count itself as
+            // every repetition has to start in the mode left by the previous one
+            (_, Some(command @ Command::RecordMacro(_)), None, ParseResult::Incomplete) => {
+                self.apply_multiplier(Some(command.to_reedline(vi_state)))
+            }
+            (_, Some(Command::ReplayMacro(name)), None, ParseResult::Incomplete) => {
+                vi_state.replay_macro(*name, self.total_multiplier())
+            }
             (_, Some(command), None, ParseResult::Incomplete) => {
-                let events = self.apply_multiplier(Some(command.to_reedline(vi_state)));
+                let events = match command {
+                    // Like in Vim the count is the number of lines, not of joins
+                    Command::JoinLines => self.repeat(
+                        Some(command.to_reedline(vi_state)),
+                        self.total_multiplier().max(2) - 1,
+                    ),
+                    _ => self.apply_multiplier(Some(command.to_reedline(vi_state))),
+                };
                 match &events {
                     ReedlineEvent::None => {}
                     event => vi_state.previous = Some(event.clone()),
@@ -131,8 +184,12 @@ impl ParsedViSequence {
             }
             // This case handles all combinations of commands and motions that could exist
             (_, Some(command), _, ParseResult::Valid(motion)) => {
-                let events =
-                    self.apply_multiplier(command.to_reedline_with_motion(motion, vi_state));
+                let events = if command.counts_motion() {
+                    let count = self.total_multiplier();
+                    self.repeat(command.to_reedline_with_motion(motion, count, vi_state), 1)
+                } else {
+                    self.apply_multiplier(command.to_reedline_with_motion(motion, 1, vi_state))
+                };
                 match &events {
                     ReedlineEvent::None => {}
                     event => vi_state.previous = Some(event.clone()),
@@ -171,16 +228,40 @@ where
     }
 }
 
-pub fn parse<'iter, I>(input: &mut Peekable<I>) -> ParsedViSequence
+/// Parse the `"x` prefix choosing the register used by the command
+fn parse_register<'iter, I>(input: &mut Peekable<I>) -> ParseResult<Option<char>>
 where
     I: Iterator<Item = &'iter char>,
 {
+    match input.peek() {
+        Some('"') => {
+            let _ = input.next();
+            match input.next() {
+                Some(&name) if Registers::is_valid(name) => ParseResult::Valid(Some(name)),
+                Some(_) => ParseResult::Invalid,
+                None => ParseResult::Incomplete,
+            }
+        }
+        _ => ParseResult::Valid(None),
+    }
+}
+
+pub fn parse<'iter, I>(input: &mut Peekable<I>) -> ParsedViSequence
+where
+    I: Iterator<Item = &'iter char> + Clone,
+{
+    let register = match parse_register(input) {
+        ParseResult::Valid(register) => register,
+        ParseResult::Incomplete => return ParsedViSequence::unfinished(ParseResult::Incomplete),
+        ParseResult::Invalid => return ParsedViSequence::unfinished(ParseResult::Invalid),
+    };
     let multiplier = parse_number(input);
     let command = parse_command(input);
     let count = parse_number(input);
     let motion = parse_motion(input, command.as_ref().and_then(Command::whole_line_char));
 
     ParsedViSequence {
+        register,
         multiplier,
         command,
         count,
@@ -191,6 +272,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::enums::{TextObject, TextObjectKind};
+    use crate::{core_editor::Editor, UndoBehavior};
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
@@ -206,6 +289,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: None,
                 command: Some(Command::Delete),
                 count: None,
@@ -225,6 +309,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: None,
                 command: Some(Command::Delete),
                 count: None,
@@ -244,6 +329,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: Some(Command::Delete),
                 count: None,
@@ -265,6 +351,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: Some(Command::Delete),
                 count: None,
@@ -284,6 +371,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: Some(Command::Delete),
                 count: Some(2),
@@ -303,6 +391,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: Some(Command::Delete),
                 count: Some(20),
@@ -322,6 +411,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: Some(Command::Delete),
                 count: None,
@@ -341,6 +431,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: None,
                 command: Some(Command::Delete),
                 count: None,
@@ -360,6 +451,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: Some(Command::Delete),
                 count: None,
@@ -377,6 +469,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: None,
                 command: Some(Command::Incomplete),
                 count: None,
@@ -389,6 +482,54 @@ mod tests {
         assert_eq!(output.is_complete(ViMode::Visual), false);
     }
 
+    #[rstest]
+    #[case(&['d', 'i'])]
+    #[case(&['y', 'a'])]
+    fn test_partial_text_object(#[case] input: &[char]) {
+        let output = vi_parse(input);
+
+        assert_eq!(output.command, Some(Command::Incomplete));
+        assert_eq!(output.is_valid(), true);
+        assert_eq!(output.is_complete(ViMode::Normal), false);
+    }
+
+    #[rstest]
+    #[case(&['q'], Some(Command::Incomplete), false)]
+    #[case(&['q', 'a'], Some(Command::RecordMacro('a')), true)]
+    #[case(&['q', 'A'], Some(Command::RecordMacro('A')), true)]
+    #[case(&['@'], Some(Command::Incomplete), false)]
+    #[case(&['@', 'a'], Some(Command::ReplayMacro('a')), true)]
+    #[case(&['@', '@'], Some(Command::ReplayMacro('@')), true)]
+    fn test_macro(
+        #[case] input: &[char],
+        #[case] command: Option<Command>,
+        #[case] complete: bool,
+    ) {
+        let output = vi_parse(input);
+
+        assert_eq!(output.command, command);
+        assert_eq!(output.is_complete(ViMode::Normal), complete);
+    }
+
+    #[test]
+    fn test_visual_insert_waits_for_text_object() {
+        let output = vi_parse(&['i']);
+
+        assert_eq!(output.is_complete(ViMode::Normal), true);
+        assert_eq!(output.is_complete(ViMode::Visual), false);
+    }
+
+    #[rstest]
+    #[case(&['"'], true)]
+    #[case(&['"', 'a'], true)]
+    #[case(&['"', '!'], false)]
+    fn test_partial_register(#[case] input: &[char], #[case] valid: bool) {
+        let output = vi_parse(input);
+
+        assert_eq!(output.is_valid(), valid);
+        assert_eq!(output.is_complete(ViMode::Normal), false);
+    }
+
     #[test]
     fn test_partial_motion() {
         let input = ['f'];
@@ -397,6 +538,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: None,
                 command: None,
                 count: None,
@@ -416,6 +558,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: None,
                 command: Some(Command::ReplaceChar('k')),
                 count: None,
@@ -436,6 +579,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: None,
                 count: None,
@@ -455,6 +599,7 @@ mod tests {
         assert_eq!(
             output,
             ParsedViSequence {
+                register: None,
                 multiplier: Some(2),
                 command: None,
                 count: None,
@@ -545,6 +690,29 @@ mod tests {
     #[case(&['c', 't', 'a'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutRightBefore('a')]), ReedlineEvent::Repaint]))]
     #[case(&['c', 'F', 'a'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutLeftUntil('a')]), ReedlineEvent::Repaint]))]
     #[case(&['c', 'T', 'a'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutLeftBefore('a')]), ReedlineEvent::Repaint]))]
+    #[case(&['y', 'y'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopyCurrentLine])]))]
+    #[case(&['y', 'w'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopyWordRightToNext])]))]
+    #[case(&['y', 'b'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopyWordLeft])]))]
+    #[case(&['y', '$'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopyToLineEnd])]))]
+    #[case(&['y', 't', 'a'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopyRightBefore('a')])]))]
+    #[case(&['d', 'i', 'w'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutTextObject(TextObject { kind: TextObjectKind::Word, around: false })])]))]
+    #[case(&['d', 'a', 'W'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutTextObject(TextObject { kind: TextObjectKind::BigWord, around: true })])]))]
+    #[case(&['c', 'i', '"'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutTextObject(TextObject { kind: TextObjectKind::Pair('"'), around: false })])]))]
+    #[case(&['c', 'a', ')'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CutTextObject(TextObject { kind: TextObjectKind::Pair(')'), around: true })])]))]
+    #[case(&['y', 'i', '{'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::CopyTextObject(TextObject { kind: TextObjectKind::Pair('{'), around: false })])]))]
+    #[case(&['d', 'i', 'x'], ReedlineEvent::None)]
+    #[case(&['"', 'a', 'y', 'y'], ReedlineEvent::Multiple(vec![
+        ReedlineEvent::Edit(vec![EditCommand::SelectRegister('a')]),
+        ReedlineEvent::Edit(vec![EditCommand::CopyCurrentLine])]))]
+    #[case(&['"', '+', 'P'], ReedlineEvent::Multiple(vec![
+        ReedlineEvent::Edit(vec![EditCommand::SelectRegister('+')]),
+        ReedlineEvent::Edit(vec![EditCommand::PasteCutBufferBefore])]))]
+    #[case(&['"', 'b', '2', 'p'], ReedlineEvent::Multiple(vec![
+        ReedlineEvent::Edit(vec![EditCommand::SelectRegister('b')]),
+        ReedlineEvent::Edit(vec![EditCommand::PasteCutBufferAfter]),
+        ReedlineEvent::Edit(vec![EditCommand::SelectRegister('b')]),
+        ReedlineEvent::Edit(vec![EditCommand::PasteCutBufferAfter])]))]
+    #[case(&['"', 'a', 'w'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::MoveWordRightStart{select: false}])]))]
     fn test_reedline_move(#[case] input: &[char], #[case] expected: ReedlineEvent) {
         let mut vi = Vi::default();
         let res = vi_parse(input);
@@ -635,6 +803,10 @@ mod tests {
         ]))]
     #[case(&['d'], ReedlineEvent::Multiple(vec![
         ReedlineEvent::Edit(vec![EditCommand::CutSelection])]))]
+    #[case(&['y'], ReedlineEvent::Multiple(vec![
+        ReedlineEvent::Edit(vec![EditCommand::CopySelection])]))]
+    #[case(&['i', 'w'], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::SelectTextObject(TextObject { kind: TextObjectKind::Word, around: false })])]))]
+    #[case(&['a', '['], ReedlineEvent::Multiple(vec![ReedlineEvent::Edit(vec![EditCommand::SelectTextObject(TextObject { kind: TextObjectKind::Pair('['), around: true })])]))]
     fn test_reedline_move_in_visual_mode(#[case] input: &[char], #[case] expected: ReedlineEvent) {
         let mut vi = Vi {
             mode: ViMode::Visual,
@@ -645,4 +817,70 @@ mod tests {
 
         assert_eq!(output, expected);
     }
+
+    fn run_edits(editor: &mut Editor, event: ReedlineEvent) {
+        match event {
+            ReedlineEvent::Multiple(events) => events
+                .into_iter()
+                .for_each(|event| run_edits(editor, event)),
+            ReedlineEvent::Edit(commands) => commands
+                .iter()
+                .for_each(|command| editor.run_edit_command(command)),
+            _ => {}
+        }
+    }
+
+    #[rstest]
+    #[case(&['g', 'j'])]
+    #[case(&['g', 'e'])]
+    #[case(&['g', '0'])]
+    #[case(&['d', 'g', 'j'])]
+    fn test_unknown_g_command_is_invalid(#[case] input: &[char]) {
+        assert_eq!(vi_parse(input).is_valid(), false);
+    }
+
+    #[rstest]
+    #[case(&['g', 'U', 'i', 'w'], "foo bar baz", 5, "foo BAR baz", 4)]
+    #[case(&['g', 'U', 'w'], "foo bar baz", 1, "fOO bar baz", 1)]
+    #[case(&['g', 'U', 'e'], "foo bar baz", 4, "foo BAR baz", 4)]
+    #[case(&['g', 'U', 'b'], "foo bar baz", 6, "foo BAr baz", 4)]
+    #[case(&['g', 'U', '$'], "foo bar\nbaz", 4, "foo BAR\nbaz", 4)]
+    #[case(&['g', 'u', 'u'], "FOO\nBAR", 5, "FOO\nbar", 4)]
+    #[case(&['g', '~', 't', 'z'], "foo Bar baz", 0, "FOO bAR BAz", 0)]
+    #[case(&['g', 'u', 'j'], "A\nB\nC", 0, "a\nb\nC", 0)]
+    #[case(&['>', '>'], "foo\nbar", 5, "foo\n    bar", 8)]
+    #[case(&['>', 'j'], "foo\nbar", 1, "    foo\n    bar", 4)]
+    #[case(&['<', 'k'], "    foo\n\tbar", 9, "foo\nbar", 0)]
+    #[case(&['J'], "foo\n  bar\nbaz", 1, "foo bar\nbaz", 3)]
+    #[case(&['2', 'J'], "a\nb\nc", 0, "a b\nc", 1)]
+    #[case(&['3', 'J'], "a\nb\nc\nd", 0, "a b c\nd", 3)]
+    #[case(&['3', '>', '>'], "a\nb\nc\nd", 0, "    a\n    b\n    c\nd", 4)]
+    #[case(&['2', '<', 'j'], "\ta\n\tb\n\tc\n\td", 0, "a\nb\nc\n\td", 0)]
+    #[case(&['2', 'g', '~', 'w'], "foo bar baz", 0, "FOO BAR baz", 0)]
+    #[case(&['2', 'g', 'U', 'w'], "foo bar baz", 0, "FOO BAR baz", 0)]
+    #[case(&['g', 'U', '2', 'w'], "foo bar baz", 0, "FOO BAR baz", 0)]
+    #[case(&['3', 'g', 'u', 'u'], "A\nB\nC\nD", 0, "a\nb\nc\nD", 0)]
+    #[case(&['g', 'U', '2', 't', 'z'], "foo z bar z", 0, "FOO Z BAR z", 0)]
+    fn test_operator_edits(
+        #[case] input: &[char],
+        #[case] buffer: &str,
+        #[case] cursor: usize,
+        #[case] expected_buffer: &str,
+        #[case] expected_cursor: usize,
+    ) {
+        let mut vi = Vi::default();
+        let mut editor = Editor::default();
+        editor.set_buffer(buffer.to_string(), UndoBehavior::CreateUndoPoint);
+        editor.run_edit_command(&EditCommand::MoveToPosition {
+            position: cursor,
+            select: false,
+        });
+
+        let output = vi_parse(input);
+        assert_eq!(output.is_complete(ViMode::Normal), true);
+        run_edits(&mut editor, output.to_reedline_event(&mut vi));
+
+        assert_eq!(editor.get_buffer(), expected_buffer);
+        assert_eq!(editor.insertion_point(), expected_cursor);
+    }
 }
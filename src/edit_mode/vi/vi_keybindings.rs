@@ -21,6 +21,14 @@ pub fn default_vi_normal_keybindings() -> Keybindings {
     add_common_control_bindings(&mut kb);
     add_common_navigation_bindings(&mut kb);
     add_common_selection_bindings(&mut kb);
+    // Ctrl-A increments the number under the cursor like in Vi
+    kb.remove_binding(
+        KeyCombination {
+            modifier: KM::CONTROL,
+            key_code: KC::Char('a'),
+        },
+        vec![],
+    );
     // Replicate vi's default behavior for Backspace and delete
     kb.add_binding(
         KeyCombination {
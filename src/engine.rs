@@ -3,12 +3,9 @@ use std::path::PathBuf;
 use itertools::Itertools;
 use nu_ansi_term::{Color, Style};
 
-use crate::{enums::ReedlineRawEvent, CursorConfig};
 #[cfg(feature = "bashisms")]
-use crate::{
-    history::SearchFilter,
-    menu_functions::{parse_selection_char, ParseAction},
-};
+use crate::menu_functions::{parse_selection_char, ParseAction};
+use crate::{enums::ReedlineRawEvent, CursorConfig};
 #[cfg(feature = "external_printer")]
 use {
     crate::external_printer::ExternalPrinter,
@@ -18,31 +15,34 @@ use {
 use {
     crate::{
         completion::{Completer, DefaultCompleter},
-        core_editor::Editor,
+        core_editor::{BufferSearch, Editor},
         edit_mode::{EditMode, Emacs},
         enums::{EventStatus, ReedlineEvent},
+        ex_command::{parse_ex_line, ExCommand, ExLine},
         highlighter::SimpleMatchHighlighter,
         hinter::Hinter,
         history::{
             FileBackedHistory, History, HistoryCursor, HistoryItem, HistoryItemId,
-            HistoryNavigationQuery, HistorySessionId, SearchDirection, SearchQuery,
+            HistoryNavigationQuery, HistorySessionId, SearchDirection, SearchFilter, SearchQuery,
         },
-        painting::{Painter, PainterSuspendedState, PromptLines},
+        input_source::{CrosstermInput, InputSource},
+        painting::{which_key_string, Painter, PainterSuspendedState, PromptLines, PromptSearch},
         prompt::{PromptEditMode, PromptHistorySearchStatus},
         result::{ReedlineError, ReedlineErrorVariants},
         terminal_extensions::{bracketed_paste::BracketedPasteGuard, kitty::KittyProtocolGuard},
         utils::text_manipulation,
-        EditCommand, ExampleHighlighter, Highlighter, LineBuffer, Menu, MenuEvent, Prompt,
-        PromptHistorySearch, ReedlineMenu, Signal, UndoBehavior, ValidationResult, Validator,
+        CustomEvent, CustomEventResult, EditCommand, ExampleHighlighter, Highlighter, LineBuffer,
+        Menu, MenuEvent, Prompt, PromptBufferSearch, PromptHistorySearch, ReedlineMenu, Signal,
+        UndoBehavior, ValidationResult, Validator,
     },
     crossterm::{
-        cursor::{SetCursorStyle, Show},
-        event,
+        cursor::{self, SetCursorStyle, Show},
         event::{Event, KeyCode, KeyEvent, KeyModifiers},
         terminal, QueueableCommand,
     },
     std::{
-        fs::File, io, io::Result, io::Write, process::Command, time::Duration, time::SystemTime,
+        collections::HashMap, fs::File, io, io::Result, io::Write, process::Command,
+        time::Duration, time::Instant, time::SystemTime,
     },
 };
 
@@ -78,6 +78,12 @@ enum InputMode {
     /// Either bash style up/down history or fish style prefix search,
     /// Edits directly switch to [`InputMode::Regular`]
     HistoryTraversal,
+    /// Search through the current buffer with a different prompt indicator,
+    /// editing affects the search string and the cursor jumps to the matches
+    BufferSearch,
+    /// Typing a Vi ex command after `:`, the command line replaces the buffer
+    /// until it is executed or cancelled
+    ExCommand,
 }
 
 /// Line editor engine
@@ -111,6 +117,8 @@ pub struct Reedline {
     history_exclusion_prefix: Option<String>,
     history_excluded_item: Option<HistoryItem>,
     history_cursor_on_excluded: bool,
+    // Argument of a history entry inserted last, replaced when inserting the next one
+    yanked_arg: Option<YankedArg>,
     input_mode: InputMode,
 
     // State of the painter after a `ReedlineEvent::ExecuteHostCommand` was requested, used after
@@ -125,8 +133,22 @@ pub struct Reedline {
 
     transient_prompt: Option<Box<dyn Prompt>>,
 
+    // Where the terminal events are read from
+    input: Box<dyn InputSource>,
+
     // Edit Mode: Vi, Emacs
     edit_mode: Box<dyn EditMode>,
+    // How long a pending key sequence waits for its next key, forever if none
+    key_sequence_timeout: Option<Duration>,
+    // Provisional text of the pending key sequence as painted last
+    painted_provisional_text: Option<String>,
+    // How long a pending key sequence waits before listing the keys that can follow
+    which_key_delay: Option<Duration>,
+    which_key_visible: bool,
+    // When the pending key sequence gives up on waiting for its next key
+    sequence_deadline: Option<Instant>,
+    // When the keys that can continue the pending sequence are shown
+    which_key_deadline: Option<Instant>,
 
     // Provides the tab completions
     completer: Box<dyn Completer>,
@@ -139,6 +161,19 @@ pub struct Reedline {
     // Style used for visual selection
     visual_selection_style: Style,
 
+    // Search inside the buffer, kept after the search to jump between the matches
+    buffer_search: Option<BufferSearch>,
+    // Style used for the matches of the buffer search
+    buffer_search_style: Style,
+
+    // Line typed after `:` and the commands it can run besides the built-in ones
+    ex_command_line: String,
+    ex_commands: HashMap<String, Box<dyn ExCommand>>,
+
+    // Callbacks run by `ReedlineEvent::Custom` and the message the last one left
+    custom_events: HashMap<String, Box<dyn CustomEvent>>,
+    custom_event_message: Option<String>,
+
     // Showcase hints based on various strategies (history, language-completion, spellcheck, etc)
     hinter: Option<Box<dyn Hinter>>,
     hide_hints: bool,
@@ -174,6 +209,16 @@ struct BufferEditor {
     temp_file: PathBuf,
 }
 
+/// Argument of a history entry inserted by [`ReedlineEvent::YankLastArg`] or
+/// [`ReedlineEvent::YankNthArg`]
+struct YankedArg {
+    /// History entry the argument was taken from
+    id: HistoryItemId,
+    /// Position of the inserted text in the buffer
+    start: usize,
+    text: String,
+}
+
 impl Drop for Reedline {
     fn drop(&mut self) {
         if self.cursor_shapes.is_some() {
@@ -200,6 +245,7 @@ impl Reedline {
         let painter = Painter::new(std::io::BufWriter::new(std::io::stderr()));
         let buffer_highlighter = Box::<ExampleHighlighter>::default();
         let visual_selection_style = Style::new().on(Color::LightGray);
+        let buffer_search_style = Style::new().fg(Color::Black).on(Color::Yellow);
         let completer = Box::<DefaultCompleter>::default();
         let hinter = None;
         let validator = None;
@@ -218,16 +264,30 @@ impl Reedline {
             history_exclusion_prefix: None,
             history_excluded_item: None,
             history_cursor_on_excluded: false,
+            yanked_arg: None,
             input_mode: InputMode::Regular,
             suspended_state: None,
             painter,
             transient_prompt: None,
+            input: Box::new(CrosstermInput),
             edit_mode,
+            key_sequence_timeout: None,
+            painted_provisional_text: None,
+            which_key_delay: None,
+            which_key_visible: false,
+            sequence_deadline: None,
+            which_key_deadline: None,
             completer,
             quick_completions: false,
             partial_completions: false,
             highlighter: buffer_highlighter,
             visual_selection_style,
+            buffer_search: None,
+            buffer_search_style,
+            ex_command_line: String::new(),
+            ex_commands: HashMap::new(),
+            custom_events: HashMap::new(),
+            custom_event_message: None,
             hinter,
             hide_hints: false,
             validator,
@@ -405,6 +465,13 @@ impl Reedline {
         self
     }
 
+    /// A builder that configures the style used for the matches of the search inside the buffer
+    #[must_use]
+    pub fn with_buffer_search_style(mut self, style: Style) -> Self {
+        self.buffer_search_style = style;
+        self
+    }
+
     /// A builder which configures the history for your instance of the Reedline engine
     /// # Example
     /// ```rust,no_run
@@ -462,6 +529,46 @@ impl Reedline {
         self
     }
 
+    /// A builder that registers a command for the Vi ex command line opened with `:`
+    ///
+    /// The command runs when `:name` is entered, optionally followed by arguments.
+    /// A command registered under the name of a built-in command replaces it, `:w` submits
+    /// the buffer like Enter and `:q` acts like Ctrl-D, which only ends an empty input.
+    ///
+    /// ```rust
+    /// use reedline::{Editor, Reedline, Signal};
+    ///
+    /// let line_editor = Reedline::create().with_ex_command(
+    ///     "cd",
+    ///     Box::new(|args: &str, _editor: &mut Editor| Some(Signal::Success(format!("cd {args}")))),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_ex_command(mut self, name: &str, command: Box<dyn ExCommand>) -> Self {
+        self.ex_commands.insert(name.to_string(), command);
+        self
+    }
+
+    /// A builder that replaces where [`Reedline::read_line()`] reads the terminal events
+    /// from
+    ///
+    /// See [`InputSource`] for an example feeding keystrokes from a test.
+    #[must_use]
+    pub fn with_input_source(mut self, input: Box<dyn InputSource>) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// A builder that registers a callback run by [`ReedlineEvent::Custom`] with the `name`
+    ///
+    /// The callback edits the buffer without returning from `read_line`, see
+    /// [`CustomEvent`] for an example.
+    #[must_use]
+    pub fn with_custom_event(mut self, name: &str, event: Box<dyn CustomEvent>) -> Self {
+        self.custom_events.insert(name.to_string(), event);
+        self
+    }
+
     /// A builder that configures the alternate text editor used to edit the line buffer
     ///
     /// You are responsible for providing a file path that is unique to this reedline session
@@ -516,6 +623,29 @@ impl Reedline {
         self
     }
 
+    /// A builder which sets how long a pending key sequence waits for its next key
+    ///
+    /// Once the timeout expires, the keys typed so far are handled as if they didn't
+    /// start a sequence, like with Vim's `timeoutlen` which is one second by default. With
+    /// `None`, the default, the sequence waits forever.
+    #[must_use]
+    pub fn with_key_sequence_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.key_sequence_timeout = timeout;
+        self
+    }
+
+    /// A builder which lists the keys that can continue a pending key sequence
+    ///
+    /// Once the sequence waited `delay` for its next key, an overlay below the buffer
+    /// shows the keys with the event each one triggers. While it is open, the sequence
+    /// doesn't time out. It closes when the sequence resolves. With `None`, the default,
+    /// no overlay is shown.
+    #[must_use]
+    pub fn with_which_key(mut self, delay: Option<Duration>) -> Self {
+        self.which_key_delay = delay;
+        self
+    }
+
     /// A builder that appends a menu to the engine
     #[must_use]
     pub fn with_menu(mut self, menu: ReedlineMenu) -> Self {
@@ -643,16 +773,107 @@ impl Reedline {
     /// Returns a [`std::io::Result`] in which the `Err` type is [`std::io::Result`]
     /// and the `Ok` variant wraps a [`Signal`] which handles user inputs.
     pub fn read_line(&mut self, prompt: &dyn Prompt) -> Result<Signal> {
-        terminal::enable_raw_mode()?;
-        self.bracketed_paste.enter();
-        self.kitty_protocol.enter();
+        self.begin_line(prompt)?;
 
-        let result = self.read_line_helper(prompt);
+        // Leaves the raw mode on its own, with the signal as well as with an error
+        self.read_line_helper(prompt)
+    }
 
+    /// Start reading a line without blocking, for hosts running their own event loop
+    ///
+    /// Enables the raw mode of the terminal and paints the `prompt`. Afterwards, feed the
+    /// terminal events to [`Reedline::handle_raw_event()`] and call [`Reedline::tick()`]
+    /// whenever [`Reedline::tick_timeout()`] elapses without an event, until one of them
+    /// returns a [`Signal`]. Async hosts can take the events from crossterm's
+    /// `EventStream`. [`Reedline::read_line()`] runs the same steps in a blocking loop.
+    ///
+    /// Pass the same `prompt` to all the steps of a line.
+    pub fn begin_line(&mut self, prompt: &dyn Prompt) -> Result<()> {
+        if self.input.is_terminal() {
+            terminal::enable_raw_mode()?;
+            self.bracketed_paste.enter();
+            self.kitty_protocol.enter();
+        }
+
+        let result = self.start_line(prompt);
+        if result.is_err() {
+            // The error that stopped the line matters more than one restoring the terminal
+            let _ = self.end_line();
+        }
+        result
+    }
+
+    /// Leave the raw mode entered by [`Reedline::begin_line()`]
+    ///
+    /// This happens on its own when a step returns a [`Signal`] or an error. Call it to
+    /// abandon a line before that.
+    pub fn end_line(&mut self) -> Result<()> {
         self.bracketed_paste.exit();
         self.kitty_protocol.exit();
-        terminal::disable_raw_mode()?;
-        result
+        if self.input.is_terminal() {
+            terminal::disable_raw_mode()?;
+        }
+        Ok(())
+    }
+
+    /// Handle a terminal event for the line started with [`Reedline::begin_line()`]
+    ///
+    /// Returns the [`Signal`] once the line is done.
+    pub fn handle_raw_event(
+        &mut self,
+        prompt: &dyn Prompt,
+        event: Event,
+    ) -> Result<Option<Signal>> {
+        self.handle_raw_events(prompt, vec![event])
+    }
+
+    /// Handle terminal events that arrived together, e.g. from pasted text
+    ///
+    /// Consecutive edits are applied at once and only the last resize is kept, which
+    /// saves repainting after every single event.
+    pub fn handle_raw_events(
+        &mut self,
+        prompt: &dyn Prompt,
+        events: Vec<Event>,
+    ) -> Result<Option<Signal>> {
+        match self.process_events(prompt, events) {
+            Ok(None) => Ok(None),
+            Ok(Some(signal)) => {
+                self.end_line()?;
+                Ok(Some(signal))
+            }
+            Err(error) => {
+                let _ = self.end_line();
+                Err(error)
+            }
+        }
+    }
+
+    /// Update the line started with [`Reedline::begin_line()`] while no event arrives
+    ///
+    /// Replays a pending key sequence that timed out, lists the keys that can continue
+    /// it and prints the messages of the external printer.
+    pub fn tick(&mut self, prompt: &dyn Prompt) -> Result<Option<Signal>> {
+        self.handle_raw_events(prompt, vec![])
+    }
+
+    /// How long to wait for the next event before calling [`Reedline::tick()`]
+    ///
+    /// Returns `None` if nothing changes until the next event.
+    pub fn tick_timeout(&self) -> Option<Duration> {
+        // If the `external_printer` feature is enabled, we need to
+        // periodically yield so that external printers get a chance to
+        // print. A pending key sequence only waits until its deadlines.
+        #[cfg(feature = "external_printer")]
+        let wait = Some(EXTERNAL_PRINTER_WAIT);
+        #[cfg(not(feature = "external_printer"))]
+        let wait: Option<Duration> = None;
+        let deadline_wait = self
+            .sequence_deadline
+            .into_iter()
+            .chain(self.which_key_deadline)
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        wait.into_iter().chain(deadline_wait).min()
     }
 
     /// Returns the current insertion point of the input buffer.
@@ -685,139 +906,248 @@ impl Reedline {
         Ok(())
     }
 
-    /// Helper implementing the logic for [`Reedline::read_line()`] to be wrapped
-    /// in a `raw_mode` context.
+    /// Blocking event loop of [`Reedline::read_line()`], to be wrapped in a `raw_mode`
+    /// context.
     fn read_line_helper(&mut self, prompt: &dyn Prompt) -> Result<Signal> {
-        self.painter
-            .initialize_prompt_position(self.suspended_state.as_ref())?;
-        if self.suspended_state.is_some() {
-            // Last editor was suspended to run a ExecuteHostCommand event,
-            // we are resuming operation now.
-            self.suspended_state = None;
-        }
-        self.hide_hints = false;
-
-        self.repaint(prompt)?;
-
         loop {
-            #[cfg(feature = "external_printer")]
-            if let Some(ref external_printer) = self.external_printer {
-                // get messages from printer as crlf separated "lines"
-                let messages = Self::external_messages(external_printer)?;
-                if !messages.is_empty() {
-                    // print the message(s)
-                    self.painter.print_external_message(
-                        messages,
-                        self.editor.line_buffer(),
-                        prompt,
-                    )?;
-                    self.repaint(prompt)?;
+            let events = match self.read_events() {
+                Ok(events) => events,
+                Err(error) => {
+                    let _ = self.end_line();
+                    return Err(error);
                 }
+            };
+
+            // Without events, this ticks the engine
+            if let Some(signal) = self.handle_raw_events(prompt, events)? {
+                return Ok(signal);
             }
+        }
+    }
 
-            // Helper function that returns true if the input is complete and
-            // can be sent to the hosting application.
-            fn completed(events: &[Event]) -> bool {
-                if let Some(event) = events.last() {
-                    matches!(
-                        event,
-                        Event::Key(KeyEvent {
-                            code: KeyCode::Enter,
-                            modifiers: KeyModifiers::NONE,
-                            ..
-                        })
-                    )
-                } else {
-                    false
-                }
+    /// Wait for the next batch of terminal events, none if the engine has to be ticked
+    fn read_events(&mut self) -> Result<Vec<Event>> {
+        // Helper function that returns true if the input is complete and
+        // can be sent to the hosting application.
+        fn completed(events: &[Event]) -> bool {
+            if let Some(event) = events.last() {
+                matches!(
+                    event,
+                    Event::Key(KeyEvent {
+                        code: KeyCode::Enter,
+                        modifiers: KeyModifiers::NONE,
+                        ..
+                    })
+                )
+            } else {
+                false
             }
+        }
 
-            let mut events: Vec<Event> = vec![];
+        let mut events: Vec<Event> = vec![];
 
-            // If the `external_printer` feature is enabled, we need to
-            // periodically yield so that external printers get a chance to
-            // print. Otherwise, we can just block until we receive an event.
-            #[cfg(feature = "external_printer")]
-            if event::poll(EXTERNAL_PRINTER_WAIT)? {
-                events.push(crossterm::event::read()?);
+        // Block until we receive an event, unless the engine has to be ticked. A closed
+        // input has nothing to wait for, reading it fails.
+        match self.tick_timeout() {
+            Some(wait) if !self.input.is_closed() => {
+                if self.input.poll(wait)? {
+                    events.push(self.input.read()?);
+                }
             }
-            #[cfg(not(feature = "external_printer"))]
-            events.push(crossterm::event::read()?);
+            _ => events.push(self.input.read()?),
+        }
+
+        // Receive all events in the queue without blocking. Will stop when
+        // a line of input is completed.
+        while !completed(&events) && self.input.poll(Duration::from_millis(0))? {
+            events.push(self.input.read()?);
+        }
 
-            // Receive all events in the queue without blocking. Will stop when
-            // a line of input is completed.
-            while !completed(&events) && event::poll(Duration::from_millis(0))? {
-                events.push(crossterm::event::read()?);
+        // If we believe there's text pasting or resizing going on, batch
+        // more events at the cost of a slight delay.
+        if events.len() > EVENTS_THRESHOLD
+            || events.iter().any(|e| matches!(e, Event::Resize(_, _)))
+        {
+            while !completed(&events) && self.input.poll(POLL_WAIT)? {
+                events.push(self.input.read()?);
             }
+        }
 
-            // If we believe there's text pasting or resizing going on, batch
-            // more events at the cost of a slight delay.
-            if events.len() > EVENTS_THRESHOLD
-                || events.iter().any(|e| matches!(e, Event::Resize(_, _)))
-            {
-                while !completed(&events) && event::poll(POLL_WAIT)? {
-                    events.push(crossterm::event::read()?);
-                }
+        Ok(events)
+    }
+
+    /// Reset the state of the previous line and paint the prompt
+    fn start_line(&mut self, prompt: &dyn Prompt) -> Result<()> {
+        // Without the terminal, the line starts at the top of the screen
+        let cursor_position = if self.input.is_terminal() {
+            cursor::position()?
+        } else {
+            (0, 0)
+        };
+        self.painter
+            .initialize_prompt_position(self.suspended_state.as_ref(), cursor_position)?;
+        if self.suspended_state.is_some() {
+            // Last editor was suspended to run a ExecuteHostCommand event,
+            // we are resuming operation now.
+            self.suspended_state = None;
+        }
+        self.hide_hints = false;
+        self.yanked_arg = None;
+        self.which_key_visible = false;
+        self.custom_event_message = None;
+        self.sequence_deadline = None;
+        self.which_key_deadline = None;
+
+        self.repaint(prompt)
+    }
+
+    /// Apply a batch of terminal events, or the expired deadlines if there are none
+    fn process_events(
+        &mut self,
+        prompt: &dyn Prompt,
+        events: Vec<Event>,
+    ) -> Result<Option<Signal>> {
+        #[cfg(feature = "external_printer")]
+        if let Some(ref external_printer) = self.external_printer {
+            // get messages from printer as crlf separated "lines"
+            let messages = Self::external_messages(external_printer)?;
+            if !messages.is_empty() {
+                // print the message(s)
+                self.painter
+                    .print_external_message(messages, self.editor.line_buffer(), prompt)?;
+                self.repaint(prompt)?;
             }
+        }
 
-            // Convert `Event` into `ReedlineEvent`. Also, fuse consecutive
-            // `ReedlineEvent::EditCommand` into one. Also, if there're multiple
-            // `ReedlineEvent::Resize`, only keep the last one.
-            let mut reedline_events: Vec<ReedlineEvent> = vec![];
-            let mut edits = vec![];
-            let mut resize = None;
-            for event in events {
-                if let Ok(event) = ReedlineRawEvent::try_from(event) {
-                    match self.edit_mode.parse_event(event) {
-                        ReedlineEvent::Edit(edit) => edits.extend(edit),
-                        ReedlineEvent::Resize(x, y) => resize = Some((x, y)),
-                        event => {
-                            if !edits.is_empty() {
-                                reedline_events
-                                    .push(ReedlineEvent::Edit(std::mem::take(&mut edits)));
-                            }
-                            reedline_events.push(event);
+        // Convert `Event` into `ReedlineEvent`. Also, fuse consecutive
+        // `ReedlineEvent::EditCommand` into one. Also, if there're multiple
+        // `ReedlineEvent::Resize`, only keep the last one.
+        let mut reedline_events: Vec<ReedlineEvent> = vec![];
+        let mut edits = vec![];
+        let mut resize = None;
+        let typed = events.iter().any(|event| matches!(event, Event::Key(_)));
+        // The message of a custom event stays until the next key
+        let message_cleared = typed && self.custom_event_message.take().is_some();
+        // The pending key sequence waited long enough, list the keys that can follow
+        if events.is_empty()
+            && matches!(self.which_key_deadline, Some(deadline) if deadline <= Instant::now())
+        {
+            self.which_key_deadline = None;
+            self.sequence_deadline = None;
+            self.which_key_visible = true;
+            self.repaint(prompt)?;
+        }
+        // No key followed the pending key sequence in time, replay its keys
+        if events.is_empty()
+            && matches!(self.sequence_deadline, Some(deadline) if deadline <= Instant::now())
+        {
+            reedline_events.push(ReedlineEvent::Multiple(vec![
+                self.edit_mode.cancel_pending_sequence(),
+                ReedlineEvent::Repaint,
+            ]));
+        }
+        for event in events {
+            if let Ok(event) = ReedlineRawEvent::try_from(event) {
+                match self.edit_mode.parse_event(event) {
+                    ReedlineEvent::Edit(edit) => edits.extend(edit),
+                    ReedlineEvent::Resize(x, y) => resize = Some((x, y)),
+                    event => {
+                        if !edits.is_empty() {
+                            reedline_events.push(ReedlineEvent::Edit(std::mem::take(&mut edits)));
                         }
+                        reedline_events.push(event);
                     }
                 }
             }
-            if !edits.is_empty() {
-                reedline_events.push(ReedlineEvent::Edit(edits));
-            }
-            if let Some((x, y)) = resize {
-                reedline_events.push(ReedlineEvent::Resize(x, y));
-            }
+        }
+        if !edits.is_empty() {
+            reedline_events.push(ReedlineEvent::Edit(edits));
+        }
+        if let Some((x, y)) = resize {
+            reedline_events.push(ReedlineEvent::Resize(x, y));
+        }
 
-            // Handle reedline events.
-            for event in reedline_events {
-                match self.handle_event(prompt, event)? {
-                    EventStatus::Exits(signal) => {
-                        // Check if we are merely suspended (to process an ExecuteHostCommand event)
-                        // or if we're about to quit the editor.
-                        if self.suspended_state.is_none() {
-                            // We are about to quit the editor, move the cursor below the input
-                            // area, for external commands or new read_line call
-                            self.painter.move_cursor_to_end()?;
-                        }
-                        return Ok(signal);
-                    }
-                    EventStatus::Handled => {
-                        self.repaint(prompt)?;
-                    }
-                    EventStatus::Inapplicable => {
-                        // Nothing changed, no need to repaint
+        // Handle reedline events.
+        for event in reedline_events {
+            match self.handle_event(prompt, event)? {
+                EventStatus::Exits(signal) => {
+                    // Check if we are merely suspended (to process an ExecuteHostCommand event)
+                    // or if we're about to quit the editor.
+                    if self.suspended_state.is_none() {
+                        // We are about to quit the editor, move the cursor below the input
+                        // area, for external commands or new read_line call
+                        self.painter.move_cursor_to_end()?;
                     }
+                    return Ok(Some(signal));
+                }
+                EventStatus::Handled => {
+                    self.repaint(prompt)?;
+                }
+                EventStatus::Inapplicable => {
+                    // Nothing changed, no need to repaint
                 }
             }
         }
+
+        // Show the keys of a pending sequence right away, even if no event was handled,
+        // and update the overlay listing the keys that can follow
+        let pending = self.edit_mode.has_pending_sequence();
+        let which_key_changed = self.which_key_visible && typed;
+        self.which_key_visible &= pending;
+        if which_key_changed
+            || message_cleared
+            || self.edit_mode.provisional_text() != self.painted_provisional_text
+        {
+            self.repaint(prompt)?;
+        }
+
+        self.which_key_deadline = match self.which_key_delay {
+            Some(delay) if pending && !self.which_key_visible => {
+                if typed {
+                    Some(Instant::now() + delay)
+                } else {
+                    self.which_key_deadline
+                }
+            }
+            _ => None,
+        };
+        self.sequence_deadline = match self.key_sequence_timeout {
+            // With the overlay open, the sequence waits for the user to pick a key
+            Some(timeout) if pending && !self.which_key_visible => {
+                if typed {
+                    Some(Instant::now() + timeout)
+                } else {
+                    self.sequence_deadline
+                }
+            }
+            _ => None,
+        };
+
+        Ok(None)
     }
 
     fn handle_event(&mut self, prompt: &dyn Prompt, event: ReedlineEvent) -> Result<EventStatus> {
-        if self.input_mode == InputMode::HistorySearch {
-            self.handle_history_search_event(event)
-        } else {
-            self.handle_editor_event(prompt, event)
+        let searching = self.is_searching();
+        let status = match self.input_mode {
+            InputMode::HistorySearch => self.handle_history_search_event(event),
+            InputMode::BufferSearch => self.handle_buffer_search_event(event),
+            InputMode::ExCommand => self.handle_ex_command_event(prompt, event),
+            InputMode::Regular | InputMode::HistoryTraversal => {
+                self.handle_editor_event(prompt, event)
+            }
+        };
+        // The edit mode types the search until the search ends, however it ended
+        if searching && !self.is_searching() {
+            self.edit_mode.exit_search();
         }
+        status
+    }
+
+    fn is_searching(&self) -> bool {
+        matches!(
+            self.input_mode,
+            InputMode::BufferSearch | InputMode::ExCommand
+        )
     }
 
     fn handle_history_search_event(&mut self, event: ReedlineEvent) -> io::Result<EventStatus> {
@@ -925,7 +1255,214 @@ impl Reedline {
             | ReedlineEvent::MenuRight
             | ReedlineEvent::MenuPageNext
             | ReedlineEvent::MenuPagePrevious
-            | ReedlineEvent::Helix(_) => Ok(EventStatus::Inapplicable),
+            | ReedlineEvent::SearchBuffer { .. }
+            | ReedlineEvent::SearchBufferNext
+            | ReedlineEvent::SearchBufferPrevious
+            | ReedlineEvent::ExCommandLine
+            | ReedlineEvent::YankLastArg
+            | ReedlineEvent::YankNthArg(_)
+            | ReedlineEvent::Custom(_)
+            | ReedlineEvent::Helix(_)
+            | ReedlineEvent::Emacs(_) => Ok(EventStatus::Inapplicable),
+        }
+    }
+
+    fn handle_buffer_search_event(&mut self, event: ReedlineEvent) -> io::Result<EventStatus> {
+        match event {
+            ReedlineEvent::UntilFound(events) => {
+                for event in events {
+                    match self.handle_buffer_search_event(event)? {
+                        EventStatus::Inapplicable => {
+                            // Try again with the next event handler
+                        }
+                        success => {
+                            return Ok(success);
+                        }
+                    }
+                }
+                // Exhausting the event handlers is still considered handled
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Multiple(events) => {
+                let mut latest_signal = EventStatus::Inapplicable;
+                for event in events {
+                    match self.handle_buffer_search_event(event)? {
+                        EventStatus::Handled => latest_signal = EventStatus::Handled,
+                        EventStatus::Inapplicable => {}
+                        exits => return Ok(exits),
+                    }
+                }
+                Ok(latest_signal)
+            }
+            ReedlineEvent::Edit(commands) => {
+                self.run_buffer_search_commands(&commands);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::SearchBuffer { backward, .. } => {
+                self.jump_to_buffer_search_match(backward);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::SearchBufferNext => {
+                self.jump_to_buffer_search_match(false);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::SearchBufferPrevious => {
+                self.jump_to_buffer_search_match(true);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Enter
+            | ReedlineEvent::HistoryHintComplete
+            | ReedlineEvent::Submit
+            | ReedlineEvent::SubmitOrNewline => {
+                // The cursor already sits on the match
+                self.input_mode = InputMode::Regular;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Esc => {
+                self.cancel_buffer_search();
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::CtrlC => {
+                self.cancel_buffer_search();
+                Ok(EventStatus::Exits(Signal::CtrlC))
+            }
+            ReedlineEvent::ClearScreen => {
+                self.painter.clear_screen()?;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::ClearScrollback => {
+                self.painter.clear_scrollback()?;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Resize(width, height) => {
+                self.painter.handle_resize(width, height);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Mouse | ReedlineEvent::Repaint => Ok(EventStatus::Handled),
+            ReedlineEvent::None
+            | ReedlineEvent::CtrlD
+            | ReedlineEvent::HistoryHintWordComplete
+            | ReedlineEvent::PreviousHistory
+            | ReedlineEvent::Up
+            | ReedlineEvent::Down
+            | ReedlineEvent::Right
+            | ReedlineEvent::Left
+            | ReedlineEvent::NextHistory
+            | ReedlineEvent::SearchHistory
+            | ReedlineEvent::Menu(_)
+            | ReedlineEvent::MenuNext
+            | ReedlineEvent::MenuPrevious
+            | ReedlineEvent::MenuUp
+            | ReedlineEvent::MenuDown
+            | ReedlineEvent::MenuLeft
+            | ReedlineEvent::MenuRight
+            | ReedlineEvent::MenuPageNext
+            | ReedlineEvent::MenuPagePrevious
+            | ReedlineEvent::ExecuteHostCommand(_)
+            | ReedlineEvent::OpenEditor
+            | ReedlineEvent::ExCommandLine
+            | ReedlineEvent::YankLastArg
+            | ReedlineEvent::YankNthArg(_)
+            | ReedlineEvent::Custom(_)
+            | ReedlineEvent::Helix(_)
+            | ReedlineEvent::Emacs(_) => Ok(EventStatus::Inapplicable),
+        }
+    }
+
+    fn handle_ex_command_event(
+        &mut self,
+        prompt: &dyn Prompt,
+        event: ReedlineEvent,
+    ) -> io::Result<EventStatus> {
+        match event {
+            ReedlineEvent::UntilFound(events) => {
+                for event in events {
+                    match self.handle_ex_command_event(prompt, event)? {
+                        EventStatus::Inapplicable => {
+                            // Try again with the next event handler
+                        }
+                        success => {
+                            return Ok(success);
+                        }
+                    }
+                }
+                // Exhausting the event handlers is still considered handled
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Multiple(events) => {
+                let mut latest_signal = EventStatus::Inapplicable;
+                for event in events {
+                    match self.handle_ex_command_event(prompt, event)? {
+                        EventStatus::Handled => latest_signal = EventStatus::Handled,
+                        EventStatus::Inapplicable => {}
+                        exits => return Ok(exits),
+                    }
+                }
+                Ok(latest_signal)
+            }
+            ReedlineEvent::Edit(commands) => {
+                self.run_ex_command_line_commands(&commands);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Enter
+            | ReedlineEvent::HistoryHintComplete
+            | ReedlineEvent::Submit
+            | ReedlineEvent::SubmitOrNewline => {
+                self.input_mode = InputMode::Regular;
+                let line = std::mem::take(&mut self.ex_command_line);
+                self.run_ex_command_line(prompt, &line)
+            }
+            ReedlineEvent::Esc => {
+                self.cancel_ex_command_line();
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::CtrlC => {
+                self.cancel_ex_command_line();
+                Ok(EventStatus::Exits(Signal::CtrlC))
+            }
+            ReedlineEvent::ClearScreen => {
+                self.painter.clear_screen()?;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::ClearScrollback => {
+                self.painter.clear_scrollback()?;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Resize(width, height) => {
+                self.painter.handle_resize(width, height);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Mouse | ReedlineEvent::Repaint => Ok(EventStatus::Handled),
+            ReedlineEvent::None
+            | ReedlineEvent::CtrlD
+            | ReedlineEvent::HistoryHintWordComplete
+            | ReedlineEvent::PreviousHistory
+            | ReedlineEvent::Up
+            | ReedlineEvent::Down
+            | ReedlineEvent::Right
+            | ReedlineEvent::Left
+            | ReedlineEvent::NextHistory
+            | ReedlineEvent::SearchHistory
+            | ReedlineEvent::SearchBuffer { .. }
+            | ReedlineEvent::SearchBufferNext
+            | ReedlineEvent::SearchBufferPrevious
+            | ReedlineEvent::Menu(_)
+            | ReedlineEvent::MenuNext
+            | ReedlineEvent::MenuPrevious
+            | ReedlineEvent::MenuUp
+            | ReedlineEvent::MenuDown
+            | ReedlineEvent::MenuLeft
+            | ReedlineEvent::MenuRight
+            | ReedlineEvent::MenuPageNext
+            | ReedlineEvent::MenuPagePrevious
+            | ReedlineEvent::ExecuteHostCommand(_)
+            | ReedlineEvent::OpenEditor
+            | ReedlineEvent::ExCommandLine
+            | ReedlineEvent::YankLastArg
+            | ReedlineEvent::YankNthArg(_)
+            | ReedlineEvent::Custom(_)
+            | ReedlineEvent::Helix(_)
+            | ReedlineEvent::Emacs(_) => Ok(EventStatus::Inapplicable),
         }
     }
 
@@ -1067,6 +1604,7 @@ impl Reedline {
             }
             ReedlineEvent::Esc => {
                 self.deactivate_menus();
+                self.editor.deactivate_mark();
                 Ok(EventStatus::Handled)
             }
             ReedlineEvent::CtrlD => {
@@ -1234,6 +1772,42 @@ impl Reedline {
                 self.enter_history_search();
                 Ok(EventStatus::Handled)
             }
+            ReedlineEvent::YankLastArg => {
+                self.yank_history_arg(None);
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::YankNthArg(index) => {
+                self.yank_history_arg(Some(index));
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::Custom(name) => Ok(self.run_custom_event(&name)),
+            ReedlineEvent::SearchBuffer { backward, select } => {
+                self.buffer_search = Some(BufferSearch::new(
+                    backward,
+                    select,
+                    self.editor.insertion_point(),
+                ));
+                self.input_mode = InputMode::BufferSearch;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::ExCommandLine => {
+                self.deactivate_menus();
+                self.ex_command_line.clear();
+                self.input_mode = InputMode::ExCommand;
+                Ok(EventStatus::Handled)
+            }
+            ReedlineEvent::SearchBufferNext | ReedlineEvent::SearchBufferPrevious => {
+                match &self.buffer_search {
+                    Some(search) if !search.term.is_empty() => {
+                        self.jump_to_buffer_search_match(matches!(
+                            event,
+                            ReedlineEvent::SearchBufferPrevious
+                        ));
+                        Ok(EventStatus::Handled)
+                    }
+                    _ => Ok(EventStatus::Inapplicable),
+                }
+            }
             ReedlineEvent::Multiple(events) => {
                 let mut latest_signal = EventStatus::Inapplicable;
                 for event in events {
@@ -1269,9 +1843,10 @@ impl Reedline {
                 // Exhausting the event handlers is still considered handled
                 Ok(EventStatus::Inapplicable)
             }
-            ReedlineEvent::None | ReedlineEvent::Mouse | ReedlineEvent::Helix(_) => {
-                Ok(EventStatus::Inapplicable)
-            }
+            ReedlineEvent::None
+            | ReedlineEvent::Mouse
+            | ReedlineEvent::Helix(_)
+            | ReedlineEvent::Emacs(_) => Ok(EventStatus::Inapplicable),
         }
     }
 
@@ -1349,6 +1924,69 @@ impl Reedline {
             .update_undo_state(UndoBehavior::HistoryNavigation)
     }
 
+    /// Insert the argument at `index` of the previous history entry, the last one for `None`
+    ///
+    /// Right after inserting an argument, it gets replaced by the one of the entry before.
+    /// Entries without such an argument are skipped.
+    fn yank_history_arg(&mut self, index: Option<usize>) {
+        let line_buffer = self.editor.line_buffer();
+        let previous = self.yanked_arg.take().filter(|yanked| {
+            let end = yanked.start + yanked.text.len();
+            line_buffer.insertion_point() == end
+                && line_buffer.get_buffer().get(yanked.start..end) == Some(yanked.text.as_str())
+        });
+
+        let mut start_id = previous.as_ref().map(|yanked| yanked.id);
+        loop {
+            let query = SearchQuery {
+                start_id,
+                ..SearchQuery::last_with_search(SearchFilter::anything(
+                    self.get_history_session_id(),
+                ))
+            };
+            let Some(HistoryItem {
+                id: Some(id),
+                command_line,
+                ..
+            }) = self
+                .history
+                .search(query)
+                .ok()
+                .and_then(|mut items| items.pop())
+            else {
+                // Stay at the oldest entry when pressed again
+                self.yanked_arg = previous;
+                return;
+            };
+
+            let words = text_manipulation::split_shell_words(&command_line);
+            let word = match index {
+                Some(index) => words.get(index),
+                None => words.last(),
+            };
+            if let Some(word) = word {
+                let (start, end) = match &previous {
+                    Some(yanked) => (yanked.start, yanked.start + yanked.text.len()),
+                    None => (self.editor.insertion_point(), self.editor.insertion_point()),
+                };
+                self.editor.edit_buffer(
+                    |buffer| {
+                        buffer.replace_range(start..end, word);
+                        buffer.set_insertion_point(start + word.len());
+                    },
+                    UndoBehavior::CreateUndoPoint,
+                );
+                self.yanked_arg = Some(YankedArg {
+                    id,
+                    start,
+                    text: word.to_string(),
+                });
+                return;
+            }
+            start_id = Some(id);
+        }
+    }
+
     /// Enable the search and navigation through the history from the line buffer prompt
     ///
     /// Enables either prefix search with output in the line buffer or simple traversal
@@ -1381,6 +2019,148 @@ impl Reedline {
         self.input_mode = InputMode::HistorySearch;
     }
 
+    /// Dispatches the applicable [`EditCommand`] actions for editing the buffer search string
+    /// and moves to the first match from where the search started.
+    fn run_buffer_search_commands(&mut self, commands: &[EditCommand]) {
+        let Some(search) = self.buffer_search.as_mut() else {
+            return;
+        };
+        for command in commands {
+            match command {
+                EditCommand::InsertChar(c) => search.term.push(*c),
+                EditCommand::InsertString(s) => search.term.push_str(s),
+                EditCommand::Backspace => {
+                    search.term = text_manipulation::remove_last_grapheme(&search.term).to_string();
+                }
+                _ => {}
+            }
+        }
+
+        let (origin, backward) = (search.origin, search.backward);
+        match search.find(self.editor.get_buffer(), origin, backward) {
+            Some(found) => self.move_to_buffer_search_match(found),
+            None => self.run_edit_commands(&[EditCommand::MoveToPosition {
+                position: origin,
+                select: false,
+            }]),
+        }
+    }
+
+    /// Jumps to the next match of the buffer search, or the previous one if `reverse`
+    fn jump_to_buffer_search_match(&mut self, reverse: bool) {
+        let Some(search) = self.buffer_search.as_mut() else {
+            return;
+        };
+        let backward = search.backward != reverse;
+        let cursor = self.editor.insertion_point();
+        let from = if backward {
+            cursor
+        } else {
+            self.editor
+                .line_buffer()
+                .grapheme_right_index_from_pos(cursor)
+        };
+        if let Some(found) = search.find(self.editor.get_buffer(), from, backward) {
+            // Further typing continues from the new match
+            search.origin = found.start;
+            self.move_to_buffer_search_match(found);
+        }
+    }
+
+    fn move_to_buffer_search_match(&mut self, found: std::ops::Range<usize>) {
+        let select = matches!(&self.buffer_search, Some(search) if search.select);
+        let mut commands = vec![EditCommand::MoveToPosition {
+            position: found.start,
+            select: false,
+        }];
+        if select {
+            commands.push(EditCommand::MoveToPosition {
+                position: self
+                    .editor
+                    .line_buffer()
+                    .grapheme_left_index_from_pos(found.end),
+                select: true,
+            });
+        }
+        self.run_edit_commands(&commands);
+    }
+
+    /// Dispatches the applicable [`EditCommand`] actions for editing the ex command line
+    fn run_ex_command_line_commands(&mut self, commands: &[EditCommand]) {
+        for command in commands {
+            match command {
+                EditCommand::InsertChar(c) => self.ex_command_line.push(*c),
+                EditCommand::InsertString(s) => self.ex_command_line.push_str(s),
+                EditCommand::Backspace => {
+                    self.ex_command_line =
+                        text_manipulation::remove_last_grapheme(&self.ex_command_line).to_string();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Runs the command typed on the ex command line
+    ///
+    /// Commands registered by the host take precedence over the built-in `w` and `q`.
+    fn run_ex_command_line(&mut self, prompt: &dyn Prompt, line: &str) -> io::Result<EventStatus> {
+        match parse_ex_line(line) {
+            #[cfg(feature = "regex")]
+            Some(ExLine::Substitute(substitution)) => substitution.run(&mut self.editor),
+            Some(ExLine::Command { name, args }) => {
+                if let Some(command) = self.ex_commands.get_mut(name) {
+                    if let Some(signal) = command.run(args, &mut self.editor) {
+                        self.suspended_state = Some(self.painter.state_before_suspension());
+                        return Ok(EventStatus::Exits(signal));
+                    }
+                } else {
+                    match name {
+                        "w" | "write" | "wq" | "x" => {
+                            return self.handle_editor_event(prompt, ReedlineEvent::Submit);
+                        }
+                        "q" | "quit" => {
+                            return self.handle_editor_event(prompt, ReedlineEvent::CtrlD);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            None => {}
+        }
+        Ok(EventStatus::Handled)
+    }
+
+    /// Runs the callback registered for a [`ReedlineEvent::Custom`]
+    fn run_custom_event(&mut self, name: &str) -> EventStatus {
+        let Some(event) = self.custom_events.get_mut(name) else {
+            return EventStatus::Inapplicable;
+        };
+        match event.run(&mut self.editor, self.history.as_ref()) {
+            CustomEventResult::Handled => EventStatus::Handled,
+            CustomEventResult::Inapplicable => EventStatus::Inapplicable,
+            CustomEventResult::Message(message) => {
+                self.custom_event_message = Some(message);
+                EventStatus::Handled
+            }
+        }
+    }
+
+    fn cancel_ex_command_line(&mut self) {
+        self.ex_command_line.clear();
+        self.input_mode = InputMode::Regular;
+    }
+
+    /// Leaves the buffer search, moving the cursor back to where the search started
+    fn cancel_buffer_search(&mut self) {
+        if let Some(search) = self.buffer_search.take() {
+            self.run_edit_commands(&[EditCommand::MoveToPosition {
+                position: search.origin,
+                select: false,
+            }]);
+        }
+        self.input_mode = InputMode::Regular;
+    }
+
     /// Dispatches the applicable [`EditCommand`] actions for editing the history search string.
     ///
     /// Only modifies internal state, does not perform regular output!
@@ -1513,10 +2293,10 @@ impl Reedline {
     /// Repaint of either the buffer or the parts for reverse history search
     fn repaint(&mut self, prompt: &dyn Prompt) -> io::Result<()> {
         // Repainting
-        if self.input_mode == InputMode::HistorySearch {
-            self.history_search_paint(prompt)
-        } else {
-            self.buffer_paint(prompt)
+        match self.input_mode {
+            InputMode::HistorySearch => self.history_search_paint(prompt),
+            InputMode::ExCommand => self.ex_command_paint(prompt),
+            _ => self.buffer_paint(prompt),
         }
     }
 
@@ -1725,7 +2505,7 @@ impl Reedline {
             let lines = PromptLines::new(
                 prompt,
                 self.prompt_edit_mode(),
-                Some(prompt_history_search),
+                Some(PromptSearch::History(prompt_history_search)),
                 &res_string,
                 "",
                 "",
@@ -1744,6 +2524,29 @@ impl Reedline {
         Ok(())
     }
 
+    /// Repaint logic for the ex command line
+    ///
+    /// Overwrites the prompt indicator and shows the typed command in place of the buffer.
+    fn ex_command_paint(&mut self, prompt: &dyn Prompt) -> Result<()> {
+        let lines = PromptLines::new(
+            prompt,
+            self.prompt_edit_mode(),
+            Some(PromptSearch::ExCommand),
+            &self.ex_command_line,
+            "",
+            "",
+        );
+
+        self.painter.repaint_buffer(
+            prompt,
+            &lines,
+            self.prompt_edit_mode(),
+            None,
+            self.use_ansi_coloring,
+            &self.cursor_shapes,
+        )
+    }
+
     /// Triggers a full repaint including the prompt parts
     ///
     /// Includes the highlighting and hinting calls.
@@ -1754,16 +2557,46 @@ impl Reedline {
         let mut styled_text = self
             .highlighter
             .highlight(buffer_to_paint, cursor_position_in_buffer);
+        let buffer_search = self
+            .buffer_search
+            .as_ref()
+            .filter(|_| self.input_mode == InputMode::BufferSearch);
+        let mut search_indicator = None;
+        if let Some(search) = buffer_search {
+            let mut matches = search.matches(buffer_to_paint).peekable();
+            let status = if search.term.is_empty() || matches.peek().is_some() {
+                PromptHistorySearchStatus::Passing
+            } else {
+                PromptHistorySearchStatus::Failing
+            };
+            for found in matches {
+                styled_text.style_range(found.start, found.end, self.buffer_search_style);
+            }
+            search_indicator = Some(PromptSearch::Buffer(PromptBufferSearch::new(
+                status,
+                search.backward,
+                search.term.clone(),
+            )));
+        }
         if let Some((from, to)) = self.editor.get_selection() {
             styled_text.style_range(from, to, self.visual_selection_style);
         }
+        for (from, to) in self.editor.get_secondary_selections() {
+            styled_text.style_range(from, to, self.visual_selection_style);
+        }
 
-        let (before_cursor, after_cursor) = styled_text.render_around_insertion_point(
+        let (mut before_cursor, after_cursor) = styled_text.render_around_insertion_point(
             cursor_position_in_buffer,
             prompt,
             self.use_ansi_coloring,
         );
 
+        // Keys of a pending sequence show up as if they were already inserted
+        self.painted_provisional_text = self.edit_mode.provisional_text();
+        if let Some(text) = &self.painted_provisional_text {
+            before_cursor.push_str(text);
+        }
+
         let hint: String = if self.hints_active() {
             self.hinter.as_mut().map_or_else(String::new, |hinter| {
                 hinter.handle(
@@ -1783,13 +2616,32 @@ impl Reedline {
             String::new()
         };
 
+        // The keys that can continue a pending sequence take the place of the hint
+        let continuations = if self.which_key_visible {
+            self.edit_mode.sequence_continuations()
+        } else {
+            vec![]
+        };
+        let hint = if !continuations.is_empty() {
+            which_key_string(
+                &continuations,
+                self.painter.screen_width(),
+                self.use_ansi_coloring,
+            )
+        } else if let Some(message) = &self.custom_event_message {
+            // So does the message of a custom event, on the line below the buffer
+            format!("\n{message}")
+        } else {
+            hint
+        };
+
         // Needs to add return carriage to newlines because when not in raw mode
         // some OS don't fully return the carriage
 
         let mut lines = PromptLines::new(
             prompt,
             self.prompt_edit_mode(),
-            None,
+            search_indicator,
             &before_cursor,
             &after_cursor,
             &hint,
@@ -1899,3 +2751,336 @@ fn thread_safe() {
     fn f<S: Send>(_: S) {}
     f(Reedline::create());
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        default_emacs_keybindings, default_helix_insert_keybindings,
+        default_helix_normal_keybindings, DefaultPrompt, Helix, KeySequence, PromptHelixMode,
+        PromptViMode, Vi,
+    };
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use std::collections::VecDeque;
+
+    fn with_history(entries: &[&str]) -> Reedline {
+        let mut history = FileBackedHistory::new(10).unwrap();
+        for entry in entries {
+            history
+                .save(HistoryItem::from_command_line(*entry))
+                .unwrap();
+        }
+        Reedline::create().with_history(Box::new(history))
+    }
+
+    #[test]
+    fn yank_last_arg_cycles_through_the_history() {
+        let mut reedline = with_history(&["ls 'my dir'", "echo", "cd foo"]);
+        let prompt = DefaultPrompt::default();
+        reedline.run_edit_commands(&[EditCommand::InsertString("cat ".into())]);
+
+        let yank = |reedline: &mut Reedline| {
+            reedline
+                .handle_editor_event(&prompt, ReedlineEvent::YankLastArg)
+                .unwrap();
+            reedline.current_buffer_contents().to_string()
+        };
+        assert_eq!(yank(&mut reedline), "cat foo");
+        assert_eq!(yank(&mut reedline), "cat echo");
+        assert_eq!(yank(&mut reedline), "cat 'my dir'");
+        assert_eq!(yank(&mut reedline), "cat 'my dir'");
+    }
+
+    #[test]
+    fn yank_nth_arg_skips_entries_without_the_arg() {
+        let mut reedline = with_history(&["git commit", "ls"]);
+        let prompt = DefaultPrompt::default();
+
+        reedline
+            .handle_editor_event(&prompt, ReedlineEvent::YankNthArg(1))
+            .unwrap();
+        assert_eq!(reedline.current_buffer_contents(), "commit");
+        assert_eq!(reedline.current_insertion_point(), 6);
+
+        // Moving away starts over with the most recent entry
+        reedline.run_edit_commands(&[EditCommand::MoveToStart { select: false }]);
+        reedline
+            .handle_editor_event(&prompt, ReedlineEvent::YankNthArg(0))
+            .unwrap();
+        assert_eq!(reedline.current_buffer_contents(), "lscommit");
+    }
+
+    #[test]
+    fn custom_event_edits_the_buffer_in_place() {
+        let mut reedline = with_history(&["ls", "cd foo"]).with_custom_event(
+            "count",
+            Box::new(|editor: &mut Editor, history: &dyn History| {
+                let count = history.count_all().unwrap_or_default();
+                editor.edit_buffer(
+                    |buffer| buffer.insert_str(&count.to_string()),
+                    UndoBehavior::CreateUndoPoint,
+                );
+                CustomEventResult::Handled
+            }),
+        );
+        let prompt = DefaultPrompt::default();
+
+        let status = reedline
+            .handle_editor_event(&prompt, ReedlineEvent::Custom("count".to_string()))
+            .unwrap();
+        assert!(matches!(status, EventStatus::Handled));
+        assert_eq!(reedline.current_buffer_contents(), "2");
+    }
+
+    #[test]
+    fn custom_event_can_leave_a_message() {
+        let mut reedline = Reedline::create()
+            .with_custom_event(
+                "skip",
+                Box::new(|_: &mut Editor, _: &dyn History| CustomEventResult::Inapplicable),
+            )
+            .with_custom_event(
+                "greet",
+                Box::new(|_: &mut Editor, _: &dyn History| {
+                    CustomEventResult::Message("hello".to_string())
+                }),
+            );
+        let prompt = DefaultPrompt::default();
+
+        let status = reedline
+            .handle_editor_event(
+                &prompt,
+                ReedlineEvent::UntilFound(vec![
+                    ReedlineEvent::Custom("unknown".to_string()),
+                    ReedlineEvent::Custom("skip".to_string()),
+                    ReedlineEvent::Custom("greet".to_string()),
+                ]),
+            )
+            .unwrap();
+        assert!(matches!(status, EventStatus::Handled));
+        assert_eq!(reedline.custom_event_message.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn raw_events_are_handled_until_a_signal() {
+        let mut reedline = Reedline::create();
+        let prompt = DefaultPrompt::default();
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        // Resizes are applied after the rest of a batch
+        let signal = reedline
+            .handle_raw_event(&prompt, Event::Resize(80, 24))
+            .unwrap();
+        assert!(signal.is_none());
+        let signal = reedline
+            .handle_raw_events(
+                &prompt,
+                vec![key(KeyCode::Char('l')), key(KeyCode::Char('s'))],
+            )
+            .unwrap();
+        assert!(signal.is_none());
+        assert!(reedline.tick(&prompt).unwrap().is_none());
+        let signal = reedline
+            .handle_raw_event(&prompt, key(KeyCode::Enter))
+            .unwrap();
+        assert!(matches!(signal, Some(Signal::Success(line)) if line == "ls"));
+    }
+
+    #[test]
+    fn tick_replays_a_timed_out_key_sequence() {
+        let mut keybindings = default_emacs_keybindings();
+        keybindings.add_binding(
+            "j".parse().unwrap(),
+            vec!["k".parse().unwrap()],
+            ReedlineEvent::Esc,
+        );
+        let mut reedline = Reedline::create()
+            .with_edit_mode(Box::new(Emacs::new(keybindings)))
+            .with_key_sequence_timeout(Some(Duration::ZERO));
+        let prompt = DefaultPrompt::default();
+
+        reedline
+            .handle_raw_event(&prompt, Event::Resize(80, 24))
+            .unwrap();
+        reedline
+            .handle_raw_event(
+                &prompt,
+                Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)),
+            )
+            .unwrap();
+        assert_eq!(reedline.current_buffer_contents(), "");
+        assert_eq!(reedline.tick_timeout(), Some(Duration::ZERO));
+        reedline.tick(&prompt).unwrap();
+        assert_eq!(reedline.current_buffer_contents(), "j");
+    }
+
+    /// Replays the keys of a key notation instead of reading the terminal
+    struct Keystrokes(VecDeque<Event>);
+
+    impl Keystrokes {
+        fn new(keys: &str) -> Self {
+            let keys: KeySequence = keys.parse().unwrap();
+            Keystrokes(
+                keys.0
+                    .into_iter()
+                    .map(|key| Event::Key(KeyEvent::new(key.key_code, key.modifier)))
+                    .collect(),
+            )
+        }
+    }
+
+    impl InputSource for Keystrokes {
+        fn poll(&mut self, _timeout: Duration) -> Result<bool> {
+            Ok(!self.0.is_empty())
+        }
+
+        fn read(&mut self) -> Result<Event> {
+            self.0
+                .pop_front()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "out of keys"))
+        }
+
+        fn is_closed(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        fn is_terminal(&self) -> bool {
+            false
+        }
+    }
+
+    #[rstest]
+    #[case("l s Enter", "Success(\"ls\")")]
+    #[case("a b Backspace C-a c Enter", "Success(\"ca\")")]
+    #[case("e c h o C-c", "CtrlC")]
+    #[case("C-d", "CtrlD")]
+    fn read_line_returns_the_signal_of_the_keys(#[case] keys: &str, #[case] expected: &str) {
+        let mut reedline = Reedline::create().with_input_source(Box::new(Keystrokes::new(keys)));
+
+        let signal = reedline.read_line(&DefaultPrompt::default()).unwrap();
+        assert_eq!(format!("{signal:?}"), expected);
+    }
+
+    #[test]
+    fn read_line_fails_when_the_input_ends() {
+        let mut reedline = Reedline::create().with_input_source(Box::new(Keystrokes::new("l s")));
+
+        let error = reedline.read_line(&DefaultPrompt::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reedline.current_buffer_contents(), "ls");
+    }
+
+    #[test]
+    fn overlapping_helix_selections_are_merged_before_an_edit() {
+        let mut reedline = Reedline::create()
+            .with_edit_mode(Box::new(Helix::new(
+                default_helix_insert_keybindings(),
+                default_helix_normal_keybindings(),
+            )))
+            .with_input_source(Box::new(Keystrokes::new(
+                "a b a b a b a b Esc g h v g l s a b Enter l l d Enter",
+            )));
+
+        let error = reedline.read_line(&DefaultPrompt::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reedline.current_buffer_contents(), "");
+    }
+
+    #[rstest]
+    #[case(
+        "o n e Space t w o Space t h r e e Esc 0 \" a 2 d w",
+        "one two ",
+        "three"
+    )]
+    #[case(
+        "o n e A-Enter t w o A-Enter t h r e e Esc k k \" a 2 d d",
+        "one\ntwo\n",
+        "three"
+    )]
+    #[case("o n e Space t w o Esc 0 \" a 3 x P", "one", "one two")]
+    fn counted_vi_commands_fill_the_register(
+        #[case] keys: &str,
+        #[case] register: &str,
+        #[case] buffer: &str,
+    ) {
+        let mut reedline = Reedline::create()
+            .with_edit_mode(Box::new(Vi::default()))
+            .with_input_source(Box::new(Keystrokes::new(keys)));
+
+        let error = reedline.read_line(&DefaultPrompt::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reedline.editor.registers().get('a'), Some(register));
+        assert_eq!(reedline.current_buffer_contents(), buffer);
+    }
+
+    #[rstest]
+    #[case("Esc : q Enter", Some("CtrlD"), "")]
+    #[case("a b Esc : q Enter", None, "ab")]
+    #[case("a b Esc : w Enter", Some("Success(\"ab\")"), "")]
+    fn ex_quit_acts_like_ctrl_d(
+        #[case] keys: &str,
+        #[case] signal: Option<&str>,
+        #[case] buffer: &str,
+    ) {
+        let mut reedline = Reedline::create()
+            .with_edit_mode(Box::new(Vi::default()))
+            .with_input_source(Box::new(Keystrokes::new(keys)));
+
+        // Without a signal the keys run out
+        let result = reedline.read_line(&DefaultPrompt::default()).ok();
+        assert_eq!(
+            result.map(|signal| format!("{signal:?}")).as_deref(),
+            signal
+        );
+        assert_eq!(reedline.current_buffer_contents(), buffer);
+    }
+
+    #[rstest]
+    #[case(Box::new(Vi::default()), PromptEditMode::Vi(PromptViMode::Normal))]
+    #[case(
+        Box::new(Helix::new(
+            default_helix_insert_keybindings(),
+            default_helix_normal_keybindings(),
+        )),
+        PromptEditMode::Helix(PromptHelixMode::Normal)
+    )]
+    fn ctrl_c_leaves_the_search_mode(
+        #[case] edit_mode: Box<dyn EditMode>,
+        #[case] expected: PromptEditMode,
+    ) {
+        let mut reedline = Reedline::create()
+            .with_edit_mode(edit_mode)
+            .with_input_source(Box::new(Keystrokes::new("a Esc / a C-c")));
+
+        let signal = reedline.read_line(&DefaultPrompt::default()).unwrap();
+        assert!(matches!(signal, Signal::CtrlC));
+        assert_eq!(reedline.prompt_edit_mode(), expected);
+    }
+
+    #[rstest]
+    #[case("", "abc d")]
+    #[case("Esc", "abc ef")]
+    #[case("C-g", "abc ef")]
+    fn quitting_drops_the_mark(#[case] quit: &str, #[case] expected: &str) {
+        let keys = format!("a b c Space d e f C-Space Left Left {quit} C-w");
+        let mut reedline = Reedline::create().with_input_source(Box::new(Keystrokes::new(&keys)));
+
+        let error = reedline.read_line(&DefaultPrompt::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reedline.current_buffer_contents(), expected);
+    }
+
+    #[rstest]
+    #[case("C-w", "")]
+    #[case("M-w", "ab")]
+    fn history_recall_drops_the_mark(#[case] kill: &str, #[case] expected: &str) {
+        let keys = format!("a b c d e f g h C-Space Left Up {kill}");
+        let mut reedline =
+            with_history(&["ab"]).with_input_source(Box::new(Keystrokes::new(&keys)));
+
+        let error = reedline.read_line(&DefaultPrompt::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reedline.current_buffer_contents(), expected);
+    }
+}
@@ -44,6 +44,24 @@ pub enum EditCommand {
         select: bool,
     },
 
+    /// Move to the first non-whitespace character of the current line
+    MoveToLineNonBlankStart {
+        /// Select the text between the current cursor position and destination
+        select: bool,
+    },
+
+    /// Move one line up within the buffer
+    MoveLineUp {
+        /// Select the text between the current cursor position and destination
+        select: bool,
+    },
+
+    /// Move one line down within the buffer
+    MoveLineDown {
+        /// Select the text between the current cursor position and destination
+        select: bool,
+    },
+
     /// Move one character to the left
     MoveLeft {
         /// Select the text between the current cursor position and destination
@@ -187,6 +205,12 @@ pub enum EditCommand {
     /// Paste the cut buffer in front of the insertion point (vi `p`)
     PasteCutBufferAfter,
 
+    /// Insert the most recent kill from the kill ring (Emacs `C-y`)
+    Yank,
+
+    /// Replace the text inserted right before by a yank with the previous kill (Emacs `M-y`)
+    YankPop,
+
     /// Upper case the current word
     UppercaseWord,
 
@@ -196,6 +220,9 @@ pub enum EditCommand {
     /// Capitalize the current character
     CapitalizeChar,
 
+    /// Capitalize the current word, lower casing the rest of it
+    CapitalizeWord,
+
     /// Switch the case of the current character
     SwitchcaseChar,
 
@@ -255,6 +282,57 @@ pub enum EditCommand {
         select: bool,
     },
 
+    /// Copy the current line
+    CopyCurrentLine,
+
+    /// Copy from the start of the current line to the insertion point
+    CopyFromLineStart,
+
+    /// Copy from the insertion point to the end of the current line
+    CopyToLineEnd,
+
+    /// Copy the word left of the insertion point
+    CopyWordLeft,
+
+    /// Copy the WORD left of the insertion point
+    CopyBigWordLeft,
+
+    /// Copy the word right of the insertion point
+    CopyWordRight,
+
+    /// Copy the WORD right of the insertion point
+    CopyBigWordRight,
+
+    /// Copy the word right of the insertion point and any following space
+    CopyWordRightToNext,
+
+    /// Copy the WORD right of the insertion point and any following space
+    CopyBigWordRightToNext,
+
+    /// Copy the grapheme left of the insertion point
+    CopyLeft,
+
+    /// Copy the grapheme right of the insertion point
+    CopyRight,
+
+    /// CopyUntil right until char
+    CopyRightUntil(char),
+
+    /// CopyUntil right before char
+    CopyRightBefore(char),
+
+    /// CopyUntil left until char
+    CopyLeftUntil(char),
+
+    /// CopyUntil left before char
+    CopyLeftBefore(char),
+
+    /// Cut the text object at the cursor
+    CutTextObject(TextObject),
+
+    /// Copy the text object at the cursor
+    CopyTextObject(TextObject),
+
     /// Select whole input buffer
     SelectAll,
 
@@ -270,6 +348,106 @@ pub enum EditCommand {
     /// Clears the current selection
     ClearSelection,
 
+    /// Select the grapheme under the cursor unless a selection is already active
+    EnsureSelection,
+
+    /// Set the mark at the cursor, the region between them follows the cursor moves
+    /// until the next edit (Emacs `C-Space`)
+    SetMark,
+
+    /// Swap the cursor and the mark, activating the region (Emacs `C-x C-x`)
+    ExchangePointAndMark,
+
+    /// Cut the region between the mark and the cursor, or the word left of the cursor
+    /// without an active mark (Emacs `C-w`)
+    KillRegion,
+
+    /// Paste the cut buffer behind the end of the selection or the cursor (Helix `p`)
+    PasteAfterSelection,
+
+    /// Paste the cut buffer in front of the start of the selection or the cursor (Helix `P`)
+    PasteBeforeSelection,
+
+    /// Replace every grapheme of the selection, or the one under the cursor, with the given char
+    ReplaceSelectionChars(char),
+
+    /// Move to the bracket matching the one under the cursor
+    MoveToMatchingBracket {
+        /// Select the text between the current cursor position and destination
+        select: bool,
+    },
+
+    /// Surround the selection, or the grapheme under the cursor, with the pair belonging to the char
+    SurroundSelection(char),
+
+    /// Replace the pair of the first char surrounding the cursor with the pair of the second char
+    ReplaceSurrounding(char, char),
+
+    /// Delete the pair of the char surrounding the cursor
+    DeleteSurrounding(char),
+
+    /// Select the text object at the cursor
+    SelectTextObject(TextObject),
+
+    /// Add a copy of every selection on the line below it, the copy of the primary selection becoming the new primary one
+    CopySelectionToNextLine,
+
+    /// Replace the selections with a selection for every match of the regex inside of them
+    ///
+    /// Without the `regex` feature the pattern matches literally.
+    SelectRegexMatches(String),
+
+    /// Select up to the `count`th occurrence of `c` on the current line (Helix `f`, `t`, `F`
+    /// and `T`)
+    ///
+    /// Leaves the selection as it is if there are fewer occurrences.
+    SelectToChar {
+        /// Char to search for
+        c: char,
+        /// Search to the right of the cursor instead of to the left
+        right: bool,
+        /// Stop in front of the char
+        before_char: bool,
+        /// Which occurrence to select up to
+        count: usize,
+        /// Extend the selection instead of starting a new one at the cursor
+        extend: bool,
+    },
+
+    /// Drop all selections except the primary one
+    KeepPrimarySelection,
+
+    /// Use the register with the given name for the next cut, copy or paste
+    SelectRegister(char),
+
+    /// Change the shape of the selection, starting one at the cursor if there is none
+    SetSelectionMode(SelectionMode),
+
+    /// Upper case the selection, or the current character without one
+    UppercaseSelection,
+
+    /// Lower case the selection, or the current character without one
+    LowercaseSelection,
+
+    /// Switch the case of the selection, or of the current character without one
+    SwitchcaseSelection,
+
+    /// Indent the lines touched by the selection, or the current line without one
+    IndentLines,
+
+    /// Remove one level of indentation from the lines touched by the selection,
+    /// or from the current line without one
+    DedentLines,
+
+    /// Join the lines touched by the selection, or the current line with the next one
+    JoinLines,
+
+    /// Add the amount to the number under or after the cursor on the current line
+    IncrementNumber(i64),
+
+    /// Subtract the amount from the number under or after the cursor on the current line
+    DecrementNumber(i64),
+
     /// Cut selection to system clipboard
     #[cfg(feature = "system_clipboard")]
     CutSelectionSystem,
@@ -294,6 +472,11 @@ impl Display for EditCommand {
             EditCommand::MoveToLineEnd { .. } => {
                 write!(f, "MoveToLineEnd Optional[select: <bool>]")
             }
+            EditCommand::MoveToLineNonBlankStart { .. } => {
+                write!(f, "MoveToLineNonBlankStart Optional[select: <bool>]")
+            }
+            EditCommand::MoveLineUp { .. } => write!(f, "MoveLineUp Optional[select: <bool>]"),
+            EditCommand::MoveLineDown { .. } => write!(f, "MoveLineDown Optional[select: <bool>]"),
             EditCommand::MoveLeft { .. } => write!(f, "MoveLeft Optional[select: <bool>]"),
             EditCommand::MoveRight { .. } => write!(f, "MoveRight Optional[select: <bool>]"),
             EditCommand::MoveWordLeft { .. } => write!(f, "MoveWordLeft Optional[select: <bool>]"),
@@ -349,11 +532,14 @@ impl Display for EditCommand {
             EditCommand::CutWordRightToNext => write!(f, "CutWordRightToNext"),
             EditCommand::CutBigWordRightToNext => write!(f, "CutBigWordRightToNext"),
             EditCommand::PasteCutBufferBefore => write!(f, "PasteCutBufferBefore"),
+            EditCommand::Yank => write!(f, "Yank"),
+            EditCommand::YankPop => write!(f, "YankPop"),
             EditCommand::PasteCutBufferAfter => write!(f, "PasteCutBufferAfter"),
             EditCommand::UppercaseWord => write!(f, "UppercaseWord"),
             EditCommand::LowercaseWord => write!(f, "LowercaseWord"),
             EditCommand::SwitchcaseChar => write!(f, "SwitchcaseChar"),
             EditCommand::CapitalizeChar => write!(f, "CapitalizeChar"),
+            EditCommand::CapitalizeWord => write!(f, "CapitalizeWord"),
             EditCommand::SwapWords => write!(f, "SwapWords"),
             EditCommand::SwapGraphemes => write!(f, "SwapGraphemes"),
             EditCommand::Undo => write!(f, "Undo"),
@@ -369,6 +555,54 @@ impl Display for EditCommand {
             EditCommand::CopySelection => write!(f, "CopySelection"),
             EditCommand::Paste => write!(f, "Paste"),
             EditCommand::ClearSelection => write!(f, "ClearSelection"),
+            EditCommand::SetMark => write!(f, "SetMark"),
+            EditCommand::ExchangePointAndMark => write!(f, "ExchangePointAndMark"),
+            EditCommand::KillRegion => write!(f, "KillRegion"),
+            EditCommand::EnsureSelection => write!(f, "EnsureSelection"),
+            EditCommand::PasteAfterSelection => write!(f, "PasteAfterSelection"),
+            EditCommand::PasteBeforeSelection => write!(f, "PasteBeforeSelection"),
+            EditCommand::ReplaceSelectionChars(_) => write!(f, "ReplaceSelectionChars <char>"),
+            EditCommand::MoveToMatchingBracket { .. } => {
+                write!(f, "MoveToMatchingBracket Optional[select: <bool>]")
+            }
+            EditCommand::SurroundSelection(_) => write!(f, "SurroundSelection <char>"),
+            EditCommand::ReplaceSurrounding(_, _) => write!(f, "ReplaceSurrounding <char> <char>"),
+            EditCommand::DeleteSurrounding(_) => write!(f, "DeleteSurrounding <char>"),
+            EditCommand::SelectTextObject(_) => write!(f, "SelectTextObject <text object>"),
+            EditCommand::CopyCurrentLine => write!(f, "CopyCurrentLine"),
+            EditCommand::CopyFromLineStart => write!(f, "CopyFromLineStart"),
+            EditCommand::CopyToLineEnd => write!(f, "CopyToLineEnd"),
+            EditCommand::CopyWordLeft => write!(f, "CopyWordLeft"),
+            EditCommand::CopyBigWordLeft => write!(f, "CopyBigWordLeft"),
+            EditCommand::CopyWordRight => write!(f, "CopyWordRight"),
+            EditCommand::CopyBigWordRight => write!(f, "CopyBigWordRight"),
+            EditCommand::CopyWordRightToNext => write!(f, "CopyWordRightToNext"),
+            EditCommand::CopyBigWordRightToNext => write!(f, "CopyBigWordRightToNext"),
+            EditCommand::CopyLeft => write!(f, "CopyLeft"),
+            EditCommand::CopyRight => write!(f, "CopyRight"),
+            EditCommand::CopyRightUntil(_) => write!(f, "CopyRightUntil Value: <char>"),
+            EditCommand::CopyRightBefore(_) => write!(f, "CopyRightBefore Value: <char>"),
+            EditCommand::CopyLeftUntil(_) => write!(f, "CopyLeftUntil Value: <char>"),
+            EditCommand::CopyLeftBefore(_) => write!(f, "CopyLeftBefore Value: <char>"),
+            EditCommand::CutTextObject(_) => write!(f, "CutTextObject <text object>"),
+            EditCommand::CopyTextObject(_) => write!(f, "CopyTextObject <text object>"),
+            EditCommand::CopySelectionToNextLine => write!(f, "CopySelectionToNextLine"),
+            EditCommand::SelectRegexMatches(_) => write!(f, "SelectRegexMatches <regex>"),
+            EditCommand::SelectToChar { .. } => write!(
+                f,
+                "SelectToChar Value: <char>, <right: bool>, <before_char: bool>, <count: usize>, <extend: bool>"
+            ),
+            EditCommand::KeepPrimarySelection => write!(f, "KeepPrimarySelection"),
+            EditCommand::SelectRegister(_) => write!(f, "SelectRegister Value: <char>"),
+            EditCommand::SetSelectionMode(_) => write!(f, "SetSelectionMode <selection mode>"),
+            EditCommand::UppercaseSelection => write!(f, "UppercaseSelection"),
+            EditCommand::LowercaseSelection => write!(f, "LowercaseSelection"),
+            EditCommand::SwitchcaseSelection => write!(f, "SwitchcaseSelection"),
+            EditCommand::IndentLines => write!(f, "IndentLines"),
+            EditCommand::DedentLines => write!(f, "DedentLines"),
+            EditCommand::JoinLines => write!(f, "JoinLines"),
+            EditCommand::IncrementNumber(_) => write!(f, "IncrementNumber Value: <int>"),
+            EditCommand::DecrementNumber(_) => write!(f, "DecrementNumber Value: <int>"),
             #[cfg(feature = "system_clipboard")]
             EditCommand::CutSelectionSystem => write!(f, "CutSelectionSystem"),
             #[cfg(feature = "system_clipboard")]
@@ -390,6 +624,10 @@ impl EditCommand {
             | EditCommand::MoveToLineStart { select, .. }
             | EditCommand::MoveToLineEnd { select, .. }
             | EditCommand::MoveToPosition { select, .. }
+            | EditCommand::MoveToLineNonBlankStart { select, .. }
+            | EditCommand::MoveLineUp { select, .. }
+            | EditCommand::MoveLineDown { select, .. }
+            | EditCommand::MoveToMatchingBracket { select, .. }
             | EditCommand::MoveLeft { select, .. }
             | EditCommand::MoveRight { select, .. }
             | EditCommand::MoveWordLeft { select, .. }
@@ -406,7 +644,16 @@ impl EditCommand {
                 EditType::MoveCursor { select: *select }
             }
 
-            EditCommand::SelectAll => EditType::MoveCursor { select: true },
+            EditCommand::SelectAll
+            | EditCommand::EnsureSelection
+            | EditCommand::SetMark
+            | EditCommand::ExchangePointAndMark
+            | EditCommand::SelectTextObject(_)
+            | EditCommand::CopySelectionToNextLine
+            | EditCommand::SelectRegexMatches(_)
+            | EditCommand::SelectToChar { .. }
+            | EditCommand::KeepPrimarySelection
+            | EditCommand::SetSelectionMode(_) => EditType::MoveCursor { select: true },
             // Text edits
             EditCommand::InsertChar(_)
             | EditCommand::Backspace
@@ -433,11 +680,14 @@ impl EditCommand {
             | EditCommand::CutWordRightToNext
             | EditCommand::CutBigWordRightToNext
             | EditCommand::PasteCutBufferBefore
+            | EditCommand::Yank
+            | EditCommand::YankPop
             | EditCommand::PasteCutBufferAfter
             | EditCommand::UppercaseWord
             | EditCommand::LowercaseWord
             | EditCommand::SwitchcaseChar
             | EditCommand::CapitalizeChar
+            | EditCommand::CapitalizeWord
             | EditCommand::SwapWords
             | EditCommand::SwapGraphemes
             | EditCommand::CutRightUntil(_)
@@ -445,14 +695,48 @@ impl EditCommand {
             | EditCommand::CutLeftUntil(_)
             | EditCommand::CutLeftBefore(_)
             | EditCommand::CutSelection
-            | EditCommand::Paste => EditType::EditText,
+            | EditCommand::Paste
+            | EditCommand::PasteAfterSelection
+            | EditCommand::PasteBeforeSelection
+            | EditCommand::ReplaceSelectionChars(_)
+            | EditCommand::SurroundSelection(_)
+            | EditCommand::ReplaceSurrounding(_, _)
+            | EditCommand::DeleteSurrounding(_)
+            | EditCommand::CutTextObject(_)
+            | EditCommand::KillRegion
+            | EditCommand::UppercaseSelection
+            | EditCommand::LowercaseSelection
+            | EditCommand::SwitchcaseSelection
+            | EditCommand::IndentLines
+            | EditCommand::DedentLines
+            | EditCommand::JoinLines
+            | EditCommand::IncrementNumber(_)
+            | EditCommand::DecrementNumber(_) => EditType::EditText,
 
             #[cfg(feature = "system_clipboard")] // Sadly cfg attributes in patterns don't work
             EditCommand::CutSelectionSystem | EditCommand::PasteSystem => EditType::EditText,
 
             EditCommand::Undo | EditCommand::Redo => EditType::UndoRedo,
 
-            EditCommand::CopySelection | EditCommand::ClearSelection => EditType::NoOp,
+            EditCommand::CopySelection
+            | EditCommand::SelectRegister(_)
+            | EditCommand::ClearSelection
+            | EditCommand::CopyCurrentLine
+            | EditCommand::CopyFromLineStart
+            | EditCommand::CopyToLineEnd
+            | EditCommand::CopyWordLeft
+            | EditCommand::CopyBigWordLeft
+            | EditCommand::CopyWordRight
+            | EditCommand::CopyBigWordRight
+            | EditCommand::CopyWordRightToNext
+            | EditCommand::CopyBigWordRightToNext
+            | EditCommand::CopyLeft
+            | EditCommand::CopyRight
+            | EditCommand::CopyRightUntil(_)
+            | EditCommand::CopyRightBefore(_)
+            | EditCommand::CopyLeftUntil(_)
+            | EditCommand::CopyLeftBefore(_)
+            | EditCommand::CopyTextObject(_) => EditType::NoOp,
             #[cfg(feature = "system_clipboard")]
             EditCommand::CopySelectionSystem => EditType::NoOp,
         }
@@ -473,6 +757,44 @@ pub enum EditType {
     NoOp,
 }
 
+/// A region of text around the cursor defined by its content
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct TextObject {
+    /// What the text object consists of
+    pub kind: TextObjectKind,
+
+    /// Include the surrounding whitespace or delimiters
+    pub around: bool,
+}
+
+/// The kinds of [`TextObject`]s
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TextObjectKind {
+    /// A word as defined by the unicode word boundaries
+    #[default]
+    Word,
+
+    /// A run of non-whitespace characters
+    BigWord,
+
+    /// Text enclosed by the bracket pair or quote the char belongs to
+    Pair(char),
+}
+
+/// How the text between the selection anchor and the cursor is selected
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    /// Every grapheme from the anchor to the cursor
+    #[default]
+    Char,
+
+    /// The whole lines containing the anchor, the cursor and everything in between
+    Line,
+
+    /// The rectangle with the anchor and the cursor in opposite corners
+    Block,
+}
+
 /// Every line change should come with an `UndoBehavior` tag, which can be used to
 /// calculate how the change should be reflected on the undo stack
 #[derive(Debug)]
@@ -566,6 +888,114 @@ pub enum HelixNormal {
 
     /// Move to the end of the next long word
     MoveNextLongWordEnd,
+
+    /// Delete the selection and store it in the cut buffer
+    Delete,
+
+    /// Delete the selection, store it in the cut buffer and activate insert mode
+    Change,
+
+    /// Copy the selection into the cut buffer
+    Yank,
+
+    /// Paste the cut buffer after the selection
+    PasteAfter,
+
+    /// Paste the cut buffer before the selection
+    PasteBefore,
+
+    /// Replace each character of the selection with the next typed character
+    Replace,
+
+    /// Replace the selection with the content of the cut buffer
+    ReplaceWithYanked,
+
+    /// Move to the start of the line
+    GotoLineStart,
+
+    /// Move to the end of the line
+    GotoLineEnd,
+
+    /// Move to the first non-whitespace character of the line
+    GotoFirstNonWhitespace,
+
+    /// Move to the last line of the buffer
+    GotoLastLine,
+
+    /// Move to the start of the buffer
+    GotoFileStart,
+
+    /// Move <n> lines up without leaving the buffer
+    MoveLineUp,
+
+    /// Move <n> lines down without leaving the buffer
+    MoveLineDown,
+
+    /// Align the view so the prompt is at the top of the screen
+    AlignViewTop,
+
+    /// Enter match mode, where the next key picks a match command
+    MatchMode,
+
+    /// Move to the matching bracket
+    MatchBracket,
+
+    /// Surround the selection with the pair of the next typed character
+    SurroundAdd,
+
+    /// Replace the surrounding pair of the next typed character with the one after it
+    SurroundReplace,
+
+    /// Delete the surrounding pair of the next typed character
+    SurroundDelete,
+
+    /// Select inside the text object of the next typed character
+    SelectTextObjectInside,
+
+    /// Select around the text object of the next typed character
+    SelectTextObjectAround,
+
+    /// Select until the <n>th next occurrence of the next typed character
+    FindNextChar,
+
+    /// Select until in front of the <n>th next occurrence of the next typed character
+    TillNextChar,
+
+    /// Select back to the <n>th previous occurrence of the next typed character
+    FindPrevChar,
+
+    /// Select back to behind the <n>th previous occurrence of the next typed character
+    TillPrevChar,
+
+    /// Repeat the last find/till motion
+    RepeatLastMotion,
+
+    /// Copy the selections to the next line
+    CopySelectionOnNextLine,
+
+    /// Select the matches of a typed regex inside the selections
+    SelectRegex,
+
+    /// Keep only the primary selection
+    KeepPrimarySelection,
+
+    /// Search forward through the buffer for a typed string
+    SearchForward,
+
+    /// Search backward through the buffer for a typed string
+    SearchBackward,
+
+    /// Select the next match of the buffer search
+    SearchNext,
+
+    /// Select the previous match of the buffer search
+    SearchPrevious,
+
+    /// Add <n> to the number under or after the cursor
+    Increment,
+
+    /// Subtract <n> from the number under or after the cursor
+    Decrement,
 }
 
 impl Display for HelixNormal {
@@ -583,6 +1013,42 @@ impl Display for HelixNormal {
             HelixNormal::MoveNextLongWordStart => write!(f, "MoveNextLongWordStart"),
             HelixNormal::MovePrevLongWordStart => write!(f, "MovePrevLongWordStart"),
             HelixNormal::MoveNextLongWordEnd => write!(f, "MoveNextLongWordEnd"),
+            HelixNormal::Delete => write!(f, "Delete"),
+            HelixNormal::Change => write!(f, "Change"),
+            HelixNormal::Yank => write!(f, "Yank"),
+            HelixNormal::PasteAfter => write!(f, "PasteAfter"),
+            HelixNormal::PasteBefore => write!(f, "PasteBefore"),
+            HelixNormal::Replace => write!(f, "Replace"),
+            HelixNormal::ReplaceWithYanked => write!(f, "ReplaceWithYanked"),
+            HelixNormal::GotoLineStart => write!(f, "GotoLineStart"),
+            HelixNormal::GotoLineEnd => write!(f, "GotoLineEnd"),
+            HelixNormal::GotoFirstNonWhitespace => write!(f, "GotoFirstNonWhitespace"),
+            HelixNormal::GotoLastLine => write!(f, "GotoLastLine"),
+            HelixNormal::GotoFileStart => write!(f, "GotoFileStart"),
+            HelixNormal::MoveLineUp => write!(f, "MoveLineUp"),
+            HelixNormal::MoveLineDown => write!(f, "MoveLineDown"),
+            HelixNormal::AlignViewTop => write!(f, "AlignViewTop"),
+            HelixNormal::MatchMode => write!(f, "MatchMode"),
+            HelixNormal::MatchBracket => write!(f, "MatchBracket"),
+            HelixNormal::SurroundAdd => write!(f, "SurroundAdd"),
+            HelixNormal::SurroundReplace => write!(f, "SurroundReplace"),
+            HelixNormal::SurroundDelete => write!(f, "SurroundDelete"),
+            HelixNormal::SelectTextObjectInside => write!(f, "SelectTextObjectInside"),
+            HelixNormal::SelectTextObjectAround => write!(f, "SelectTextObjectAround"),
+            HelixNormal::FindNextChar => write!(f, "FindNextChar"),
+            HelixNormal::TillNextChar => write!(f, "TillNextChar"),
+            HelixNormal::FindPrevChar => write!(f, "FindPrevChar"),
+            HelixNormal::TillPrevChar => write!(f, "TillPrevChar"),
+            HelixNormal::RepeatLastMotion => write!(f, "RepeatLastMotion"),
+            HelixNormal::CopySelectionOnNextLine => write!(f, "CopySelectionOnNextLine"),
+            HelixNormal::SelectRegex => write!(f, "SelectRegex"),
+            HelixNormal::KeepPrimarySelection => write!(f, "KeepPrimarySelection"),
+            HelixNormal::SearchForward => write!(f, "SearchForward"),
+            HelixNormal::SearchBackward => write!(f, "SearchBackward"),
+            HelixNormal::SearchNext => write!(f, "SearchNext"),
+            HelixNormal::SearchPrevious => write!(f, "SearchPrevious"),
+            HelixNormal::Increment => write!(f, "Increment"),
+            HelixNormal::Decrement => write!(f, "Decrement"),
         }
     }
 }
@@ -608,6 +1074,26 @@ impl Display for HelixEvent {
     }
 }
 
+/// Emacs mode specific events.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub enum EmacsEvent {
+    /// Start a numeric argument of four for the next command, or multiply it by four (`C-u`)
+    #[default]
+    UniversalArgument,
+
+    /// Append the digit to the numeric argument for the next command (`M-<digit>`)
+    DigitArgument(u8),
+}
+
+impl Display for EmacsEvent {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            EmacsEvent::UniversalArgument => write!(f, "UniversalArgument"),
+            EmacsEvent::DigitArgument(_) => write!(f, "DigitArgument Value: <int>"),
+        }
+    }
+}
+
 /// Reedline supported actions.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, EnumIter)]
 pub enum ReedlineEvent {
@@ -691,6 +1177,34 @@ pub enum ReedlineEvent {
     /// Search the history for a string
     SearchHistory,
 
+    /// Insert the last argument of the previous history entry
+    ///
+    /// Repeated right away, the inserted argument is replaced by the one of the entry before.
+    YankLastArg,
+
+    /// Insert the argument at the index of the previous history entry, `0` being the command
+    ///
+    /// Repeated right away, the inserted argument is replaced by the one of the entry before.
+    YankNthArg(usize),
+
+    /// Search the buffer for a typed string, jumping to the matches while typing
+    SearchBuffer {
+        /// Search backwards from the cursor
+        backward: bool,
+        /// Select the match instead of only moving the cursor to its start
+        select: bool,
+    },
+
+    /// Jump to the next match of the buffer search, in the direction of the search
+    SearchBufferNext,
+
+    /// Jump to the previous match of the buffer search, against the direction of the search
+    SearchBufferPrevious,
+
+    /// Open the Vi ex command line to type a command like `:cd ..`, or `:s/foo/bar/g` with the
+    /// `regex` feature
+    ExCommandLine,
+
     /// In vi mode multiple reedline events can be chained while parsing the
     /// command or movement characters
     Multiple(Vec<ReedlineEvent>),
@@ -728,11 +1242,19 @@ pub enum ReedlineEvent {
     /// Way to bind the execution of a whole command (directly returning from [`crate::Reedline::read_line()`]) to a keybinding
     ExecuteHostCommand(String),
 
+    /// Run the [`CustomEvent`](crate::CustomEvent) registered under the name with
+    /// [`Reedline::with_custom_event`](crate::Reedline::with_custom_event), staying in
+    /// [`crate::Reedline::read_line()`]
+    Custom(String),
+
     /// Open text editor
     OpenEditor,
 
     /// Helix mode specific events
     Helix(HelixEvent),
+
+    /// Emacs mode specific events
+    Emacs(EmacsEvent),
 }
 
 impl Display for ReedlineEvent {
@@ -763,6 +1285,14 @@ impl Display for ReedlineEvent {
             ReedlineEvent::Left => write!(f, "Left"),
             ReedlineEvent::NextHistory => write!(f, "NextHistory"),
             ReedlineEvent::SearchHistory => write!(f, "SearchHistory"),
+            ReedlineEvent::YankLastArg => write!(f, "YankLastArg"),
+            ReedlineEvent::YankNthArg(_) => write!(f, "YankNthArg <int>"),
+            ReedlineEvent::SearchBuffer { .. } => {
+                write!(f, "SearchBuffer Optional[backward: <bool>, select: <bool>]")
+            }
+            ReedlineEvent::SearchBufferNext => write!(f, "SearchBufferNext"),
+            ReedlineEvent::SearchBufferPrevious => write!(f, "SearchBufferPrevious"),
+            ReedlineEvent::ExCommandLine => write!(f, "ExCommandLine"),
             ReedlineEvent::Multiple(_) => write!(f, "Multiple[ {{ ReedLineEvents, }} ]"),
             ReedlineEvent::UntilFound(_) => write!(f, "UntilFound [ {{ ReedLineEvents, }} ]"),
             ReedlineEvent::Menu(_) => write!(f, "Menu Name: <string>"),
@@ -775,8 +1305,10 @@ impl Display for ReedlineEvent {
             ReedlineEvent::MenuPageNext => write!(f, "MenuPageNext"),
             ReedlineEvent::MenuPagePrevious => write!(f, "MenuPagePrevious"),
             ReedlineEvent::ExecuteHostCommand(_) => write!(f, "ExecuteHostCommand"),
+            ReedlineEvent::Custom(_) => write!(f, "Custom <string>"),
             ReedlineEvent::OpenEditor => write!(f, "OpenEditor"),
             ReedlineEvent::Helix(helix_event) => write!(f, "HelixEvent({helix_event})"),
+            ReedlineEvent::Emacs(emacs_event) => write!(f, "EmacsEvent({emacs_event})"),
         }
     }
 }
@@ -792,6 +1324,7 @@ pub(crate) enum EventStatus {
 /// It ensures that the given event doesn't contain [KeyEventKind::Release]
 /// (which is rejected) or [KeyEventKind::Repeat] (which is converted to
 /// [KeyEventKind::Press]).
+#[derive(Clone)]
 pub struct ReedlineRawEvent(Event);
 
 impl TryFrom<Event> for ReedlineRawEvent {
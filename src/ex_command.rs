@@ -0,0 +1,313 @@
+use crate::{core_editor::Editor, Signal};
+#[cfg(feature = "regex")]
+use crate::{LineBuffer, UndoBehavior};
+#[cfg(feature = "regex")]
+use regex::RegexBuilder;
+
+/// A command that can be run from the Vi ex command line opened with `:`
+///
+/// The command receives everything typed after its name, e.g. `..` for `:cd ..`.
+/// Returning a [`Signal`] hands control back to the host in the same way as
+/// [`ReedlineEvent::ExecuteHostCommand`](crate::ReedlineEvent::ExecuteHostCommand),
+/// the buffer is kept for the next call to `read_line`.
+///
+/// Closures taking the arguments and the [`Editor`] implement this trait.
+///
+/// ## Example
+/// ```rust
+/// use reedline::{Editor, Reedline, UndoBehavior};
+///
+/// let line_editor = Reedline::create().with_ex_command(
+///     "upper",
+///     Box::new(|_args: &str, editor: &mut Editor| {
+///         let upper = editor.get_buffer().to_uppercase();
+///         editor.edit_buffer(
+///             |buffer| buffer.set_buffer(upper),
+///             UndoBehavior::CreateUndoPoint,
+///         );
+///         None
+///     }),
+/// );
+/// ```
+pub trait ExCommand: Send {
+    /// Run the command with the `args` following its name
+    fn run(&mut self, args: &str, editor: &mut Editor) -> Option<Signal>;
+}
+
+impl<F> ExCommand for F
+where
+    F: FnMut(&str, &mut Editor) -> Option<Signal> + Send,
+{
+    fn run(&mut self, args: &str, editor: &mut Editor) -> Option<Signal> {
+        self(args, editor)
+    }
+}
+
+/// A line typed on the ex command line
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ExLine<'line> {
+    /// `:s/pattern/replacement/flags`, or `:%s` for the whole buffer
+    #[cfg(feature = "regex")]
+    Substitute(Substitution),
+    /// Any other command, looked up by its name among the built-in and host commands
+    Command { name: &'line str, args: &'line str },
+}
+
+/// Parse the text typed after `:`
+///
+/// Returns `None` for an empty line or a malformed substitution.
+pub(crate) fn parse_ex_line(line: &str) -> Option<ExLine<'_>> {
+    let line = line.trim_start_matches([':', ' ']);
+    let (whole_buffer, line) = match line.strip_prefix('%') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    #[cfg(feature = "regex")]
+    let mut chars = line.chars();
+    #[cfg(feature = "regex")]
+    if chars.next() == Some('s') {
+        if let Some(delimiter) = chars.next() {
+            if !delimiter.is_alphanumeric() && !delimiter.is_whitespace() && delimiter != '\\' {
+                return Substitution::parse(chars.as_str(), delimiter, whole_buffer)
+                    .map(ExLine::Substitute);
+            }
+        }
+    }
+
+    // Only the substitution supports a range
+    if whole_buffer {
+        return None;
+    }
+    let (name, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    (!name.is_empty()).then(|| ExLine::Command {
+        name,
+        args: args.trim(),
+    })
+}
+
+#[cfg(feature = "regex")]
+/// Replacement of the matches of a regex, on the current line or over the whole buffer
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Substitution {
+    pattern: String,
+    /// Replacement in the syntax of the `regex` crate
+    replacement: String,
+    global: bool,
+    ignore_case: bool,
+    whole_buffer: bool,
+}
+
+#[cfg(feature = "regex")]
+impl Substitution {
+    /// Parse the part following `s` and the delimiter, e.g. `foo/bar/g` in `s/foo/bar/g`
+    fn parse(input: &str, delimiter: char, whole_buffer: bool) -> Option<Self> {
+        let mut parts = split_unescaped(input, delimiter).into_iter();
+        let pattern = parts.next().unwrap_or_default();
+        let replacement = parts.next().unwrap_or_default();
+        let flags = parts.next().unwrap_or_default();
+        if pattern.is_empty() || parts.next().is_some() {
+            return None;
+        }
+
+        let mut substitution = Substitution {
+            pattern,
+            replacement: expand_replacement(&replacement),
+            global: false,
+            ignore_case: false,
+            whole_buffer,
+        };
+        for flag in flags.trim_end().chars() {
+            match flag {
+                'g' => substitution.global = true,
+                'i' => substitution.ignore_case = true,
+                'I' => substitution.ignore_case = false,
+                _ => return None,
+            }
+        }
+        Some(substitution)
+    }
+
+    /// Apply the substitution to the buffer of the `editor`
+    ///
+    /// The cursor moves to the start of the last line that changed.
+    /// An invalid pattern or a pattern without matches leaves the buffer untouched.
+    pub(crate) fn run(&self, editor: &mut Editor) {
+        let line_buffer = editor.line_buffer();
+        let range = if self.whole_buffer {
+            0..line_buffer.get_buffer().len()
+        } else {
+            line_buffer.current_line_range()
+        };
+        if let Some((buffer, cursor)) = self.apply(line_buffer.get_buffer(), range) {
+            let mut line_buffer = LineBuffer::from(buffer.as_str());
+            line_buffer.set_insertion_point(cursor);
+            editor.set_line_buffer(line_buffer, UndoBehavior::CreateUndoPoint);
+        }
+    }
+
+    /// Substitute the matches inside of the lines covered by `range`
+    ///
+    /// Returns the new buffer and the start of the last changed line.
+    fn apply(&self, buffer: &str, range: std::ops::Range<usize>) -> Option<(String, usize)> {
+        let regex = RegexBuilder::new(&self.pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .ok()?;
+        let limit = if self.global { 0 } else { 1 };
+
+        let mut result = buffer[..range.start].to_string();
+        let mut last_changed_line = None;
+        for line in buffer[range.clone()].split_inclusive('\n') {
+            let (content, newline) = match line.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (line, ""),
+            };
+            if regex.is_match(content) {
+                last_changed_line = Some(result.len());
+            }
+            result.push_str(&regex.replacen(content, limit, self.replacement.as_str()));
+            result.push_str(newline);
+        }
+        result.push_str(&buffer[range.end..]);
+
+        last_changed_line.map(|cursor| (result, cursor))
+    }
+}
+
+#[cfg(feature = "regex")]
+/// Split `input` at every `delimiter` that isn't preceded by a backslash
+///
+/// Escaped delimiters lose their backslash, other escapes are kept for the regex.
+fn split_unescaped(input: &str, delimiter: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        let current = parts.last_mut().expect("there is always a part");
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) if escaped == delimiter => current.push(escaped),
+                Some(escaped) => {
+                    current.push('\\');
+                    current.push(escaped);
+                }
+                None => current.push('\\'),
+            },
+            c if c == delimiter => parts.push(String::new()),
+            c => current.push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(feature = "regex")]
+/// Translate a Vi replacement string into the syntax of the `regex` crate
+///
+/// `&` and `\0` insert the whole match, `\1` to `\9` the capture groups.
+fn expand_replacement(replacement: &str) -> String {
+    let mut expanded = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '&' => expanded.push_str("${0}"),
+            '$' => expanded.push_str("$$"),
+            '\\' => match chars.next() {
+                Some(group @ '0'..='9') => {
+                    expanded.push_str("${");
+                    expanded.push(group);
+                    expanded.push('}');
+                }
+                Some('n') => expanded.push('\n'),
+                Some('t') => expanded.push('\t'),
+                Some('$') => expanded.push_str("$$"),
+                Some(escaped) => expanded.push(escaped),
+                None => expanded.push('\\'),
+            },
+            c => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", None)]
+    #[case("w", Some(ExLine::Command { name: "w", args: "" }))]
+    #[case(" cd  ../foo ", Some(ExLine::Command { name: "cd", args: "../foo" }))]
+    #[case("%w", None)]
+    #[case("s", Some(ExLine::Command { name: "s", args: "" }))]
+    fn test_parse_ex_line(#[case] line: &str, #[case] expected: Option<ExLine>) {
+        assert_eq!(parse_ex_line(line), expected);
+    }
+
+    #[rstest]
+    #[case("s/")]
+    #[case("s/a/b/x")]
+    #[case("s/a/b/g/")]
+    #[cfg(feature = "regex")]
+    fn test_parse_malformed_substitution(#[case] line: &str) {
+        assert_eq!(parse_ex_line(line), None);
+    }
+
+    #[rstest]
+    #[cfg(feature = "regex")]
+    #[case("s/foo/bar", "foo", "bar", false, false)]
+    #[case("%s/foo/bar/g", "foo", "bar", true, true)]
+    #[case("s#a/b#c\\#d#gi", "a/b", "c#d", true, false)]
+    #[case("s/a\\/b/\\1&$/", "a/b", "${1}${0}$$", false, false)]
+    #[case("s/foo", "foo", "", false, false)]
+    fn test_parse_substitution(
+        #[case] line: &str,
+        #[case] pattern: &str,
+        #[case] replacement: &str,
+        #[case] global: bool,
+        #[case] whole_buffer: bool,
+    ) {
+        let Some(ExLine::Substitute(substitution)) = parse_ex_line(line) else {
+            panic!("{line} is not a substitution");
+        };
+
+        assert_eq!(substitution.pattern, pattern);
+        assert_eq!(substitution.replacement, replacement);
+        assert_eq!(substitution.global, global);
+        assert_eq!(substitution.whole_buffer, whole_buffer);
+    }
+
+    #[rstest]
+    #[cfg(feature = "regex")]
+    #[case("s/o/0/", "foo\nboo", 5, Some(("foo\nb0o", 4)))]
+    #[case("s/o/0/g", "foo\nboo", 0, Some(("f00\nboo", 0)))]
+    #[case("%s/o/0/", "foo\nboo\nbar", 0, Some(("f0o\nb0o\nbar", 4)))]
+    #[case("%s/(b)(a)/\\2\\1/", "bar\nbaz", 0, Some(("abr\nabz", 4)))]
+    #[case("s/O/0/i", "foo", 0, Some(("f0o", 0)))]
+    #[case("s/$/;/", "foo\nbar", 0, Some(("foo;\nbar", 0)))]
+    #[case("s/x/y/", "foo", 0, None)]
+    #[case("s/(/y/", "foo(", 0, None)]
+    fn test_substitute(
+        #[case] line: &str,
+        #[case] buffer: &str,
+        #[case] cursor: usize,
+        #[case] expected: Option<(&str, usize)>,
+    ) {
+        let Some(ExLine::Substitute(substitution)) = parse_ex_line(line) else {
+            panic!("{line} is not a substitution");
+        };
+        let mut line_buffer = LineBuffer::from(buffer);
+        line_buffer.set_insertion_point(cursor);
+        let range = if substitution.whole_buffer {
+            0..buffer.len()
+        } else {
+            line_buffer.current_line_range()
+        };
+
+        assert_eq!(
+            substitution.apply(buffer, range),
+            expected.map(|(buffer, cursor)| (buffer.to_string(), cursor))
+        );
+    }
+}
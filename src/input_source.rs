@@ -0,0 +1,90 @@
+use {
+    crossterm::event::{self, Event},
+    std::{io::Result, time::Duration},
+};
+
+/// Source of the terminal events read by [`Reedline::read_line`](crate::Reedline::read_line)
+///
+/// By default the events are read from the terminal through crossterm, see
+/// [`CrosstermInput`]. Another source can e.g. replay recorded keystrokes or feed
+/// them from a test.
+///
+/// ## Example
+/// ```rust
+/// use reedline::{DefaultPrompt, InputSource, Reedline, Signal};
+/// use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+/// use std::{collections::VecDeque, io, time::Duration};
+///
+/// struct Keystrokes(VecDeque<Event>);
+///
+/// impl InputSource for Keystrokes {
+///     fn poll(&mut self, _timeout: Duration) -> io::Result<bool> {
+///         Ok(!self.0.is_empty())
+///     }
+///
+///     fn read(&mut self) -> io::Result<Event> {
+///         self.0
+///             .pop_front()
+///             .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "out of keystrokes"))
+///     }
+///
+///     fn is_closed(&self) -> bool {
+///         self.0.is_empty()
+///     }
+///
+///     fn is_terminal(&self) -> bool {
+///         false
+///     }
+/// }
+///
+/// let keystrokes = [KeyCode::Char('l'), KeyCode::Char('s'), KeyCode::Enter]
+///     .into_iter()
+///     .map(|code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+///     .collect();
+/// let mut line_editor =
+///     Reedline::create().with_input_source(Box::new(Keystrokes(keystrokes)));
+///
+/// let signal = line_editor.read_line(&DefaultPrompt::default()).unwrap();
+/// assert!(matches!(signal, Signal::Success(line) if line == "ls"));
+///
+/// // Without keys left, the line ends with the error of `read`
+/// let error = line_editor.read_line(&DefaultPrompt::default()).unwrap_err();
+/// assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+/// ```
+pub trait InputSource: Send {
+    /// Wait up to `timeout` for an event, returns whether one can be read right away
+    fn poll(&mut self, timeout: Duration) -> Result<bool>;
+
+    /// Read the next event, blocking until there is one
+    fn read(&mut self) -> Result<Event>;
+
+    /// Whether the source ran out of events
+    ///
+    /// A closed source isn't polled anymore, [`InputSource::read`] gets called right away
+    /// and its error ends the line.
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    /// Whether the events come from the terminal the line is painted on
+    ///
+    /// Only then the terminal gets switched to raw mode and asked for the position of
+    /// the cursor.
+    fn is_terminal(&self) -> bool {
+        true
+    }
+}
+
+/// Reads the events from the terminal with crossterm
+#[derive(Default)]
+pub struct CrosstermInput;
+
+impl InputSource for CrosstermInput {
+    fn poll(&mut self, timeout: Duration) -> Result<bool> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> Result<Event> {
+        event::read()
+    }
+}
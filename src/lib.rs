@@ -45,13 +45,16 @@
 //!
 //! use {
 //!   crossterm::event::{KeyCode, KeyModifiers},
-//!   reedline::{default_emacs_keybindings, EditCommand, Reedline, Emacs, ReedlineEvent},
+//!   reedline::{default_emacs_keybindings, EditCommand, KeyCombination, Reedline, Emacs, ReedlineEvent},
 //! };
 //!
 //! let mut keybindings = default_emacs_keybindings();
 //! keybindings.add_binding(
-//!     KeyModifiers::ALT,
-//!     KeyCode::Char('m'),
+//!     KeyCombination {
+//!         modifier: KeyModifiers::ALT,
+//!         key_code: KeyCode::Char('m'),
+//!     },
+//!     vec![],
 //!     ReedlineEvent::Edit(vec![EditCommand::BackspaceWord]),
 //! );
 //! let edit_mode = Box::new(Emacs::new(keybindings));
@@ -96,7 +99,7 @@
 //! ```rust
 //! // Create a reedline object with tab completions support
 //!
-//! use reedline::{default_emacs_keybindings, ColumnarMenu, DefaultCompleter, Emacs, KeyCode, KeyModifiers, Reedline, ReedlineEvent, ReedlineMenu, MenuBuilder};
+//! use reedline::{default_emacs_keybindings, ColumnarMenu, DefaultCompleter, Emacs, KeyCode, KeyCombination, KeyModifiers, Reedline, ReedlineEvent, ReedlineMenu, MenuBuilder};
 //!
 //! let commands = vec![
 //!   "test".into(),
@@ -110,8 +113,11 @@
 //! // Set up the required keybindings
 //! let mut keybindings = default_emacs_keybindings();
 //! keybindings.add_binding(
-//!     KeyModifiers::NONE,
-//!     KeyCode::Tab,
+//!     KeyCombination {
+//!         modifier: KeyModifiers::NONE,
+//!         key_code: KeyCode::Tab,
+//!     },
+//!     vec![],
 //!     ReedlineEvent::UntilFound(vec![
 //!         ReedlineEvent::Menu("completion_menu".to_string()),
 //!         ReedlineEvent::MenuNext,
@@ -182,6 +188,7 @@
 //! - `sqlite`: Provides the `SqliteBackedHistory` to store richer information in the history. Statically links the required sqlite version.
 //! - `sqlite-dynlib`: Alternative to the feature `sqlite`. Will not statically link. Requires `sqlite >= 3.38` to link dynamically!
 //! - `external_printer`: **Experimental:** Thread-safe `ExternalPrinter` handle to print lines from concurrently running threads.
+//! - `regex`: Match the patterns of the Helix `s` selection as regular expressions instead of literal text and enable the Vi ex substitution `:s/pattern/replacement/flags`.
 //!
 //! ## Are we prompt yet? (Development status)
 //!
@@ -228,10 +235,12 @@
 mod core_editor;
 pub use core_editor::Editor;
 pub use core_editor::LineBuffer;
+pub use core_editor::Registers;
 
 mod enums;
 pub use enums::{
-    EditCommand, HelixEvent, HelixNormal, ReedlineEvent, ReedlineRawEvent, Signal, UndoBehavior,
+    EditCommand, EmacsEvent, HelixEvent, HelixNormal, ReedlineEvent, ReedlineRawEvent,
+    SelectionMode, Signal, TextObject, TextObjectKind, UndoBehavior,
 };
 
 mod painting;
@@ -254,15 +263,17 @@ pub use history::{
 
 mod prompt;
 pub use prompt::{
-    DefaultPrompt, DefaultPromptSegment, Prompt, PromptEditMode, PromptHelixMode,
-    PromptHistorySearch, PromptHistorySearchStatus, PromptViMode,
+    DefaultPrompt, DefaultPromptSegment, Prompt, PromptBufferSearch, PromptEditMode,
+    PromptHelixMode, PromptHistorySearch, PromptHistorySearchStatus, PromptViMode,
 };
 
 mod edit_mode;
 pub use edit_mode::{
-    default_emacs_keybindings, default_helix_insert_keybindings, default_helix_normal_keybindings,
-    default_vi_insert_keybindings, default_vi_normal_keybindings, CursorConfig, EditMode, Emacs,
-    Helix, KeyCombination, Keybindings, Vi,
+    default_emacs_keybindings, default_helix_insert_keybindings, default_helix_match_keybindings,
+    default_helix_normal_keybindings, default_vi_insert_keybindings, default_vi_normal_keybindings,
+    CursorConfig, EditMode, Emacs, Helix, Inputrc, InputrcEditingMode, InputrcIssue,
+    KeyCombination, KeyNotationError, KeySequence, KeybindingConflict, KeybindingEntry,
+    KeybindingFile, Keybindings, Vi,
 };
 
 mod highlighter;
@@ -278,6 +289,15 @@ pub use hinter::{DefaultHinter, Hinter};
 mod validator;
 pub use validator::{DefaultValidator, ValidationResult, Validator};
 
+mod ex_command;
+pub use ex_command::ExCommand;
+
+mod custom_event;
+pub use custom_event::{CustomEvent, CustomEventResult};
+
+mod input_source;
+pub use input_source::{CrosstermInput, InputSource};
+
 mod menu;
 pub use menu::{
     menu_functions, ColumnarMenu, DescriptionMenu, DescriptionMode, IdeMenu, ListMenu, Menu,
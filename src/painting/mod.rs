@@ -2,8 +2,10 @@ mod painter;
 mod prompt_lines;
 mod styled_text;
 mod utils;
+mod which_key;
 
 pub use painter::{Painter, PainterSuspendedState};
-pub(crate) use prompt_lines::PromptLines;
+pub(crate) use prompt_lines::{PromptLines, PromptSearch};
 pub use styled_text::StyledText;
 pub(crate) use utils::estimate_single_line_wraps;
+pub(crate) use which_key::which_key_string;
@@ -138,13 +138,14 @@ impl Painter {
     }
 
     /// Sets the prompt origin position and screen size for a new line editor
-    /// invocation
+    /// invocation, given the current `cursor_position`
     ///
     /// Not to be used for resizes during a running line editor, use
     /// [`Painter::handle_resize()`] instead
     pub(crate) fn initialize_prompt_position(
         &mut self,
         suspended_state: Option<&PainterSuspendedState>,
+        cursor_position: (u16, u16),
     ) -> Result<()> {
         // Update the terminal size
         self.terminal_size = {
@@ -157,7 +158,7 @@ impl Painter {
                 size
             }
         };
-        let prompt_selector = select_prompt_row(suspended_state, cursor::position()?);
+        let prompt_selector = select_prompt_row(suspended_state, cursor_position);
         self.prompt_start_row = match prompt_selector {
             PromptRowSelector::UseExistingPrompt { start_row } => start_row,
             PromptRowSelector::MakeNewPrompt { new_row } => {
@@ -257,12 +258,26 @@ impl Painter {
         self.stdout.queue(RestorePosition)?;
 
         if let Some(shapes) = cursor_config {
+            let prompt_mode = match prompt_mode {
+                PromptEditMode::Vi(PromptViMode::Recording(_, vi_mode)) => {
+                    PromptEditMode::Vi(*vi_mode)
+                }
+                prompt_mode => prompt_mode,
+            };
             let shape = match &prompt_mode {
-                PromptEditMode::Emacs => shapes.emacs,
+                PromptEditMode::Emacs | PromptEditMode::EmacsArgument(_) => shapes.emacs,
                 PromptEditMode::Vi(PromptViMode::Insert) => shapes.vi_insert,
                 PromptEditMode::Vi(PromptViMode::Normal) => shapes.vi_normal,
+                PromptEditMode::Vi(PromptViMode::VisualLine) => {
+                    shapes.vi_visual_line.or(shapes.vi_normal)
+                }
+                PromptEditMode::Vi(PromptViMode::VisualBlock) => {
+                    shapes.vi_visual_block.or(shapes.vi_normal)
+                }
                 PromptEditMode::Helix(PromptHelixMode::Insert) => shapes.helix_insert,
-                PromptEditMode::Helix(PromptHelixMode::Normal) => shapes.helix_normal,
+                PromptEditMode::Helix(PromptHelixMode::Normal | PromptHelixMode::Pending(_)) => {
+                    shapes.helix_normal
+                }
                 _ => None,
             };
             if let Some(shape) = shape {
@@ -528,7 +543,8 @@ impl Painter {
             .queue(Clear(ClearType::All))?
             .queue(MoveTo(0, 0))?
             .flush()?;
-        self.initialize_prompt_position(None)
+        // The cursor was just moved to the top left
+        self.initialize_prompt_position(None, (0, 0))
     }
 
     pub(crate) fn clear_scrollback(&mut self) -> Result<()> {
@@ -537,7 +553,8 @@ impl Painter {
             .queue(Clear(ClearType::Purge))?
             .queue(MoveTo(0, 0))?
             .flush()?;
-        self.initialize_prompt_position(None)
+        // The cursor was just moved to the top left
+        self.initialize_prompt_position(None, (0, 0))
     }
 
     // The prompt is moved to the end of the buffer after the event was handled
@@ -2,10 +2,18 @@ use super::utils::{coerce_crlf, estimate_required_lines, line_width};
 use crate::{
     menu::{Menu, ReedlineMenu},
     prompt::PromptEditMode,
-    Prompt, PromptHistorySearch,
+    Prompt, PromptBufferSearch, PromptHistorySearch,
 };
 use std::borrow::Cow;
 
+/// A search whose indicator replaces the regular prompt indicator
+pub(crate) enum PromptSearch {
+    History(PromptHistorySearch),
+    Buffer(PromptBufferSearch),
+    /// Not a search, the Vi ex command line also replaces the prompt indicator
+    ExCommand,
+}
+
 /// Aggregate of prompt and input string used by `Painter`
 #[derive(Debug)]
 pub(crate) struct PromptLines<'prompt> {
@@ -25,7 +33,7 @@ impl<'prompt> PromptLines<'prompt> {
     pub fn new(
         prompt: &'prompt dyn Prompt,
         prompt_mode: PromptEditMode,
-        history_indicator: Option<PromptHistorySearch>,
+        search_indicator: Option<PromptSearch>,
         before_cursor: &'prompt str,
         after_cursor: &'prompt str,
         hint: &'prompt str,
@@ -33,8 +41,14 @@ impl<'prompt> PromptLines<'prompt> {
         let prompt_str_left = prompt.render_prompt_left();
         let prompt_str_right = prompt.render_prompt_right();
 
-        let prompt_indicator = match history_indicator {
-            Some(prompt_search) => prompt.render_prompt_history_search_indicator(prompt_search),
+        let prompt_indicator = match search_indicator {
+            Some(PromptSearch::History(prompt_search)) => {
+                prompt.render_prompt_history_search_indicator(prompt_search)
+            }
+            Some(PromptSearch::Buffer(prompt_search)) => {
+                prompt.render_prompt_buffer_search_indicator(prompt_search)
+            }
+            Some(PromptSearch::ExCommand) => prompt.render_prompt_ex_command_indicator(),
             None => prompt.render_prompt_indicator(prompt_mode),
         };
 
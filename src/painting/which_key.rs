@@ -0,0 +1,135 @@
+use {
+    crate::{enums::ReedlineEvent, KeyCombination},
+    nu_ansi_term::{Color, Style},
+};
+
+/// Space between the columns of the overlay
+const COLUMN_PADDING: usize = 2;
+
+/// Lay out the keys that can continue a pending key sequence in columns
+///
+/// Every entry shows the key next to the event it triggers, or `+prefix` if it starts
+/// a longer sequence. Each row starts with a newline, so the overlay is drawn below the
+/// buffer it gets appended to.
+pub(crate) fn which_key_string(
+    continuations: &[(KeyCombination, Option<ReedlineEvent>)],
+    screen_width: u16,
+    use_ansi_coloring: bool,
+) -> String {
+    let entries: Vec<(String, String)> = continuations
+        .iter()
+        .map(|(key, event)| {
+            let description = match event {
+                Some(event) => format!("{event:?}"),
+                None => "+prefix".to_string(),
+            };
+            (key.to_string(), description)
+        })
+        .collect();
+    let Some(key_width) = entries.iter().map(|(key, _)| key.chars().count()).max() else {
+        return String::new();
+    };
+
+    // The descriptions are cut so that at least one column fits on the screen
+    let description_width = entries
+        .iter()
+        .map(|(_, description)| description.chars().count())
+        .max()
+        .unwrap_or_default()
+        .min(usize::from(screen_width).saturating_sub(key_width + 1));
+    let column_width = key_width + 1 + description_width;
+    let columns =
+        ((usize::from(screen_width) + COLUMN_PADDING) / (column_width + COLUMN_PADDING)).max(1);
+
+    let key_style = Style::new().fg(Color::Green).bold();
+    let mut overlay = String::new();
+    for row in entries.chunks(columns) {
+        overlay.push('\n');
+        for (column, (key, description)) in row.iter().enumerate() {
+            let description = truncate(description, description_width);
+            let key = format!("{key:key_width$}");
+            if use_ansi_coloring {
+                overlay.push_str(&key_style.paint(key).to_string());
+            } else {
+                overlay.push_str(&key);
+            }
+            overlay.push(' ');
+            if column + 1 < row.len() {
+                overlay.push_str(&format!(
+                    "{description:width$}",
+                    width = description_width + COLUMN_PADDING
+                ));
+            } else {
+                overlay.push_str(&description);
+            }
+        }
+    }
+    overlay
+}
+
+/// Cut `text` to `width` characters, marking the cut with an ellipsis
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::EditCommand;
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use pretty_assertions::assert_eq;
+
+    fn continuations() -> Vec<(KeyCombination, Option<ReedlineEvent>)> {
+        vec![
+            (
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('e'),
+                },
+                Some(ReedlineEvent::OpenEditor),
+            ),
+            (
+                KeyCombination {
+                    modifier: KeyModifiers::CONTROL,
+                    key_code: KeyCode::Char('u'),
+                },
+                Some(ReedlineEvent::Edit(vec![EditCommand::UppercaseSelection])),
+            ),
+            (
+                KeyCombination {
+                    modifier: KeyModifiers::NONE,
+                    key_code: KeyCode::Char('r'),
+                },
+                None,
+            ),
+        ]
+    }
+
+    #[test]
+    fn entries_are_laid_out_in_columns() {
+        assert_eq!(
+            which_key_string(&continuations(), 80, false),
+            "\nC-e OpenEditor                  C-u Edit([UppercaseSelection])\
+             \nr   +prefix"
+        );
+    }
+
+    #[test]
+    fn descriptions_are_cut_to_the_screen_width() {
+        assert_eq!(
+            which_key_string(&continuations(), 16, false),
+            "\nC-e OpenEditor\nC-u Edit([Upper…\nr   +prefix"
+        );
+    }
+
+    #[test]
+    fn nothing_is_shown_without_continuations() {
+        assert_eq!(which_key_string(&[], 80, false), "");
+    }
+}
@@ -42,12 +42,42 @@ impl PromptHistorySearch {
     }
 }
 
+/// A representation of the search inside the buffer
+pub struct PromptBufferSearch {
+    /// The status of the search
+    pub status: PromptHistorySearchStatus,
+    /// Whether the search goes backwards through the buffer
+    pub backward: bool,
+    /// The search term used during the search
+    pub term: String,
+}
+
+impl PromptBufferSearch {
+    /// A constructor to create a buffer search
+    pub const fn new(
+        status: PromptHistorySearchStatus,
+        backward: bool,
+        search_term: String,
+    ) -> Self {
+        PromptBufferSearch {
+            status,
+            backward,
+            term: search_term,
+        }
+    }
+}
+
 /// Modes that the prompt can be in
-#[derive(Serialize, Deserialize, Clone, Debug, EnumIter)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, EnumIter)]
 pub enum PromptEditMode {
     /// Emacs normal mode
     Emacs,
 
+    /// Emacs mode while a numeric argument is typed, holding its value
+    ///
+    /// Exhaustive matches on [`PromptEditMode`] need a new arm for this variant.
+    EmacsArgument(usize),
+
     /// A vi-specific mode
     Vi(PromptViMode),
 
@@ -59,7 +89,7 @@ pub enum PromptEditMode {
 }
 
 /// The vi-specific modes that the prompt can be in
-#[derive(Serialize, Deserialize, Clone, Debug, EnumIter, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, EnumIter, Default)]
 pub enum PromptViMode {
     /// The default mode
     #[default]
@@ -67,10 +97,19 @@ pub enum PromptViMode {
 
     /// Insertion mode
     Insert,
+
+    /// Visual mode selecting whole lines
+    VisualLine,
+
+    /// Visual mode selecting a rectangular block
+    VisualBlock,
+
+    /// Recording a macro into the register, in the wrapped mode
+    Recording(char, Box<PromptViMode>),
 }
 
 /// The helix-specific modes that the prompt can be in
-#[derive(Serialize, Deserialize, Clone, Debug, EnumIter, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, EnumIter, Default)]
 pub enum PromptHelixMode {
     /// The default mode
     #[default]
@@ -78,12 +117,15 @@ pub enum PromptHelixMode {
 
     /// Insertion mode
     Insert,
+
+    /// Normal mode while a key sequence is in progress, holding the keys typed so far
+    Pending(String),
 }
 
 impl Display for PromptEditMode {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
-            PromptEditMode::Emacs => write!(f, "Emacs"),
+            PromptEditMode::Emacs | PromptEditMode::EmacsArgument(_) => write!(f, "Emacs"),
             PromptEditMode::Vi(_) => write!(f, "Vi_Normal\nVi_Insert"),
             PromptEditMode::Helix(_) => write!(f, "Helix_Normal\nHelix_Insert"),
             PromptEditMode::Custom(s) => write!(f, "Custom_{s}"),
@@ -96,18 +138,38 @@ impl Display for PromptEditMode {
 /// displayed before the `LineBuffer` is drawn.
 pub trait Prompt: Send {
     /// Provide content of the left full prompt
-    fn render_prompt_left(&self) -> Cow<str>;
+    fn render_prompt_left(&self) -> Cow<'_, str>;
     /// Provide content of the right full prompt
-    fn render_prompt_right(&self) -> Cow<str>;
+    fn render_prompt_right(&self) -> Cow<'_, str>;
     /// Render the prompt indicator (Last part of the prompt that changes based on the editor mode)
-    fn render_prompt_indicator(&self, prompt_mode: PromptEditMode) -> Cow<str>;
+    fn render_prompt_indicator(&self, prompt_mode: PromptEditMode) -> Cow<'_, str>;
     /// Indicator to show before explicit new lines
-    fn render_prompt_multiline_indicator(&self) -> Cow<str>;
+    fn render_prompt_multiline_indicator(&self) -> Cow<'_, str>;
     /// Render the prompt indicator for `Ctrl-R` history search
     fn render_prompt_history_search_indicator(
         &self,
         history_search: PromptHistorySearch,
-    ) -> Cow<str>;
+    ) -> Cow<'_, str>;
+    /// Render the prompt indicator for the search inside the buffer
+    fn render_prompt_buffer_search_indicator(
+        &self,
+        buffer_search: PromptBufferSearch,
+    ) -> Cow<'_, str> {
+        let prefix = match buffer_search.status {
+            PromptHistorySearchStatus::Passing => "",
+            PromptHistorySearchStatus::Failing => "failing ",
+        };
+        let direction = if buffer_search.backward {
+            "backward-search"
+        } else {
+            "search"
+        };
+        Cow::Owned(format!("({prefix}{direction}: {}) ", buffer_search.term))
+    }
+    /// Render the prompt indicator for the Vi ex command line opened with `:`
+    fn render_prompt_ex_command_indicator(&self) -> Cow<'_, str> {
+        Cow::Borrowed(":")
+    }
     /// Get the default prompt color
     fn get_prompt_color(&self) -> Color {
         DEFAULT_PROMPT_COLOR
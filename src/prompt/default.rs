@@ -11,6 +11,8 @@ use {
 pub static DEFAULT_PROMPT_INDICATOR: &str = "〉";
 pub static DEFAULT_VI_INSERT_PROMPT_INDICATOR: &str = ": ";
 pub static DEFAULT_VI_NORMAL_PROMPT_INDICATOR: &str = "〉";
+pub static DEFAULT_VI_VISUAL_LINE_PROMPT_INDICATOR: &str = "V〉";
+pub static DEFAULT_VI_VISUAL_BLOCK_PROMPT_INDICATOR: &str = "^V〉";
 pub static DEFAULT_HELIX_INSERT_PROMPT_INDICATOR: &str = ": ";
 pub static DEFAULT_HELIX_NORMAL_PROMPT_INDICATOR: &str = "〉";
 pub static DEFAULT_MULTILINE_INDICATOR: &str = "::: ";
@@ -39,10 +41,10 @@ pub enum DefaultPromptSegment {
     Empty,
 }
 
-/// Given a prompt segment, render it to a Cow<str> that we can use to
+/// Given a prompt segment, render it to a Cow<'_, str> that we can use to
 /// easily implement [`Prompt`]'s `render_prompt_left` and `render_prompt_right`
 /// functions.
-fn render_prompt_segment(prompt: &DefaultPromptSegment) -> Cow<str> {
+fn render_prompt_segment(prompt: &DefaultPromptSegment) -> Cow<'_, str> {
     match &prompt {
         DefaultPromptSegment::Basic(s) => Cow::Borrowed(s),
         DefaultPromptSegment::WorkingDirectory => {
@@ -55,37 +57,50 @@ fn render_prompt_segment(prompt: &DefaultPromptSegment) -> Cow<str> {
 }
 
 impl Prompt for DefaultPrompt {
-    fn render_prompt_left(&self) -> Cow<str> {
+    fn render_prompt_left(&self) -> Cow<'_, str> {
         render_prompt_segment(&self.left_prompt)
     }
 
-    fn render_prompt_right(&self) -> Cow<str> {
+    fn render_prompt_right(&self) -> Cow<'_, str> {
         render_prompt_segment(&self.right_prompt)
     }
 
-    fn render_prompt_indicator(&self, edit_mode: PromptEditMode) -> Cow<str> {
+    fn render_prompt_indicator(&self, edit_mode: PromptEditMode) -> Cow<'_, str> {
         match edit_mode {
             PromptEditMode::Emacs => DEFAULT_PROMPT_INDICATOR.into(),
+            PromptEditMode::EmacsArgument(argument) => {
+                format!("(arg: {argument}) {DEFAULT_PROMPT_INDICATOR}").into()
+            }
             PromptEditMode::Vi(vi_mode) => match vi_mode {
                 PromptViMode::Normal => DEFAULT_VI_NORMAL_PROMPT_INDICATOR.into(),
                 PromptViMode::Insert => DEFAULT_VI_INSERT_PROMPT_INDICATOR.into(),
+                PromptViMode::VisualLine => DEFAULT_VI_VISUAL_LINE_PROMPT_INDICATOR.into(),
+                PromptViMode::VisualBlock => DEFAULT_VI_VISUAL_BLOCK_PROMPT_INDICATOR.into(),
+                PromptViMode::Recording(register, vi_mode) => format!(
+                    "recording @{register} {}",
+                    self.render_prompt_indicator(PromptEditMode::Vi(*vi_mode))
+                )
+                .into(),
             },
             PromptEditMode::Helix(helix_mode) => match helix_mode {
                 PromptHelixMode::Normal => DEFAULT_HELIX_NORMAL_PROMPT_INDICATOR.into(),
                 PromptHelixMode::Insert => DEFAULT_HELIX_INSERT_PROMPT_INDICATOR.into(),
+                PromptHelixMode::Pending(keys) => {
+                    format!("{keys}{DEFAULT_HELIX_NORMAL_PROMPT_INDICATOR}").into()
+                }
             },
             PromptEditMode::Custom(str) => format!("({str})").into(),
         }
     }
 
-    fn render_prompt_multiline_indicator(&self) -> Cow<str> {
+    fn render_prompt_multiline_indicator(&self) -> Cow<'_, str> {
         Cow::Borrowed(DEFAULT_MULTILINE_INDICATOR)
     }
 
     fn render_prompt_history_search_indicator(
         &self,
         history_search: PromptHistorySearch,
-    ) -> Cow<str> {
+    ) -> Cow<'_, str> {
         let prefix = match history_search.status {
             PromptHistorySearchStatus::Passing => "",
             PromptHistorySearchStatus::Failing => "failing ",
@@ -2,8 +2,8 @@ mod base;
 mod default;
 
 pub use base::{
-    Prompt, PromptEditMode, PromptHelixMode, PromptHistorySearch, PromptHistorySearchStatus,
-    PromptViMode,
+    Prompt, PromptBufferSearch, PromptEditMode, PromptHelixMode, PromptHistorySearch,
+    PromptHistorySearchStatus, PromptViMode,
 };
 
 pub use default::{DefaultPrompt, DefaultPromptSegment};
@@ -1,7 +1,8 @@
 use crate::{
-    default_emacs_keybindings, default_helix_insert_keybindings, default_helix_normal_keybindings,
-    default_vi_insert_keybindings, default_vi_normal_keybindings, EditCommand, Keybindings,
-    PromptEditMode, ReedlineEvent,
+    default_emacs_keybindings, default_helix_insert_keybindings, default_helix_match_keybindings,
+    default_helix_normal_keybindings, default_vi_insert_keybindings, default_vi_normal_keybindings,
+    edit_mode::modifier_names, EditCommand, KeySequence, Keybindings, PromptEditMode,
+    ReedlineEvent,
 };
 use crossterm::event::KeyCode;
 use std::fmt::{Display, Formatter};
@@ -115,12 +116,17 @@ pub fn get_reedline_edit_commands() -> Vec<String> {
 /// Get the default keybindings and return a `Vec<(String, String, String, String)>`
 /// where String 1 is `mode`, String 2 is `key_modifiers`, String 3 is `key_code`, and
 /// Sting 4 is `event`
+///
+/// The `key_code` is the whole binding in key notation like `C-x` or `C-x C-e`, which parses
+/// back into a [`KeySequence`]. The `key_modifiers` are the modifiers of a single key in the
+/// same notation, like `C-M`, and empty for a sequence of keys.
 pub fn get_reedline_default_keybindings() -> Vec<(String, String, String, String)> {
     let options = vec![
         ("emacs", default_emacs_keybindings()),
         ("vi_normal", default_vi_normal_keybindings()),
         ("vi_insert", default_vi_insert_keybindings()),
         ("helix_normal", default_helix_normal_keybindings()),
+        ("helix_match", default_helix_match_keybindings()),
         ("helix_insert", default_helix_insert_keybindings()),
     ];
 
@@ -136,12 +142,18 @@ fn get_keybinding_strings(
 ) -> Vec<(String, String, String, String)> {
     let mut data: Vec<(String, String, String, String)> = keybindings
         .get_keybindings()
-        .iter()
-        .map(|(combination, event)| {
+        .into_iter()
+        .map(|(keys, event)| {
+            let modifier = match keys.as_slice() {
+                [combination] => modifier_names(combination.modifier)
+                    .collect::<Vec<_>>()
+                    .join("-"),
+                _ => String::new(),
+            };
             (
                 mode.to_string(),
-                format!("{:?}", combination.modifier),
-                format!("{:?}", combination.key_code),
+                modifier,
+                KeySequence(keys).to_string(),
                 format!("{event:?}"),
             )
         })
@@ -151,3 +163,31 @@ fn get_keybinding_strings(
 
     data
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn default_keybindings_are_listed_in_key_notation() {
+        let keybindings = get_reedline_default_keybindings();
+        assert!(keybindings.iter().any(|(mode, modifier, key_code, _)| (
+            mode.as_str(),
+            modifier.as_str(),
+            key_code.as_str()
+        ) == ("emacs", "C", "C-a")));
+
+        for (mode, modifier, key_code, _) in keybindings {
+            let keys: KeySequence = key_code
+                .parse()
+                .unwrap_or_else(|error| panic!("{mode} {key_code}: {error}"));
+            if let [combination] = keys.0.as_slice() {
+                let expected: Vec<_> = modifier_names(combination.modifier).collect();
+                assert_eq!(modifier, expected.join("-"));
+            } else {
+                assert_eq!(modifier, "");
+            }
+        }
+    }
+}
@@ -10,10 +10,43 @@ pub fn remove_last_grapheme(string: &str) -> &str {
     }
 }
 
+/// Split a command line into its words like a shell does
+///
+/// Words are separated by whitespace outside of single or double quotes, a backslash escapes
+/// the following character. The words keep their quotes and escapes.
+pub fn split_shell_words(line: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = None;
+    let mut quote = None;
+    let mut chars = line.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => {
+                if let Some(start) = start.take() {
+                    words.push(&line[start..idx]);
+                }
+                continue;
+            }
+            (None, '\\') | (Some('"'), '\\') => {
+                chars.next();
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            _ => {}
+        }
+        start.get_or_insert(idx);
+    }
+    if let Some(start) = start {
+        words.push(&line[start..]);
+    }
+    words
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
     #[test]
     fn remove_last_char_works_with_empty_string() {
@@ -35,4 +68,16 @@ mod test {
 
         assert_eq!(remove_last_grapheme(string), "this is a 😞");
     }
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("  ls -la  ", vec!["ls", "-la"])]
+    #[case("git commit -m 'fix the bug'", vec!["git", "commit", "-m", "'fix the bug'"])]
+    #[case(r#"echo "a \" b" c"#, vec!["echo", r#""a \" b""#, "c"])]
+    #[case(r"cp my\ file dir", vec!["cp", r"my\ file", "dir"])]
+    #[case(r#"echo 'a\' "b'c""#, vec!["echo", r"'a\'", r#""b'c""#])]
+    #[case("echo 'unterminated quote", vec!["echo", "'unterminated quote"])]
+    fn test_split_shell_words(#[case] line: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(split_shell_words(line), expected);
+    }
 }